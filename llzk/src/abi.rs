@@ -0,0 +1,304 @@
+//! Description of the public interface of the main struct of a circuit.
+//!
+//! The [`CircuitAbi`] of a module lists the arguments of the `@compute` function of the main struct
+//! and its public members, with their shapes. It serializes to a JSON document with the following
+//! schema, where the keys always appear in this order:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "main": "@Main",
+//!   "inputs": [{ "name": "a", "public": true, "type": <shape> }, ...],
+//!   "outputs": [{ "name": "c", "type": <shape> }, ...]
+//! }
+//! ```
+//!
+//! A shape is one of:
+//!
+//! - `{ "kind": "felt" }`, with a `"field"` key if the felt type names its field;
+//! - `{ "kind": "array", "dims": [2, 3], "element": <shape> }`, in row-major order;
+//! - `{ "kind": "pod", "records": [{ "name": "x", "type": <shape> }, ...] }`.
+//!
+//! The inputs that don't have a source-level name are named `arg<index>`.
+
+use melior::ir::{Module, Type, attribute::IntegerAttribute, operation::OperationLike as _};
+
+use crate::{
+    dialect::{
+        array::ArrayType,
+        felt::FeltType,
+        function::FuncDefOpLike as _,
+        module::ModuleExt as _,
+        pod::r#type::PodType,
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef},
+    },
+    error::Error,
+    json::Json,
+};
+
+/// Version of the JSON schema of [`CircuitAbi::to_json`].
+pub const ABI_VERSION: u64 = 1;
+
+/// Shape of the values of an input or an output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    /// A field element.
+    Felt {
+        /// Name of the field, if the felt type names one.
+        field: Option<String>,
+    },
+    /// An array with concrete dimensions, in row-major order.
+    Array {
+        /// Size of each dimension.
+        dims: Vec<usize>,
+        /// Shape of the elements.
+        element: Box<Shape>,
+    },
+    /// A pod with named records.
+    Pod {
+        /// Name and shape of the records, in the order of the type.
+        records: Vec<(String, Shape)>,
+    },
+}
+
+impl Shape {
+    /// Returns the shape of the type, or `None` if the type is not a felt, an array with concrete
+    /// dimensions or a pod of those.
+    pub fn of_type(ty: Type) -> Option<Self> {
+        if let Ok(felt) = FeltType::try_from(ty) {
+            Some(Self::Felt {
+                field: felt.field_name(),
+            })
+        } else if let Ok(array) = ArrayType::try_from(ty) {
+            let dims = array
+                .dims()
+                .into_iter()
+                .map(|dim| {
+                    IntegerAttribute::try_from(dim)
+                        .ok()
+                        .and_then(|dim| usize::try_from(dim.value()).ok())
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Self::Array {
+                dims,
+                element: Box::new(Self::of_type(array.element_type())?),
+            })
+        } else if let Ok(pod) = PodType::try_from(ty) {
+            let records = pod
+                .records()
+                .into_iter()
+                .map(|record| {
+                    let name = record.name().as_string_ref().as_str().ok()?.to_owned();
+                    Some((name, Self::of_type(record.r#type())?))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(Self::Pod { records })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of felts in a value of this shape.
+    pub fn felt_count(&self) -> usize {
+        match self {
+            Self::Felt { .. } => 1,
+            Self::Array { dims, element } => dims.iter().product::<usize>() * element.felt_count(),
+            Self::Pod { records } => records.iter().map(|(_, shape)| shape.felt_count()).sum(),
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Felt { field } => Json::object(
+                [("kind", Json::from("felt"))]
+                    .into_iter()
+                    .chain(field.as_deref().map(|field| ("field", Json::from(field)))),
+            ),
+            Self::Array { dims, element } => Json::object([
+                ("kind", Json::from("array")),
+                ("dims", Json::array(dims.iter().copied())),
+                ("element", element.to_json()),
+            ]),
+            Self::Pod { records } => Json::object([
+                ("kind", Json::from("pod")),
+                (
+                    "records",
+                    Json::array(records.iter().map(|(name, shape)| {
+                        Json::object([
+                            ("name", Json::from(name.as_str())),
+                            ("type", shape.to_json()),
+                        ])
+                    })),
+                ),
+            ]),
+        }
+    }
+}
+
+/// An argument of the `@compute` function of the main struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiInput {
+    /// Source-level name of the argument.
+    pub name: String,
+    /// Whether the argument is a public input.
+    pub public: bool,
+    /// Shape of the argument.
+    pub shape: Shape,
+}
+
+/// A public member of the main struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiOutput {
+    /// Name of the member.
+    pub name: String,
+    /// Shape of the member.
+    pub shape: Shape,
+}
+
+/// Interface of the main struct of a circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitAbi {
+    /// Fully qualified name of the main struct.
+    pub main: String,
+    /// Arguments of the `@compute` function, in order.
+    pub inputs: Vec<AbiInput>,
+    /// Public members, in the order of their definitions.
+    pub outputs: Vec<AbiOutput>,
+}
+
+impl CircuitAbi {
+    /// Describes the interface of the main struct of the module, given by its `llzk.main`
+    /// attribute.
+    pub fn from_module(module: &Module) -> Result<Self, Error> {
+        let lookup = module.main_struct_def()?.ok_or(Error::GeneralError(
+            "the module does not have a main struct",
+        ))?;
+        let op = lookup
+            .operation()
+            .ok_or(Error::GeneralError("the main struct could not be found"))?;
+        Self::from_struct(StructDefOpRef::try_from(op)?)
+    }
+
+    /// Describes the interface of the struct.
+    ///
+    /// Fails if the struct doesn't have a `@compute` function, or if an argument of `@compute` or
+    /// a public member has a type that is not supported by [`Shape::of_type`].
+    pub fn from_struct(def: StructDefOpRef) -> Result<Self, Error> {
+        let compute = def.compute_func().ok_or_else(|| {
+            Error::SymbolNotFound(format!("{}::@compute", def.fully_qualified_name()))
+        })?;
+        let ty = compute.function_type()?;
+        let inputs = (0..ty.input_count())
+            .map(|index| {
+                let name = compute
+                    .arg_name(index)?
+                    .unwrap_or_else(|| format!("arg{index}"));
+                let arg_ty = ty.input(index)?;
+                let shape = Shape::of_type(arg_ty)
+                    .ok_or_else(|| unsupported(&name, &compute.location().to_string(), arg_ty))?;
+                let public =
+                    compute.arg_is_pub(index.try_into().expect("argument index too large"));
+                Ok(AbiInput {
+                    name,
+                    public,
+                    shape,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let outputs = def
+            .member_defs()
+            .into_iter()
+            .filter(|member| member.has_public_attr())
+            .map(|member| {
+                let name = member.member_name().to_owned();
+                let member_ty = member.member_type();
+                let shape = Shape::of_type(member_ty)
+                    .ok_or_else(|| unsupported(&name, &member.location().to_string(), member_ty))?;
+                Ok(AbiOutput { name, shape })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self {
+            main: def.fully_qualified_name().to_string(),
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Serializes the description to JSON, following the schema in the [module](self)
+    /// documentation.
+    pub fn to_json(&self) -> String {
+        let inputs = self.inputs.iter().map(|input| {
+            Json::object([
+                ("name", Json::from(input.name.as_str())),
+                ("public", Json::from(input.public)),
+                ("type", input.shape.to_json()),
+            ])
+        });
+        let outputs = self.outputs.iter().map(|output| {
+            Json::object([
+                ("name", Json::from(output.name.as_str())),
+                ("type", output.shape.to_json()),
+            ])
+        });
+        Json::object([
+            ("version", Json::from(ABI_VERSION)),
+            ("main", Json::from(self.main.as_str())),
+            ("inputs", Json::array(inputs)),
+            ("outputs", Json::array(outputs)),
+        ])
+        .to_string()
+    }
+}
+
+fn unsupported(name: &str, location: &str, ty: Type) -> Error {
+    Error::UnsupportedAbiType {
+        name: name.to_owned(),
+        location: location.to_owned(),
+        ty: ty.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+
+    #[test]
+    fn describe_main_struct() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("abi/test_files/shapes.mlir")).unwrap();
+        let lookup = module.main_struct_def().unwrap().unwrap();
+        let def = StructDefOpRef::try_from(lookup.operation().unwrap()).unwrap();
+        def.compute_func().unwrap().set_arg_name(0, "a").unwrap();
+
+        let abi = CircuitAbi::from_module(&module).unwrap();
+        similar_asserts::assert_eq!(abi.to_json(), include_str!("abi/test_files/shapes.json"));
+        assert_eq!(abi.inputs[1].shape.felt_count(), 6);
+        assert_eq!(abi.outputs[1].shape.felt_count(), 2);
+    }
+
+    #[test]
+    fn unsupported_types_fail() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    function.def @compute(%n: index) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %n: index) {
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let err = CircuitAbi::from_module(&module).unwrap_err();
+        assert!(
+            matches!(&err, Error::UnsupportedAbiType { name, ty, .. } if name == "arg0" && ty == "index"),
+            "unexpected error: {err}"
+        );
+    }
+}
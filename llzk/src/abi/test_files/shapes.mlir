@@ -0,0 +1,18 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    struct.member @hidden : !felt.type
+    struct.member @pair : !array.type<2 x !felt.type> {llzk.pub}
+    function.def @compute(%a: !felt.type {llzk.pub}, %b: !array.type<2,3 x !felt.type>) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@c] = %a : <@Main>, !felt.type
+      struct.writem %self[@hidden] = %a : <@Main>, !felt.type
+      %pair = array.new %a, %a : <2 x !felt.type>
+      struct.writem %self[@pair] = %pair : <@Main>, !array.type<2 x !felt.type>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type {llzk.pub}, %b: !array.type<2,3 x !felt.type>) {
+      function.return
+    }
+  }
+}
@@ -0,0 +1,5 @@
+//! Analyses of LLZK IR.
+
+pub mod determinism;
+pub mod stats;
+pub mod underconstrained;
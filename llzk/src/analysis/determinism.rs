@@ -0,0 +1,398 @@
+//! Product programs for checking that the constraints of a struct determine its witness.
+//!
+//! A circuit is deterministic if any two witnesses that satisfy its constraints for the same
+//! public inputs, and agree on its public outputs, also agree on its private signals. Otherwise a
+//! prover can pick a different value for a private signal than the one `@compute` assigns to it.
+//!
+//! [`build_product_program`] fills the `@product` function of a struct with two copies of it:
+//!
+//! ```text
+//! function.def @product(%x: !felt.type {llzk.pub}, %y: !felt.type) -> !struct.type<@Main> {
+//!   %0 = function.call @Main::@compute(%x, %y)
+//!   function.call @Main::@constrain(%0, %x, %y)
+//!   %1 = llzk.nondet : !struct.type<@Main>
+//!   %2 = llzk.nondet : !felt.type
+//!   function.call @Main::@constrain(%1, %x, %2)
+//!   %3 = struct.readm %0[@out]
+//!   %4 = struct.readm %1[@out]
+//!   constrain.eq %3, %4
+//!   function.return %0
+//! }
+//! ```
+//!
+//! The first copy is the witness of `@compute` and the second one, the result of the
+//! `llzk.nondet` of the struct type, is an arbitrary witness that satisfies the constraints for the
+//! same public inputs and has the same public members. The private inputs of the second copy are
+//! arbitrary as well. The function returns the first copy.
+//!
+//! The [`DeterminismObligation`] it returns lists the private felts that must coincide in both
+//! copies for the struct to be deterministic: the private felt members of the struct and all the
+//! felt members of its private subcomponents. Private array and pod members are not supported and
+//! must be lowered to scalars beforehand.
+//!
+//! The obligation can be discharged with `SmtTranslator::translate_determinism`, which encodes the
+//! product program and the negation of the obligation, or checked on concrete witnesses with
+//! `DeterminismObligation::check`. Both require the `bigint` feature.
+
+#[cfg(feature = "bigint")]
+use crate::interpreter::StructInstance;
+use crate::{
+    builder::OpBuilder,
+    dialect::{
+        self,
+        array::ArrayType,
+        felt::FeltType,
+        function::FuncDefOpLike as _,
+        llzk::PublicAttribute,
+        pod::r#type::PodType,
+        r#struct::{
+            MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef, StructType, helpers,
+        },
+    },
+    error::Error,
+    operation::detach_and_erase_op,
+    symbol_ref::SymbolRefAttribute,
+    value_ext::replace_all_uses,
+};
+use melior::ir::{
+    BlockLike as _, RegionLike as _, Type, Value, ValueLike as _,
+    attribute::FlatSymbolRefAttribute, operation::OperationLike as _,
+};
+
+/// The members that must coincide in both copies of a product program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterminismObligation {
+    /// Fully qualified name of the struct.
+    pub struct_name: String,
+    /// Paths of the private felts, in the order of the definitions of the members. A path lists
+    /// the names of the members that lead to the felt, like `["sub", "x"]` for member `@x` of
+    /// subcomponent `@sub`.
+    ///
+    /// The felt members of the struct that don't have the public attribute are included, and so
+    /// are all the felt members of the subcomponents that don't have it, at any depth.
+    pub members: Vec<Vec<String>>,
+}
+
+#[cfg(feature = "bigint")]
+impl DeterminismObligation {
+    /// Returns the members of the obligation whose values differ between the two witnesses.
+    ///
+    /// The witnesses are usually the instance computed by the interpreter and another instance
+    /// that satisfies the constraints for the same public inputs, for example one built from a
+    /// model of the solver. Members that are not set in either instance are reported as well.
+    pub fn check(&self, witness: &StructInstance, other: &StructInstance) -> Vec<Vec<String>> {
+        self.members
+            .iter()
+            .filter(|path| {
+                let value = member_at(witness, path);
+                value.is_none() || value != member_at(other, path)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Returns the value of the member at the end of the path, if it and the subcomponents leading to
+/// it are set.
+#[cfg(feature = "bigint")]
+fn member_at(instance: &StructInstance, path: &[String]) -> Option<crate::interpreter::Value> {
+    let (member, subcomponents) = path.split_last()?;
+    let mut instance = instance.clone();
+    for name in subcomponents {
+        instance = instance.member(name)?.as_struct()?.clone();
+    }
+    instance.member(member)
+}
+
+/// Creates the `@product` function of the struct as the product program of its `@compute` and
+/// `@constrain` functions, and returns the obligation that the program must satisfy.
+///
+/// The function has the inputs of `@compute`. Inputs with the public attribute are shared by both
+/// copies. Fails if the struct already has a `@product` function or is missing one of the other
+/// two, or if it has private array or pod members, directly or in its private subcomponents.
+pub fn build_product_program(def: StructDefOpRef) -> Result<DeterminismObligation, Error> {
+    let struct_name = def.fully_qualified_name().to_string();
+    let compute = def
+        .compute_func()
+        .ok_or_else(|| Error::SymbolNotFound(format!("{struct_name}::@compute")))?;
+    if def.constrain_func().is_none() {
+        return Err(Error::SymbolNotFound(format!("{struct_name}::@constrain")));
+    }
+    if def.product_func().is_some() {
+        return Err(Error::GeneralError(
+            "struct already has a @product function",
+        ));
+    }
+
+    let mut members = vec![];
+    for member in def.member_defs() {
+        if !member.has_public_attr() {
+            let mut path = vec![member.member_name().to_owned()];
+            private_felts(def, member.member_type(), &mut path, &mut members)?;
+        }
+    }
+
+    let context = def.context();
+    let context = unsafe { context.to_ref() };
+    let loc = compute.location();
+    let ty = def.r#type();
+    let count = compute.arg_count()?;
+    let public = (0..count)
+        .map(|index| compute.arg_is_pub(u32::try_from(index).expect("argument index fits in u32")))
+        .collect::<Vec<_>>();
+    let inputs = (0..count)
+        .map(|index| Ok((compute.argument(index)?.r#type(), loc)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let arg_attrs = public
+        .iter()
+        .map(|public| {
+            if *public {
+                vec![PublicAttribute::new_named_attr(context)]
+            } else {
+                vec![]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let builder = OpBuilder::at_block_end(context, def.body());
+    let product = helpers::product_fn(&builder, loc, ty, &inputs, Some(arg_attrs.as_slice()))?;
+    for index in 0..count {
+        if let Some(name) = compute.arg_name(index)? {
+            product.set_arg_name(index, &name)?;
+        }
+    }
+    let block = product.body()?.first_block().ok_or(Error::EmptyBlock)?;
+    let new_struct = block.first_operation().ok_or(Error::EmptyBlock)?;
+    let builder = OpBuilder::at_block_end(context, block);
+    let args = (0..count)
+        .map(|index| block.argument(index).map(Value::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    let callee = |name: &str| {
+        let fqn = def.fully_qualified_name();
+        let mut nested = fqn.nested();
+        nested.push(FlatSymbolRefAttribute::new(context, name));
+        SymbolRefAttribute::new(context, fqn.root(), &nested)
+    };
+    let no_results: &[Type] = &[];
+
+    // The witness of `@compute`.
+    let witness = dialect::function::call(&builder, loc, callee("compute"), &args, &[ty])?;
+    let witness: Value = witness.result(0)?.into();
+    let mut constrain_args = vec![witness];
+    constrain_args.extend(&args);
+    dialect::function::call(
+        &builder,
+        loc,
+        callee("constrain"),
+        &constrain_args,
+        no_results,
+    )?;
+
+    // An arbitrary witness for the same public inputs.
+    let other: Value = dialect::llzk::nondet(&builder, loc, ty.into())
+        .result(0)?
+        .into();
+    let mut constrain_args = vec![other];
+    for (arg, public) in args.iter().zip(&public) {
+        constrain_args.push(if *public {
+            *arg
+        } else {
+            dialect::llzk::nondet(&builder, loc, arg.r#type())
+                .result(0)?
+                .into()
+        });
+    }
+    dialect::function::call(
+        &builder,
+        loc,
+        callee("constrain"),
+        &constrain_args,
+        no_results,
+    )?;
+
+    for member in def.member_defs() {
+        let (name, member_ty) = (member.member_name(), member.member_type());
+        if member.has_public_attr() {
+            let lhs = dialect::r#struct::readm(&builder, loc, member_ty, witness, name)?;
+            let rhs = dialect::r#struct::readm(&builder, loc, member_ty, other, name)?;
+            dialect::constrain::eq(&builder, loc, lhs.result(0)?.into(), rhs.result(0)?.into());
+        }
+    }
+
+    // The function returns the witness of `@compute` instead of the instance `product_fn` creates.
+    replace_all_uses(new_struct.result(0)?, witness);
+    detach_and_erase_op(new_struct);
+    Ok(DeterminismObligation {
+        struct_name,
+        members,
+    })
+}
+
+/// Appends the paths of the felts of a private member of the given type to `paths`, starting
+/// with `path`. Subcomponents are followed, and their members are private to the struct even if
+/// they are public in the subcomponent.
+fn private_felts<'c>(
+    scope: StructDefOpRef<'c, '_>,
+    ty: Type<'c>,
+    path: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) -> Result<(), Error> {
+    if FeltType::try_from(ty).is_ok() {
+        paths.push(path.clone());
+    } else if let Ok(ty) = StructType::try_from(ty) {
+        let lookup = ty.lookup_definition(&scope)?;
+        let op = lookup
+            .operation()
+            .ok_or_else(|| Error::SymbolNotFound(ty.name().to_string()))?;
+        let sub = StructDefOpRef::try_from(op)?;
+        for member in sub.member_defs() {
+            path.push(member.member_name().to_owned());
+            private_felts(scope, member.member_type(), path, paths)?;
+            path.pop();
+        }
+    } else if ArrayType::try_from(ty).is_ok() || PodType::try_from(ty).is_ok() {
+        return Err(Error::GeneralError(
+            "the determinism obligation does not support private array and pod members",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::LlzkContext,
+        dialect::{function::CallOpLike as _, module::ModuleExt as _},
+        operation::{BlockIterExt as _, isa},
+    };
+    use melior::ir::{Module, OperationRef};
+
+    #[test]
+    fn build_product() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/determinism.mlir")).unwrap();
+        let def = module.structs().next().unwrap();
+        let obligation = build_product_program(def).unwrap();
+        assert_eq!(
+            obligation,
+            DeterminismObligation {
+                struct_name: "@Main".to_owned(),
+                members: vec![vec!["sq".to_owned()]],
+            }
+        );
+
+        let product = def.product_func().unwrap();
+        assert!(product.has_allow_constraint_attr());
+        assert!(product.has_allow_witness_attr());
+        assert!(product.arg_is_pub(0));
+        assert!(!product.arg_is_pub(1));
+        let block = product.body().unwrap().first_block().unwrap();
+        let ops = block.ops().collect::<Vec<OperationRef>>();
+        let names = ops
+            .iter()
+            .map(|op| op.name().as_string_ref().as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "function.call",
+                "function.call",
+                "llzk.nondet",
+                "llzk.nondet",
+                "function.call",
+                "struct.readm",
+                "struct.readm",
+                "constrain.eq",
+                "function.return",
+            ]
+        );
+        let call = crate::dialect::function::CallOpRef::try_from(ops[0]).unwrap();
+        assert!(call.callee_is_struct_compute());
+        // The second copy shares the public input and has its own private input.
+        let result = |index: usize| Value::from(ops[index].result(0).unwrap());
+        let operands = ops[4].operands().collect::<Vec<_>>();
+        assert_eq!(
+            operands,
+            [result(2), block.argument(0).unwrap().into(), result(3)]
+        );
+        assert!(isa(&ops[8], "function.return"));
+        assert_eq!(ops[8].operand(0).unwrap(), result(0));
+
+        let err = build_product_program(def).unwrap_err();
+        assert!(
+            matches!(err, Error::GeneralError(_)),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn private_subcomponents() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/subcomponent.mlir")).unwrap();
+        let def = module
+            .structs()
+            .find(|def| def.is_main_component())
+            .unwrap();
+        // The public member of the subcomponent is private to the struct.
+        let obligation = build_product_program(def).unwrap();
+        assert_eq!(obligation.members, [["square".to_owned(), "sq".to_owned()]]);
+
+        // Arrays are not supported, even without a @product function.
+        let module = Module::parse(&ctx, include_str!("../witness/test_files/sub.mlir")).unwrap();
+        let def = module
+            .structs()
+            .find(|def| def.is_main_component())
+            .unwrap();
+        let err = build_product_program(def).unwrap_err();
+        assert!(
+            matches!(err, Error::GeneralError(_)),
+            "unexpected error: {err}"
+        );
+        assert!(def.product_func().is_none());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn check_witnesses() {
+        use crate::interpreter;
+
+        let path = |path: &[&str]| {
+            path.iter()
+                .map(|name| (*name).to_owned())
+                .collect::<Vec<_>>()
+        };
+        let obligation = DeterminismObligation {
+            struct_name: "@Main".to_owned(),
+            members: vec![path(&["sq"]), path(&["sub", "tmp"])],
+        };
+        let instance = |sq: u32, tmp: Option<u32>| {
+            let sub = StructInstance::new("@Sub", ["tmp".to_owned()]);
+            if let Some(tmp) = tmp {
+                sub.set_member("tmp", interpreter::Value::felt(tmp));
+            }
+            let instance = StructInstance::new("@Main", ["out", "sq", "sub"].map(str::to_owned));
+            instance.set_member("out", interpreter::Value::felt(1u32));
+            instance.set_member("sq", interpreter::Value::felt(sq));
+            instance.set_member("sub", sub.into());
+            instance
+        };
+        assert!(
+            obligation
+                .check(&instance(4, Some(0)), &instance(4, Some(0)))
+                .is_empty()
+        );
+        assert_eq!(
+            obligation.check(&instance(4, Some(0)), &instance(9, Some(0))),
+            [path(&["sq"])]
+        );
+        assert_eq!(
+            obligation.check(&instance(4, Some(0)), &instance(4, Some(1))),
+            [path(&["sub", "tmp"])]
+        );
+        assert_eq!(
+            obligation.check(&instance(4, None), &instance(4, None)),
+            [path(&["sub", "tmp"])]
+        );
+    }
+}
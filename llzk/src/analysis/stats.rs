@@ -0,0 +1,458 @@
+//! Statistics about the size of the circuits of a module.
+//!
+//! [`ModuleStats::from_module`] computes a [`CircuitStats`] for every struct of the module, with
+//! the counts of the struct itself and the totals of the circuit it instantiates, which add the
+//! totals of its subcomponents. A subcomponent is a member whose type is a struct or an array of
+//! structs; an array member counts once per element.
+//!
+//! The degree of a constraint is the degree of the polynomial `lhs - rhs` in the values that are
+//! not computed by felt arithmetic, such as arguments, member reads and `llzk.nondet` results.
+//! Constants have degree zero. A `felt.pow` only counts as felt arithmetic if its exponent is a
+//! `felt.const`, in which case it multiplies the degree of its base by the exponent.
+//!
+//! The statistics serialize to a JSON document with the following schema, where the keys always
+//! appear in this order and structs are sorted by name:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "structs": [
+//!     {
+//!       "name": "@Main",
+//!       "local": <counts>,
+//!       "total": <counts>,
+//!       "subcomponents": [{ "member": "sub", "struct": "@Sub", "instances": 2 }, ...]
+//!     },
+//!     ...
+//!   ]
+//! }
+//! ```
+//!
+//! where `<counts>` is an object with the keys `constrain_eq`, `constrain_in`, `max_degree`,
+//! `signals`, `columns`, `public` and `nondet`. The `instances` of an array with symbolic
+//! dimensions is `null`, and the totals don't include it.
+
+use crate::{
+    dialect::{
+        array::ArrayType,
+        constrain::{is_eq_op, is_in_op},
+        felt::{
+            FeltConstAttribute, is_add_op, is_const_op, is_mul_op, is_neg_op, is_pow_op, is_sub_op,
+        },
+        llzk::is_nondet_op,
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef, StructType},
+    },
+    error::Error,
+    json::Json,
+    operation::{OperationIterExt as _, isa},
+};
+use melior::ir::{
+    Module, OperationRef, ValueLike as _,
+    attribute::IntegerAttribute,
+    operation::{OperationLike as _, OperationResult},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::AddAssign,
+    os::raw::c_void,
+};
+
+/// Version of the JSON schema of [`ModuleStats::to_json`].
+pub const STATS_VERSION: u64 = 1;
+
+/// Counts of the constraints and members of a circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    /// Number of `constrain.eq` operations.
+    pub constrain_eq: usize,
+    /// Number of `constrain.in` operations.
+    pub constrain_in: usize,
+    /// Maximum degree of the `constrain.eq` operations, zero if there are none.
+    pub max_degree: usize,
+    /// Number of members marked as signals.
+    pub signals: usize,
+    /// Number of members marked as columns.
+    pub columns: usize,
+    /// Number of public members.
+    pub public: usize,
+    /// Number of `llzk.nondet` operations.
+    pub nondet: usize,
+}
+
+impl Counts {
+    /// Returns the counts of `instances` copies of the circuit.
+    fn times(self, instances: usize) -> Self {
+        Self {
+            constrain_eq: self.constrain_eq * instances,
+            constrain_in: self.constrain_in * instances,
+            max_degree: if instances == 0 { 0 } else { self.max_degree },
+            signals: self.signals * instances,
+            columns: self.columns * instances,
+            public: self.public * instances,
+            nondet: self.nondet * instances,
+        }
+    }
+
+    fn to_json(self) -> Json {
+        Json::object([
+            ("constrain_eq", Json::from(self.constrain_eq)),
+            ("constrain_in", Json::from(self.constrain_in)),
+            ("max_degree", Json::from(self.max_degree)),
+            ("signals", Json::from(self.signals)),
+            ("columns", Json::from(self.columns)),
+            ("public", Json::from(self.public)),
+            ("nondet", Json::from(self.nondet)),
+        ])
+    }
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.constrain_eq += other.constrain_eq;
+        self.constrain_in += other.constrain_in;
+        self.max_degree = self.max_degree.max(other.max_degree);
+        self.signals += other.signals;
+        self.columns += other.columns;
+        self.public += other.public;
+        self.nondet += other.nondet;
+    }
+}
+
+/// A member whose type is a struct or an array of structs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subcomponent {
+    /// Name of the member.
+    pub member: String,
+    /// Fully qualified name of the struct the member instantiates.
+    pub struct_name: String,
+    /// Number of instances, or `None` if the member is an array with symbolic dimensions.
+    pub instances: Option<usize>,
+}
+
+/// Statistics of one struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// Fully qualified name of the struct.
+    pub name: String,
+    /// Counts of the struct itself, without its subcomponents.
+    pub local: Counts,
+    /// Counts of the struct and of all its subcomponents, at any depth.
+    pub total: Counts,
+    /// Subcomponents of the struct, in the order of their definitions.
+    pub subcomponents: Vec<Subcomponent>,
+}
+
+impl CircuitStats {
+    /// Computes the counts of the struct itself. The totals are the same as the local counts,
+    /// since subcomponents are only known in the context of a module.
+    pub fn from_struct(def: StructDefOpRef) -> Self {
+        let mut local = Counts::default();
+        let mut subcomponents = vec![];
+        for member in def.member_defs() {
+            local.signals += usize::from(member.signal());
+            local.columns += usize::from(member.column());
+            local.public += usize::from(member.has_public_attr());
+            let mut ty = member.member_type();
+            let mut instances = Some(1);
+            if let Ok(array) = ArrayType::try_from(ty) {
+                instances = array.dims().into_iter().try_fold(1, |count, dim| {
+                    let dim = IntegerAttribute::try_from(dim).ok()?.value();
+                    usize::try_from(dim).ok().map(|dim| count * dim)
+                });
+                ty = array.element_type();
+            }
+            if let Ok(ty) = StructType::try_from(ty) {
+                subcomponents.push(Subcomponent {
+                    member: member.member_name().to_owned(),
+                    struct_name: ty.name().to_string(),
+                    instances,
+                });
+            }
+        }
+
+        let mut degrees = HashMap::new();
+        for op in def.descendants::<OperationRef>() {
+            if is_eq_op(&op) {
+                local.constrain_eq += 1;
+                local.max_degree = local.max_degree.max(operand_degree(&degrees, &op));
+            } else if is_in_op(&op) {
+                local.constrain_in += 1;
+            } else if is_nondet_op(&op) {
+                local.nondet += 1;
+            }
+            let degree = result_degree(&degrees, &op);
+            for result in op.results() {
+                degrees.insert(result.to_raw().ptr, degree);
+            }
+        }
+
+        Self {
+            name: def.fully_qualified_name().to_string(),
+            local,
+            total: local,
+            subcomponents,
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        let subcomponents = self.subcomponents.iter().map(|sub| {
+            Json::object([
+                ("member", Json::from(sub.member.as_str())),
+                ("struct", Json::from(sub.struct_name.as_str())),
+                ("instances", sub.instances.map_or(Json::Null, Json::from)),
+            ])
+        });
+        Json::object([
+            ("name", Json::from(self.name.as_str())),
+            ("local", self.local.to_json()),
+            ("total", self.total.to_json()),
+            ("subcomponents", Json::array(subcomponents)),
+        ])
+    }
+}
+
+/// Statistics of all the structs of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleStats {
+    /// Statistics of each struct, sorted by name.
+    pub structs: Vec<CircuitStats>,
+}
+
+impl ModuleStats {
+    /// Computes the statistics of the structs of the module, including the ones defined in nested
+    /// modules and templates.
+    ///
+    /// Fails if a subcomponent instantiates a struct that is not defined in the module or if
+    /// structs instantiate each other recursively.
+    pub fn from_module(module: &Module) -> Result<Self, Error> {
+        let mut structs = module
+            .as_operation()
+            .descendants::<StructDefOpRef>()
+            .map(CircuitStats::from_struct)
+            .collect::<Vec<_>>();
+        structs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let index = structs
+            .iter()
+            .enumerate()
+            .map(|(i, stats)| (stats.name.clone(), i))
+            .collect::<HashMap<_, _>>();
+        let mut totals = HashMap::new();
+        for i in 0..structs.len() {
+            total(&structs, &index, i, &mut totals, &mut HashSet::new())?;
+        }
+        for (i, stats) in structs.iter_mut().enumerate() {
+            stats.total = totals[&i];
+        }
+        Ok(Self { structs })
+    }
+
+    /// Returns the statistics of the struct with the given fully qualified name.
+    pub fn get(&self, name: &str) -> Option<&CircuitStats> {
+        self.structs.iter().find(|stats| stats.name == name)
+    }
+
+    /// Serializes the statistics to JSON, following the schema in the [module](self)
+    /// documentation.
+    pub fn to_json(&self) -> String {
+        Json::object([
+            ("version", Json::from(STATS_VERSION)),
+            (
+                "structs",
+                Json::array(self.structs.iter().map(CircuitStats::to_json)),
+            ),
+        ])
+        .to_string()
+    }
+}
+
+/// Computes the totals of the struct at position `i`, memoized in `totals`. `visiting` holds the
+/// structs whose totals are being computed, to detect recursive instantiations.
+fn total(
+    structs: &[CircuitStats],
+    index: &HashMap<String, usize>,
+    i: usize,
+    totals: &mut HashMap<usize, Counts>,
+    visiting: &mut HashSet<usize>,
+) -> Result<Counts, Error> {
+    if let Some(&counts) = totals.get(&i) {
+        return Ok(counts);
+    }
+    if !visiting.insert(i) {
+        return Err(Error::GeneralError(
+            "structs instantiate each other recursively",
+        ));
+    }
+    let mut counts = structs[i].local;
+    for sub in &structs[i].subcomponents {
+        let &j = index
+            .get(&sub.struct_name)
+            .ok_or_else(|| Error::SymbolNotFound(sub.struct_name.clone()))?;
+        let sub_counts = total(structs, index, j, totals, visiting)?;
+        if let Some(instances) = sub.instances {
+            counts += sub_counts.times(instances);
+        }
+    }
+    visiting.remove(&i);
+    totals.insert(i, counts);
+    Ok(counts)
+}
+
+/// Returns the degree of the difference of the operands of a `constrain.eq`.
+fn operand_degree(degrees: &HashMap<*const c_void, usize>, op: &OperationRef) -> usize {
+    op.operands()
+        .map(|operand| degrees.get(&operand.to_raw().ptr).copied().unwrap_or(1))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the degree of the results of the operation as polynomials in the values that are not
+/// computed by felt arithmetic.
+fn result_degree(degrees: &HashMap<*const c_void, usize>, op: &OperationRef) -> usize {
+    let operands = op
+        .operands()
+        .map(|operand| degrees.get(&operand.to_raw().ptr).copied().unwrap_or(1))
+        .collect::<Vec<_>>();
+    if is_const_op(op) || isa(op, "arith.constant") {
+        0
+    } else if is_add_op(op) || is_sub_op(op) || is_neg_op(op) {
+        operands.into_iter().max().unwrap_or(0)
+    } else if is_mul_op(op) {
+        operands.into_iter().sum()
+    } else if let Some(exp) = constant_exponent(op) {
+        operands[0].saturating_mul(usize::try_from(exp).unwrap_or(usize::MAX))
+    } else if !operands.is_empty() && operands.iter().all(|&degree| degree == 0) {
+        // Any other operation of constants is a constant.
+        0
+    } else {
+        1
+    }
+}
+
+/// Returns the exponent of a `felt.pow` if it is the result of a `felt.const` that fits in a `u64`.
+fn constant_exponent(op: &OperationRef) -> Option<u64> {
+    if !is_pow_op(op) {
+        return None;
+    }
+    let exp = OperationResult::try_from(op.operand(1).ok()?).ok()?.owner();
+    if !is_const_op(&exp) {
+        return None;
+    }
+    FeltConstAttribute::try_from(exp.attribute("value").ok()?)
+        .ok()?
+        .to_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::LlzkContext, dialect::r#struct::MemberDefOpRef};
+
+    #[test]
+    fn count_module() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/stats.mlir")).unwrap();
+        for member in module.as_operation().descendants::<MemberDefOpRef>() {
+            if ["y", "out", "inv"].contains(&member.member_name()) {
+                member.set_signal(true);
+            }
+        }
+        let stats = ModuleStats::from_module(&module).unwrap();
+        let names = stats
+            .structs
+            .iter()
+            .map(|stats| stats.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["@Cube", "@Main"]);
+
+        let cube = stats.get("@Cube").unwrap();
+        assert_eq!(
+            cube.local,
+            Counts {
+                constrain_eq: 1,
+                constrain_in: 0,
+                max_degree: 3,
+                signals: 1,
+                columns: 0,
+                public: 1,
+                nondet: 0,
+            }
+        );
+        assert_eq!(cube.total, cube.local);
+
+        let main = stats.get("@Main").unwrap();
+        assert_eq!(
+            main.subcomponents,
+            [
+                Subcomponent {
+                    member: "cube".to_owned(),
+                    struct_name: "@Cube".to_owned(),
+                    instances: Some(1),
+                },
+                Subcomponent {
+                    member: "cubes".to_owned(),
+                    struct_name: "@Cube".to_owned(),
+                    instances: Some(2),
+                },
+            ]
+        );
+        assert_eq!(
+            main.local,
+            Counts {
+                constrain_eq: 2,
+                constrain_in: 0,
+                max_degree: 2,
+                signals: 2,
+                columns: 0,
+                public: 1,
+                nondet: 1,
+            }
+        );
+        assert_eq!(
+            main.total,
+            Counts {
+                constrain_eq: 5,
+                constrain_in: 0,
+                max_degree: 3,
+                signals: 5,
+                columns: 0,
+                public: 4,
+                nondet: 1,
+            }
+        );
+        similar_asserts::assert_eq!(stats.to_json(), include_str!("test_files/stats.json"));
+    }
+
+    #[test]
+    fn pow_degrees() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    function.def @compute(%x: !felt.type, %y: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type, %y: !felt.type) {
+      %five = felt.const 5
+      %square = felt.mul %x, %x : !felt.type, !felt.type
+      %pow = felt.pow %square, %five : !felt.type, !felt.type
+      constrain.eq %pow, %y : !felt.type, !felt.type
+      %opaque = felt.pow %x, %y : !felt.type, !felt.type
+      constrain.eq %opaque, %y : !felt.type, !felt.type
+      %two = felt.const 2
+      %constant = felt.pow %two, %five : !felt.type, !felt.type
+      constrain.eq %constant, %y : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let stats = ModuleStats::from_module(&module).unwrap();
+        let main = stats.get("@Main").unwrap();
+        assert_eq!(main.local.constrain_eq, 3);
+        assert_eq!(main.local.max_degree, 10);
+    }
+}
@@ -0,0 +1,45 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  function.def @double(%x: !felt.type) -> !felt.type {
+    %y = felt.add %x, %x : !felt.type, !felt.type
+    function.return %y : !felt.type
+  }
+  struct.def @Sub {
+    function.def @compute(%x: !felt.type) -> !struct.type<@Sub> {
+      %self = struct.new : <@Sub>
+      function.return %self : !struct.type<@Sub>
+    }
+    function.def @constrain(%self: !struct.type<@Sub>, %x: !felt.type) {
+      %zero = felt.const 0
+      constrain.eq %x, %zero : !felt.type, !felt.type
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @f : !felt.type {llzk.pub}
+    struct.member @g : !felt.type {llzk.pub}
+    struct.member @h : !felt.type {llzk.pub}
+    struct.member @sub : !struct.type<@Sub>
+    function.def @compute(%a: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %zero = felt.const 0
+      struct.writem %self[@f] = %a : <@Main>, !felt.type
+      struct.writem %self[@g] = %zero : <@Main>, !felt.type
+      %h = felt.div %a, %a : !felt.type, !felt.type
+      struct.writem %self[@h] = %h : <@Main>, !felt.type
+      %sub = function.call @Sub::@compute(%zero) : (!felt.type) -> !struct.type<@Sub>
+      struct.writem %self[@sub] = %sub : <@Main>, !struct.type<@Sub>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type) {
+      %f = struct.readm %self[@f] : <@Main>, !felt.type
+      %unused = function.call @double(%f) : (!felt.type) -> !felt.type
+      %g = struct.readm %self[@g] : <@Main>, !felt.type
+      %sub = struct.readm %self[@sub] : <@Main>, !struct.type<@Sub>
+      function.call @Sub::@constrain(%sub, %g) : (!struct.type<@Sub>, !felt.type) -> ()
+      %h = struct.readm %self[@h] : <@Main>, !felt.type
+      %twice = function.call @double(%h) : (!felt.type) -> !felt.type
+      constrain.eq %twice, %a : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
@@ -0,0 +1,23 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @out : !felt.type {llzk.pub}
+    struct.member @sq : !felt.type
+    function.def @compute(%x: !felt.type {llzk.pub}, %y: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %sq = felt.mul %x, %x : !felt.type, !felt.type
+      struct.writem %self[@sq] = %sq : <@Main>, !felt.type
+      %out = felt.add %sq, %y : !felt.type, !felt.type
+      struct.writem %self[@out] = %out : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type {llzk.pub}, %y: !felt.type) {
+      %sq = struct.readm %self[@sq] : <@Main>, !felt.type
+      %x2 = felt.mul %x, %x : !felt.type, !felt.type
+      constrain.eq %sq, %x2 : !felt.type, !felt.type
+      %out = struct.readm %self[@out] : <@Main>, !felt.type
+      %sum = felt.add %sq, %y : !felt.type, !felt.type
+      constrain.eq %out, %sum : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
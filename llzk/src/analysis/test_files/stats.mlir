@@ -0,0 +1,43 @@
+module attributes {llzk.lang} {
+  struct.def @Cube {
+    struct.member @y : !felt.type {llzk.pub}
+    function.def @compute(%x: !felt.type) -> !struct.type<@Cube> {
+      %self = struct.new : <@Cube>
+      %square = felt.mul %x, %x : !felt.type, !felt.type
+      %cube = felt.mul %square, %x : !felt.type, !felt.type
+      struct.writem %self[@y] = %cube : <@Cube>, !felt.type
+      function.return %self : !struct.type<@Cube>
+    }
+    function.def @constrain(%self: !struct.type<@Cube>, %x: !felt.type) {
+      %y = struct.readm %self[@y] : <@Cube>, !felt.type
+      %square = felt.mul %x, %x : !felt.type, !felt.type
+      %cube = felt.mul %square, %x : !felt.type, !felt.type
+      constrain.eq %y, %cube : !felt.type, !felt.type
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @out : !felt.type {llzk.pub}
+    struct.member @inv : !felt.type
+    struct.member @cube : !struct.type<@Cube>
+    struct.member @cubes : !array.type<2 x !struct.type<@Cube>>
+    function.def @compute(%x: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@out] = %x : <@Main>, !felt.type
+      %inv = llzk.nondet : !felt.type
+      struct.writem %self[@inv] = %inv : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type) {
+      %out = struct.readm %self[@out] : <@Main>, !felt.type
+      %inv = struct.readm %self[@inv] : <@Main>, !felt.type
+      %product = felt.mul %out, %inv : !felt.type, !felt.type
+      %one = felt.const 1
+      constrain.eq %product, %one : !felt.type, !felt.type
+      %three = felt.const 3
+      %scaled = felt.mul %three, %out : !felt.type, !felt.type
+      constrain.eq %scaled, %x : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
@@ -0,0 +1,39 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Square {
+    struct.member @sq : !felt.type {llzk.pub}
+    function.def @compute(%x: !felt.type) -> !struct.type<@Square> {
+      %self = struct.new : <@Square>
+      %sq = felt.mul %x, %x : !felt.type, !felt.type
+      struct.writem %self[@sq] = %sq : <@Square>, !felt.type
+      function.return %self : !struct.type<@Square>
+    }
+    function.def @constrain(%self: !struct.type<@Square>, %x: !felt.type) {
+      %sq = struct.readm %self[@sq] : <@Square>, !felt.type
+      %x2 = felt.mul %x, %x : !felt.type, !felt.type
+      constrain.eq %sq, %x2 : !felt.type, !felt.type
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @out : !felt.type {llzk.pub}
+    struct.member @square : !struct.type<@Square>
+    function.def @compute(%x: !felt.type {llzk.pub}) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %square = function.call @Square::@compute(%x) : (!felt.type) -> !struct.type<@Square>
+      struct.writem %self[@square] = %square : <@Main>, !struct.type<@Square>
+      %sq = struct.readm %square[@sq] : <@Square>, !felt.type
+      %out = felt.add %sq, %x : !felt.type, !felt.type
+      struct.writem %self[@out] = %out : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type {llzk.pub}) {
+      %square = struct.readm %self[@square] : <@Main>, !struct.type<@Square>
+      function.call @Square::@constrain(%square, %x) : (!struct.type<@Square>, !felt.type) -> ()
+      %sq = struct.readm %square[@sq] : <@Square>, !felt.type
+      %out = struct.readm %self[@out] : <@Main>, !felt.type
+      %sum = felt.add %sq, %x : !felt.type, !felt.type
+      constrain.eq %out, %sum : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
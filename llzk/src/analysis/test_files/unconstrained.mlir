@@ -0,0 +1,27 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    struct.member @d : !felt.type {llzk.pub}
+    struct.member @e : !felt.type {llzk.pub}
+    struct.member @tmp : !felt.type
+    function.def @compute(%a: !felt.type {llzk.pub}) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@c] = %a : <@Main>, !felt.type
+      struct.writem %self[@d] = %a : <@Main>, !felt.type
+      struct.writem %self[@e] = %a : <@Main>, !felt.type
+      struct.writem %self[@tmp] = %a : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type {llzk.pub}) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      constrain.eq %c, %a : !felt.type, !felt.type
+      %d = struct.readm %self[@d] : <@Main>, !felt.type
+      %e = struct.readm %self[@e] : <@Main>, !felt.type
+      %sum = felt.add %e, %e : !felt.type, !felt.type
+      %unused = felt.mul %d, %d : !felt.type, !felt.type
+      %twice = felt.add %sum, %sum : !felt.type, !felt.type
+      constrain.eq %twice, %a : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
@@ -0,0 +1,388 @@
+//! Detection of struct members that the constraints do not determine.
+//!
+//! A member is underconstrained if two witnesses that agree on the public inputs of `@constrain`
+//! and satisfy its constraints can disagree on the value of the member. A prover can then pick any
+//! of those values, which usually breaks the soundness of the circuit.
+//!
+//! The analysis covers the members that are signals or public, as those are the values the
+//! `@compute` function outputs. [`find_underconstrained`] performs a fast syntactic check that
+//! reports the members whose value never reaches a constraint of `@constrain`. Members that
+//! reach a constraint can be checked further with `check_underconstrained`, which asks an SMT
+//! solver whether the member is uniquely determined. It requires the `bigint` feature.
+
+#[cfg(feature = "bigint")]
+use crate::{
+    dialect::felt::FeltType,
+    targets::smt::{SatResult, SmtTranslator, Solver},
+};
+use crate::{
+    dialect::{
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _},
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef},
+    },
+    error::Error,
+    operation::isa,
+    targets::collect_structs,
+    value_ext::users_of,
+};
+use melior::ir::{
+    BlockLike as _, BlockRef, Module, OperationRef, RegionLike as _, Value, ValueLike as _,
+    attribute::{FlatSymbolRefAttribute, StringAttribute},
+    operation::OperationLike,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// How well the constraints determine a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberStatus {
+    /// The value of the member does not reach any constraint.
+    Unconstrained,
+    /// The value of the member reaches a constraint, which does not mean that the constraints
+    /// determine it.
+    Constrained,
+    /// The solver found two witnesses that agree on the public inputs but not on the member.
+    Underconstrained,
+    /// The solver proved that the public inputs uniquely determine the member.
+    Determined,
+    /// The solver could not decide whether the public inputs uniquely determine the member.
+    Unknown,
+}
+
+impl MemberStatus {
+    /// Returns true if the member is known to be underconstrained.
+    pub fn is_underconstrained(self) -> bool {
+        matches!(self, Self::Unconstrained | Self::Underconstrained)
+    }
+}
+
+/// Result of the analysis for one member of a struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberReport {
+    /// Fully qualified name of the struct that defines the member.
+    pub struct_name: String,
+    /// Name of the member.
+    pub member: String,
+    /// Whether the member is a signal.
+    pub signal: bool,
+    /// Whether the member is public.
+    pub public: bool,
+    /// How well the constraints determine the member.
+    pub status: MemberStatus,
+}
+
+impl fmt::Display for MemberReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.status {
+            MemberStatus::Unconstrained => "does not reach any constraint",
+            MemberStatus::Constrained => "is constrained",
+            MemberStatus::Underconstrained => "is not uniquely determined by the public inputs",
+            MemberStatus::Determined => "is uniquely determined by the public inputs",
+            MemberStatus::Unknown => "could not be checked",
+        };
+        write!(f, "{}::@{} {status}", self.struct_name, self.member)
+    }
+}
+
+/// Reports, for the signal and public members of every struct in the module, whether their value
+/// reaches a constraint of the `@constrain` function.
+///
+/// A value reaches a constraint if a constraint operation or a call to the `@constrain` function of
+/// a subcomponent uses it, directly or through the results of other operations, including the
+/// results of other calls. Reports are sorted by struct and member name.
+pub fn find_underconstrained(module: &Module) -> Result<Vec<MemberReport>, Error> {
+    let mut structs = HashMap::new();
+    collect_structs(module.body(), &mut structs)?;
+    let mut structs = structs.into_values().collect::<Vec<_>>();
+    structs.sort_by_key(|def| def.fully_qualified_name().to_string());
+
+    let mut reports = vec![];
+    for def in structs {
+        reports.extend(analyze_struct(def)?);
+    }
+    Ok(reports)
+}
+
+/// Like [`find_underconstrained`], but uses the solver to decide whether the members that reach a
+/// constraint are uniquely determined by the public inputs of `@constrain`.
+///
+/// Each felt member is checked with a script made by
+/// [`SmtTranslator`], which fixes the sort used for felts and the default field. The solver must
+/// support that sort. Members of other types keep the status of the syntactic check.
+#[cfg(feature = "bigint")]
+pub fn check_underconstrained(
+    module: &Module,
+    translator: &SmtTranslator,
+    solver: &Solver,
+) -> Result<Vec<MemberReport>, Error> {
+    let mut structs = HashMap::new();
+    collect_structs(module.body(), &mut structs)?;
+    let mut reports = find_underconstrained(module)?;
+    for report in &mut reports {
+        if report.status != MemberStatus::Constrained {
+            continue;
+        }
+        let def = structs[&report.struct_name];
+        let is_felt = def
+            .find_member_def(&report.member)
+            .is_some_and(|member| FeltType::try_from(member.member_type()).is_ok());
+        if !is_felt {
+            continue;
+        }
+        let script = translator.translate_uniqueness(def, &report.member)?;
+        report.status = match solver.check(&script)? {
+            SatResult::Sat => MemberStatus::Underconstrained,
+            SatResult::Unsat => MemberStatus::Determined,
+            SatResult::Unknown => MemberStatus::Unknown,
+        };
+    }
+    Ok(reports)
+}
+
+fn analyze_struct(def: StructDefOpRef) -> Result<Vec<MemberReport>, Error> {
+    let Some(func) = def.constrain_func() else {
+        return Ok(vec![]);
+    };
+    let block = func.body()?.first_block().ok_or(Error::BlockExpected(0))?;
+    let this: Value = block.argument(0)?.into();
+
+    let mut reads = vec![];
+    collect_member_reads(block, this, &mut reads);
+
+    let struct_name = def.fully_qualified_name().to_string();
+    let mut reports = vec![];
+    let mut members = def.member_defs();
+    members.sort_by_key(|member| member.member_name().to_owned());
+    for member in members {
+        let (signal, public) = (member.signal(), member.has_public_attr());
+        if !signal && !public {
+            continue;
+        }
+        let name = member.member_name();
+        let constrained = reads
+            .iter()
+            .filter(|(read, _)| read == name)
+            .any(|(_, value)| reaches_constraint(*value));
+        reports.push(MemberReport {
+            struct_name: struct_name.clone(),
+            member: name.to_owned(),
+            signal,
+            public,
+            status: if constrained {
+                MemberStatus::Constrained
+            } else {
+                MemberStatus::Unconstrained
+            },
+        });
+    }
+    Ok(reports)
+}
+
+/// Collects the results of the `struct.readm` operations that read a member of `this`, paired
+/// with the name of the member, including the ones in nested regions.
+fn collect_member_reads<'c, 'a>(
+    block: BlockRef<'c, 'a>,
+    this: Value<'c, 'a>,
+    reads: &mut Vec<(String, Value<'c, 'a>)>,
+) {
+    let mut next = block.first_operation();
+    while let Some(op) = next {
+        if isa(&op, "struct.readm") && op.operand(0).is_ok_and(|operand| operand == this) {
+            if let (Some(member), Ok(result)) = (member_name(&op), op.result(0)) {
+                reads.push((member, result.into()));
+            }
+        }
+        for region in op.regions() {
+            let mut next_block = region.first_block();
+            while let Some(block) = next_block {
+                collect_member_reads(block, this, reads);
+                next_block = block.next_in_region();
+            }
+        }
+        next = op.next_in_block();
+    }
+}
+
+fn member_name<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>) -> Option<String> {
+    let attr = op.attribute("member_name").ok()?;
+    FlatSymbolRefAttribute::try_from(attr)
+        .map(|attr| attr.value().to_owned())
+        .or_else(|_| StringAttribute::try_from(attr).map(|attr| attr.value().to_owned()))
+        .ok()
+}
+
+/// Returns true if the value flows into a constraint or a call to a `@constrain` function.
+fn reaches_constraint(value: Value) -> bool {
+    let mut seen = HashSet::new();
+    let mut pending = vec![value];
+    while let Some(value) = pending.pop() {
+        if !seen.insert(value.to_raw().ptr) {
+            continue;
+        }
+        for user in users_of(value) {
+            if is_constraint(&user) {
+                return true;
+            }
+            pending.extend(user.results().map(Value::from));
+            // Values yielded by a terminator flow into the results of the enclosing operation.
+            if user.result_count() == 0 {
+                if let Some(parent) = user.block().and_then(|block| block.parent_operation()) {
+                    pending.extend(parent.results().map(Value::from));
+                }
+            }
+        }
+    }
+    false
+}
+
+fn is_constraint(op: &OperationRef) -> bool {
+    if let Ok(call) = CallOpRef::try_from(*op) {
+        return call.callee_is_struct_constrain();
+    }
+    ["constrain.eq", "constrain.in", "bool.assert"]
+        .into_iter()
+        .any(|name| isa(op, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+
+    #[test]
+    fn syntactic_check() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/unconstrained.mlir")).unwrap();
+        let reports = find_underconstrained(&module).unwrap();
+        let statuses = reports
+            .iter()
+            .map(|report| (report.member.as_str(), report.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                ("c", MemberStatus::Constrained),
+                ("d", MemberStatus::Unconstrained),
+                ("e", MemberStatus::Constrained),
+            ]
+        );
+        assert!(reports[1].status.is_underconstrained());
+    }
+
+    #[test]
+    fn only_constrain_calls_are_constraints() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/calls.mlir")).unwrap();
+        let reports = find_underconstrained(&module).unwrap();
+        let statuses = reports
+            .iter()
+            .filter(|report| report.struct_name == "@Main")
+            .map(|report| (report.member.as_str(), report.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                ("f", MemberStatus::Unconstrained),
+                ("g", MemberStatus::Constrained),
+                ("h", MemberStatus::Constrained),
+            ]
+        );
+    }
+
+    /// A solver that ignores the script and gives the same answer every time.
+    #[cfg(all(feature = "bigint", unix))]
+    fn fixed_solver(answer: &str) -> Solver {
+        Solver::new("sh")
+            .with_arg("-c")
+            .with_arg(format!("cat > /dev/null; echo {answer}"))
+    }
+
+    #[cfg(all(feature = "bigint", unix))]
+    #[test]
+    fn solver_answers() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/unconstrained.mlir")).unwrap();
+        let translator = SmtTranslator::default();
+        let cases = [
+            ("unsat", MemberStatus::Determined),
+            ("sat", MemberStatus::Underconstrained),
+            ("unknown", MemberStatus::Unknown),
+        ];
+        for (answer, status) in cases {
+            let reports =
+                check_underconstrained(&module, &translator, &fixed_solver(answer)).unwrap();
+            let statuses = reports
+                .iter()
+                .map(|report| (report.member.as_str(), report.status))
+                .collect::<Vec<_>>();
+            // The member that does not reach a constraint is not checked by the solver.
+            assert_eq!(
+                statuses,
+                [
+                    ("c", status),
+                    ("d", MemberStatus::Unconstrained),
+                    ("e", status),
+                ]
+            );
+        }
+
+        let err =
+            check_underconstrained(&module, &translator, &Solver::new("/nonexistent")).unwrap_err();
+        assert!(matches!(err, Error::SolverFailed(_)), "{err}");
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn check_with_z3() {
+        use crate::{interpreter::Field, targets::smt::FeltSort};
+
+        if Solver::z3().check_if_installed("(check-sat)\n").is_none() {
+            return;
+        }
+        let ctx = LlzkContext::new();
+        let translator = SmtTranslator::new(FeltSort::Integer)
+            .with_default_field(Field::builtin("babybear").unwrap());
+        // `@c` and `@e` are determined by the public input `%a`.
+        let module = Module::parse(&ctx, include_str!("test_files/unconstrained.mlir")).unwrap();
+        let reports = check_underconstrained(&module, &translator, &Solver::z3()).unwrap();
+        let statuses = reports
+            .iter()
+            .map(|report| (report.member.as_str(), report.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                ("c", MemberStatus::Determined),
+                ("d", MemberStatus::Unconstrained),
+                ("e", MemberStatus::Determined),
+            ]
+        );
+
+        // `@c` is only determined up to the private input it is multiplied by.
+        let module = Module::parse(
+            &ctx,
+            include_str!("../targets/smt/test_files/division.mlir"),
+        )
+        .unwrap();
+        let reports = check_underconstrained(&module, &translator, &Solver::z3()).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].status, MemberStatus::Underconstrained);
+    }
+
+    #[test]
+    fn display() {
+        let report = MemberReport {
+            struct_name: "@Main".to_owned(),
+            member: "d".to_owned(),
+            signal: true,
+            public: false,
+            status: MemberStatus::Unconstrained,
+        };
+        assert_eq!(
+            report.to_string(),
+            "@Main::@d does not reach any constraint"
+        );
+    }
+}
@@ -0,0 +1,341 @@
+#![allow(unused_crate_dependencies)]
+//! Command-line driver that parses LLZK IR, runs a pass pipeline on it and emits the result.
+//!
+//! The input is a `.mlir` or `.llzk` file in text or bytecode format. The pipeline uses the
+//! textual syntax of `mlir-opt` and can refer to any of the passes registered by
+//! [`register_all_llzk_passes`]. Diagnostics are rendered to stderr.
+//!
+//! Run with: `cargo run --package llzk --bin llzk-opt -- --help`
+
+use llzk::{passes::register_all_llzk_passes, prelude::*};
+use melior::{
+    Context,
+    diagnostic::{Diagnostic, DiagnosticSeverity},
+    pass::PassManager,
+    utility::parse_pass_pipeline,
+};
+use std::{
+    fs,
+    io::{self, Read as _, Write},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+Usage: llzk-opt [OPTIONS] [INPUT]
+
+Parses LLZK IR in text or bytecode format, runs a pass pipeline on it and emits the result.
+
+Arguments:
+  [INPUT]  Input file. Reads from stdin if missing or `-`
+
+Options:
+  -p, --pass-pipeline <PIPELINE>  Textual pass pipeline, for example
+                                  `builtin.module(llzk-flatten)`. Pipelines without an anchor
+                                  run on the top-level module
+  -o, --output <FILE>             Output file. Writes to stdout if missing or `-`
+  -I, --include-dir <DIR>         Directory searched for the files of `include.from` ops. Can be
+                                  repeated
+      --emit <FORMAT>             Output format: mlir (default), bytecode, abi, stats, dot,
+                                  pcl, r1cs, air or rust. The stats format is the JSON report
+                                  of circuit statistics, the dot format is the struct
+                                  instantiation graph and the air format is the JSON AIR of
+                                  the main struct
+      --field <NAME>              Default field of the r1cs and air formats (default: bn128)
+      --rust-field <PATH>         Path of the `ff::PrimeField` type used by the rust format
+      --no-verify                 Skip the verification of the input and of the output
+  -h, --help                      Print this message
+";
+
+/// Output formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emit {
+    Mlir,
+    Bytecode,
+    Abi,
+    Stats,
+    Dot,
+    #[cfg(feature = "pcl-backend")]
+    Pcl,
+    #[cfg(feature = "bigint")]
+    R1cs,
+    #[cfg(feature = "bigint")]
+    Air,
+    #[cfg(feature = "bigint")]
+    Rust,
+}
+
+impl Emit {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "mlir" => Ok(Self::Mlir),
+            "bytecode" => Ok(Self::Bytecode),
+            "abi" => Ok(Self::Abi),
+            "stats" => Ok(Self::Stats),
+            "dot" => Ok(Self::Dot),
+            #[cfg(feature = "pcl-backend")]
+            "pcl" => Ok(Self::Pcl),
+            #[cfg(feature = "bigint")]
+            "r1cs" => Ok(Self::R1cs),
+            #[cfg(feature = "bigint")]
+            "air" => Ok(Self::Air),
+            #[cfg(feature = "bigint")]
+            "rust" => Ok(Self::Rust),
+            #[cfg(not(feature = "pcl-backend"))]
+            "pcl" => Err(disabled(name)),
+            #[cfg(not(feature = "bigint"))]
+            "r1cs" | "air" | "rust" => Err(disabled(name)),
+            _ => Err(format!("unknown output format '{name}'")),
+        }
+    }
+}
+
+#[cfg(not(all(feature = "pcl-backend", feature = "bigint")))]
+fn disabled(format: &str) -> String {
+    format!("the {format} format is not enabled in this build of llzk-opt")
+}
+
+/// Command line options.
+#[derive(Debug)]
+struct Options {
+    input: Option<String>,
+    output: Option<String>,
+    pipeline: Option<String>,
+    load: LoadOptions,
+    emit: Emit,
+    #[cfg(feature = "bigint")]
+    field: String,
+    #[cfg(feature = "bigint")]
+    rust_field: Option<String>,
+    verify: bool,
+}
+
+impl Options {
+    /// Parses the command line arguments. Returns `None` if the usage was requested.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options {
+            input: None,
+            output: None,
+            pipeline: None,
+            load: LoadOptions::new(),
+            emit: Emit::Mlir,
+            #[cfg(feature = "bigint")]
+            field: "bn128".to_owned(),
+            #[cfg(feature = "bigint")]
+            rust_field: None,
+            verify: true,
+        };
+        while let Some(arg) = args.next() {
+            // Accepts both `--option value` and `--option=value`.
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for '{name}'"))
+            };
+            match name {
+                "-h" | "--help" => return Ok(None),
+                "-p" | "--pass-pipeline" => options.pipeline = Some(value()?),
+                "-o" | "--output" => options.output = Some(value()?),
+                "-I" | "--include-dir" => {
+                    options.load.add_include_dir(value()?);
+                }
+                "--emit" => options.emit = Emit::parse(&value()?)?,
+                #[cfg(feature = "bigint")]
+                "--field" => options.field = value()?,
+                #[cfg(feature = "bigint")]
+                "--rust-field" => options.rust_field = Some(value()?),
+                "--no-verify" => options.verify = false,
+                _ if name.starts_with('-') && name != "-" => {
+                    return Err(format!("unknown option '{name}'"));
+                }
+                _ if options.input.is_some() => {
+                    return Err(format!("unexpected argument '{arg}'"));
+                }
+                _ => options.input = Some(arg.clone()),
+            }
+        }
+        Ok(Some(options))
+    }
+}
+
+/// Writes the diagnostic and its notes to stderr.
+fn print_diagnostic(diag: &Diagnostic, depth: usize) {
+    let severity = match diag.severity() {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Note => "note",
+        DiagnosticSeverity::Remark => "remark",
+        DiagnosticSeverity::Warning => "warning",
+    };
+    eprintln!(
+        "{:indent$}{}: {severity}: {diag}",
+        "",
+        diag.location(),
+        indent = depth * 2
+    );
+    for index in 0..diag.note_count() {
+        if let Ok(note) = diag.note(index) {
+            print_diagnostic(&note, depth + 1);
+        }
+    }
+}
+
+/// Parses the input module in text or bytecode format and resolves its includes.
+///
+/// The errors are collected by the loader, so the diagnostics are silenced while it runs.
+fn load_input<'c>(context: &'c Context, options: &Options) -> Result<Module<'c>, String> {
+    let path = options.input.as_deref().filter(|path| *path != "-");
+    let mut stdin = vec![];
+    if path.is_none() {
+        io::stdin()
+            .read_to_end(&mut stdin)
+            .map_err(|err| format!("failed to read stdin: {err}"))?;
+    }
+
+    let silence = context.attach_diagnostic_handler(|_| true);
+    let module = match path {
+        Some(path) => LlzkModule::load(context, path, &options.load),
+        None => LlzkModule::parse(context, &stdin, "<stdin>", &options.load),
+    };
+    context.detach_diagnostic_handler(silence);
+    module.map_err(|err| match err {
+        // Each diagnostic is printed on its own line.
+        LlzkError::Diagnostics(_) => format!("failed to parse the input:{err}"),
+        err => format!("failed to parse the input: {err}"),
+    })
+}
+
+fn verify(module: &Module, stage: &str) -> Result<(), String> {
+    verify_operation_with_diags(&module.as_operation())
+        .map_err(|_| format!("the {stage} failed to verify"))
+}
+
+fn emit(options: &Options, module: &Module) -> Result<Vec<u8>, String> {
+    let mut out = vec![];
+    match options.emit {
+        Emit::Mlir => writeln!(out, "{}", module.as_operation()).map_err(|err| err.to_string())?,
+        Emit::Bytecode => module
+            .write_bytecode(&mut out)
+            .map_err(|err| err.to_string())?,
+        Emit::Abi => writeln!(
+            out,
+            "{}",
+            llzk::abi::CircuitAbi::from_module(module)
+                .map_err(|err| err.to_string())?
+                .to_json()
+        )
+        .map_err(|err| err.to_string())?,
+        Emit::Stats => writeln!(
+            out,
+            "{}",
+            llzk::analysis::stats::ModuleStats::from_module(module)
+                .map_err(|err| err.to_string())?
+                .to_json()
+        )
+        .map_err(|err| err.to_string())?,
+        Emit::Dot => out.extend(llzk::targets::dot::struct_graph(module).into_bytes()),
+        #[cfg(feature = "pcl-backend")]
+        Emit::Pcl => out.extend(
+            llzk::targets::pcl::translate_module(module)
+                .map_err(|err| err.to_string())?
+                .into_bytes(),
+        ),
+        #[cfg(feature = "bigint")]
+        Emit::R1cs => {
+            let field = llzk::interpreter::Field::builtin(&options.field)
+                .ok_or_else(|| format!("unknown field '{}'", options.field))?;
+            llzk::targets::r1cs::translate_module(module, &field)
+                .map_err(|err| err.to_string())?
+                .write_to(&mut out)
+                .map_err(|err| err.to_string())?;
+        }
+        #[cfg(feature = "bigint")]
+        Emit::Air => {
+            let field = llzk::interpreter::Field::builtin(&options.field)
+                .ok_or_else(|| format!("unknown field '{}'", options.field))?;
+            writeln!(
+                out,
+                "{}",
+                llzk::targets::air::translate_module(module, &field)
+                    .map_err(|err| err.to_string())?
+                    .to_json()
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        #[cfg(feature = "bigint")]
+        Emit::Rust => {
+            let field = options
+                .rust_field
+                .as_deref()
+                .ok_or("the rust format requires --rust-field")?;
+            out.extend(
+                llzk::targets::rust::translate_module(module, field)
+                    .map_err(|err| err.to_string())?
+                    .into_bytes(),
+            );
+        }
+    }
+    Ok(out)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let context = LlzkContext::new_no_log();
+    register_all_llzk_passes();
+
+    let mut module = load_input(&context, options)?;
+    context.attach_diagnostic_handler(|diag| {
+        print_diagnostic(&diag, 0);
+        true
+    });
+    if options.verify {
+        verify(&module, "input")?;
+    }
+
+    if let Some(pipeline) = &options.pipeline {
+        let pipeline = if pipeline.starts_with("builtin.module(") {
+            pipeline.clone()
+        } else {
+            format!("builtin.module({pipeline})")
+        };
+        let pm = PassManager::new(&context);
+        pm.enable_verifier(options.verify);
+        parse_pass_pipeline(pm.as_operation_pass_manager(), &pipeline)
+            .map_err(|err| format!("invalid pass pipeline: {err}"))?;
+        pm.run(&mut module)
+            .map_err(|_| "the pass pipeline failed".to_owned())?;
+        if options.verify {
+            verify(&module, "output")?;
+        }
+    }
+
+    let out = emit(options, &module)?;
+    match options.output.as_deref() {
+        None | Some("-") => io::stdout().write_all(&out),
+        Some(path) => fs::write(path, out),
+    }
+    .map_err(|err| format!("failed to write the output: {err}"))
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("llzk-opt: error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("llzk-opt: error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
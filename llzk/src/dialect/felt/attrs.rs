@@ -1,4 +1,6 @@
-use super::FeltType;
+use super::{FeltType, printed};
+#[cfg(feature = "ff")]
+use super::{LlzkField, field};
 use llzk_sys::{
     llzkAttributeIsA_Felt_FeltConstAttr, llzkAttributeIsA_Felt_FieldSpecAttr,
     llzkFelt_FeltConstAttrGet, llzkFelt_FeltConstAttrGetFromParts,
@@ -156,10 +158,101 @@ impl<'c> FeltConstAttribute<'c> {
         Self::from_parts(ctx, bitlen.try_into().unwrap(), &parts, field)
     }
 
+    /// Creates a [`FeltConstAttribute`] from an element of a [`PrimeField`](ff::PrimeField),
+    /// using the field of the element for the felt type.
+    #[cfg(feature = "ff")]
+    pub fn from_field_element<F: LlzkField>(ctx: &'c Context, value: &F) -> Self {
+        let parts = field::le_bytes_to_parts(&value.to_le_bytes());
+        // Increase by one to ensure the value is kept unsigned.
+        Self::from_parts(ctx, F::NUM_BITS + 1, &parts, Some(F::NAME))
+    }
+
     /// Returns the felt type of the attribute.
     pub fn r#type(&self) -> FeltType<'c> {
         unsafe { FeltType::from_raw(llzkFelt_FeltConstAttrGetType(self.to_raw())) }
     }
+
+    /// Converts the constant into an element of a [`PrimeField`](ff::PrimeField).
+    ///
+    /// Constants whose felt type doesn't name a field are reduced modulo the prime of `F`.
+    /// Returns `None` if the felt type names a field other than the one of `F`.
+    #[cfg(feature = "ff")]
+    pub fn to_field_element<F: LlzkField>(&self) -> Option<F> {
+        let ctx = unsafe { self.inner.context().to_ref() };
+        let ty = self.r#type();
+        if ty != FeltType::new(ctx) && ty != FeltType::for_field::<F>(ctx) {
+            return None;
+        }
+        let value = field::from_parts::<F>(&self.to_parts());
+        Some(if self.is_negative() { -value } else { value })
+    }
+
+    /// Returns `true` if the value of the constant is negative.
+    pub fn is_negative(&self) -> bool {
+        self.printed_value().0
+    }
+
+    /// Returns the absolute value of the constant as 64-bit parts in LSB order. The value zero
+    /// has no parts.
+    pub fn to_parts(&self) -> Vec<u64> {
+        printed::decimal_to_parts(&self.printed_value().1)
+    }
+
+    /// Returns the value of the constant if it is not negative and fits in a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.is_negative() {
+            return None;
+        }
+        match self.to_parts()[..] {
+            [] => Some(0),
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bits required to represent the absolute value of the constant.
+    ///
+    /// This is not necessarily the bit length the attribute was created with, which is not
+    /// exposed by the C API.
+    pub fn bits(&self) -> u64 {
+        printed::parts_bits(&self.to_parts())
+    }
+
+    /// Returns the name of the field of the constant, if its felt type names one.
+    pub fn field_name(&self) -> Option<String> {
+        self.r#type().field_name()
+    }
+
+    /// Returns the value of the constant as a [`num_bigint::BigInt`].
+    #[cfg(feature = "bigint")]
+    pub fn to_bigint(&self) -> num_bigint::BigInt {
+        let (negative, digits) = self.printed_value();
+        let magnitude = num_bigint::BigUint::parse_bytes(digits.as_bytes(), 10)
+            .expect("printed value is a base 10 integer");
+        let sign = if negative {
+            num_bigint::Sign::Minus
+        } else {
+            num_bigint::Sign::Plus
+        };
+        num_bigint::BigInt::from_biguint(sign, magnitude)
+    }
+
+    /// Returns the value of the constant as a [`num_bigint::BigUint`], or `None` if it is
+    /// negative.
+    #[cfg(feature = "bigint")]
+    pub fn to_biguint(&self) -> Option<num_bigint::BigUint> {
+        self.to_bigint().to_biguint()
+    }
+
+    /// Reads the sign and the base 10 digits of the value from the textual form of the attribute.
+    fn printed_value(&self) -> (bool, String) {
+        let text = self.inner.to_string();
+        printed::integers(&text)
+            .first()
+            .map_or((false, "0".to_owned()), |(negative, digits)| {
+                (*negative, (*digits).to_owned())
+            })
+    }
 }
 
 impl<'c> AttributeLike<'c> for FeltConstAttribute<'c> {
@@ -275,6 +368,50 @@ impl<'c> FieldSpecAttribute<'c> {
         let parts = value.to_u64_digits();
         Self::from_parts(ctx, name, bitlen.try_into().unwrap(), &parts)
     }
+
+    /// Creates a `llzk::felt::FieldSpecAttr` for the field of the given
+    /// [`PrimeField`](ff::PrimeField) type.
+    ///
+    /// Specifications should not be created for built-in fields.
+    #[cfg(feature = "ff")]
+    pub fn for_field<F: LlzkField>(ctx: &'c Context) -> Self {
+        // Increase by one to ensure the value is kept unsigned.
+        Self::from_parts(ctx, F::NAME, F::NUM_BITS + 1, &F::modulus_parts())
+    }
+
+    /// Returns the name of the field.
+    pub fn name(&self) -> String {
+        let text = self.inner.to_string();
+        printed::first_quoted(&text)
+            .or_else(|| printed::first_parameter(&text))
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    /// Returns the prime of the field as 64-bit parts in LSB order.
+    pub fn prime_parts(&self) -> Vec<u64> {
+        let text = self.inner.to_string();
+        printed::integers(&text)
+            .last()
+            .map(|(_, digits)| printed::decimal_to_parts(digits))
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of bits required to represent the prime of the field.
+    pub fn prime_bits(&self) -> u64 {
+        printed::parts_bits(&self.prime_parts())
+    }
+
+    /// Returns the prime of the field as a [`num_bigint::BigUint`].
+    #[cfg(feature = "bigint")]
+    pub fn prime(&self) -> num_bigint::BigUint {
+        num_bigint::BigUint::new(
+            self.prime_parts()
+                .iter()
+                .flat_map(|part| [*part as u32, (part >> 32) as u32])
+                .collect(),
+        )
+    }
 }
 
 impl<'c> AttributeLike<'c> for FieldSpecAttribute<'c> {
@@ -391,6 +528,37 @@ mod tests {
         assert_ne!(f.to_raw().ptr, null());
     }
 
+    #[quickcheck]
+    fn felt_const_attr_read_u64(value: u64, field: FieldArg) -> bool {
+        let value = value % (i64::MAX as u64 + 1);
+        let ctx = LlzkContext::new();
+        let f = FeltConstAttribute::new(&ctx, value, field.as_deref());
+        f.to_u64() == Some(value)
+            && !f.is_negative()
+            && f.bits() == u64::from(u64::BITS - value.leading_zeros())
+            && f.field_name() == field.0
+    }
+
+    #[quickcheck]
+    fn felt_const_attr_read_parts(low: u64, high: u64) -> bool {
+        let ctx = LlzkContext::new();
+        let f = FeltConstAttribute::from_parts(&ctx, 129, &[low, high], None);
+        let mut expected = vec![low, high];
+        while expected.last() == Some(&0) {
+            expected.pop();
+        }
+        f.to_parts() == expected && (high == 0 || f.to_u64().is_none())
+    }
+
+    #[test]
+    fn field_spec_attr_read() {
+        let ctx = LlzkContext::new();
+        let f = FieldSpecAttribute::new(&ctx, "foo", 64, "18446744069414584321");
+        assert_eq!(f.name(), "foo");
+        assert_eq!(f.prime_parts(), vec![18446744069414584321]);
+        assert_eq!(f.prime_bits(), 64);
+    }
+
     #[cfg(feature = "bigint")]
     mod bigint {
         use crate::{context::LlzkContext, prelude::FeltConstAttribute};
@@ -413,5 +581,30 @@ mod tests {
             let f = FeltConstAttribute::from_biguint(&ctx, &value, None);
             assert_ne!(f.to_raw().ptr, null());
         }
+
+        #[rstest]
+        fn felt_const_attr_read_bigint(
+            #[values(BigUint::from(0u8), BigUint::from(1u8), BigUint::from_str("21888242871839275222246405745257275088548364400416034343698204186575808495616").unwrap())]
+            value: BigUint,
+        ) {
+            let ctx = LlzkContext::new();
+            let f = FeltConstAttribute::from_biguint(&ctx, &value, None);
+            assert_eq!(f.to_biguint(), Some(value.clone()));
+            assert_eq!(f.bits(), value.bits());
+        }
+
+        #[test]
+        fn field_spec_attr_read_prime() {
+            use crate::prelude::FieldSpecAttribute;
+
+            let ctx = LlzkContext::new();
+            let prime = BigUint::from_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            )
+            .unwrap();
+            let f = FieldSpecAttribute::from_biguint(&ctx, "foo", &prime);
+            assert_eq!(f.name(), "foo");
+            assert_eq!(f.prime(), prime);
+        }
     }
 }
@@ -0,0 +1,154 @@
+//! Integration of [`ff::PrimeField`] types with felt types and attributes.
+
+use ff::PrimeField;
+
+/// A [`PrimeField`] that corresponds to a field LLZK knows about, either because it is built in
+/// or because a [`FieldSpecAttribute`](super::FieldSpecAttribute) declares it.
+///
+/// Implementing this trait enables [`FeltType::for_field`](super::FeltType::for_field),
+/// [`FieldSpecAttribute::for_field`](super::FieldSpecAttribute::for_field),
+/// [`FeltConstAttribute::from_field_element`](super::FeltConstAttribute::from_field_element) and
+/// [`FeltConstAttribute::to_field_element`](super::FeltConstAttribute::to_field_element).
+pub trait LlzkField: PrimeField {
+    /// Name of the field, as used in `!felt.type<"name">` and in field specifications.
+    const NAME: &'static str;
+
+    /// Returns the canonical representation of the element in little-endian byte order.
+    ///
+    /// The default implementation assumes that [`PrimeField::Repr`] is little-endian, which is the
+    /// case for most implementations. Fields with a big-endian representation must override it.
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_repr().as_ref().to_vec()
+    }
+
+    /// Returns the prime modulus of the field as 64-bit parts in LSB order.
+    fn modulus_parts() -> Vec<u64> {
+        let mut parts = le_bytes_to_parts(&(-Self::ONE).to_le_bytes());
+        for part in &mut parts {
+            let (sum, carry) = part.overflowing_add(1);
+            *part = sum;
+            if !carry {
+                return parts;
+            }
+        }
+        parts.push(1);
+        parts
+    }
+}
+
+/// Packs little-endian bytes into 64-bit parts in LSB order, without trailing zero parts.
+pub(super) fn le_bytes_to_parts(bytes: &[u8]) -> Vec<u64> {
+    let mut parts: Vec<u64> = bytes
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, byte| (acc << 8) | u64::from(*byte))
+        })
+        .collect();
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    parts
+}
+
+/// Converts a number given by 64-bit parts in LSB order into a field element, reducing it modulo
+/// the prime.
+pub(super) fn from_parts<F: PrimeField>(parts: &[u64]) -> F {
+    let shift = F::from(1u64 << 32).square();
+    parts
+        .iter()
+        .rev()
+        .fold(F::ZERO, |acc, part| acc * shift + F::from(*part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::LlzkContext,
+        dialect::felt::{FeltConstAttribute, FeltType, FieldSpecAttribute},
+    };
+    use ff::Field as _;
+    use quickcheck_macros::quickcheck;
+
+    #[derive(ff::PrimeField)]
+    #[PrimeFieldModulus = "2147483647"]
+    #[PrimeFieldGenerator = "7"]
+    #[PrimeFieldReprEndianness = "little"]
+    struct Mersenne31([u64; 1]);
+
+    impl LlzkField for Mersenne31 {
+        const NAME: &'static str = "mersenne31";
+    }
+
+    #[test]
+    fn modulus_parts() {
+        assert_eq!(Mersenne31::modulus_parts(), vec![2147483647]);
+    }
+
+    #[test]
+    fn parts_from_bytes() {
+        assert_eq!(le_bytes_to_parts(&[]), Vec::<u64>::new());
+        assert_eq!(le_bytes_to_parts(&[1, 0, 0, 0, 0, 0, 0, 0, 0]), vec![1]);
+        assert_eq!(le_bytes_to_parts(&[0, 0, 0, 0, 0, 0, 0, 0, 2]), vec![0, 2]);
+    }
+
+    #[quickcheck]
+    fn parts_conversion(low: u64, high: u64) -> bool {
+        let shift = Mersenne31::from(1u64 << 32).square();
+        from_parts::<Mersenne31>(&[low, high])
+            == Mersenne31::from(high) * shift + Mersenne31::from(low)
+    }
+
+    #[test]
+    fn felt_type_for_field() {
+        let ctx = LlzkContext::new();
+        assert_eq!(
+            FeltType::for_field::<Mersenne31>(&ctx),
+            FeltType::with_field(&ctx, "mersenne31")
+        );
+    }
+
+    #[test]
+    fn field_spec_for_field() {
+        let ctx = LlzkContext::new();
+        let spec = FieldSpecAttribute::for_field::<Mersenne31>(&ctx);
+        assert!(spec.to_string().contains("2147483647"));
+    }
+
+    #[quickcheck]
+    fn felt_const_roundtrip(value: u64) -> bool {
+        let ctx = LlzkContext::new();
+        let element = Mersenne31::from(value);
+        let attr = FeltConstAttribute::from_field_element(&ctx, &element);
+        attr.r#type() == FeltType::for_field::<Mersenne31>(&ctx)
+            && attr.to_field_element::<Mersenne31>() == Some(element)
+    }
+
+    #[test]
+    fn felt_const_of_max_element() {
+        let ctx = LlzkContext::new();
+        let element = -Mersenne31::ONE;
+        let attr = FeltConstAttribute::from_field_element(&ctx, &element);
+        assert_eq!(attr.to_field_element::<Mersenne31>(), Some(element));
+    }
+
+    #[test]
+    fn felt_const_without_field_is_reduced() {
+        let ctx = LlzkContext::new();
+        let attr = FeltConstAttribute::new(&ctx, 2147483649, None);
+        assert_eq!(
+            attr.to_field_element::<Mersenne31>(),
+            Some(Mersenne31::from(2u64))
+        );
+    }
+
+    #[test]
+    fn felt_const_of_other_field() {
+        let ctx = LlzkContext::new();
+        let attr = FeltConstAttribute::new(&ctx, 1, Some("babybear"));
+        assert_eq!(attr.to_field_element::<Mersenne31>(), None);
+    }
+}
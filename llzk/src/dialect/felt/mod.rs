@@ -1,10 +1,15 @@
 //! `felt` dialect.
 
 mod attrs;
+#[cfg(feature = "ff")]
+mod field;
 mod ops;
+mod printed;
 mod r#type;
 
 pub use attrs::{FeltConstAttribute, FieldSpecAttribute};
+#[cfg(feature = "ff")]
+pub use field::LlzkField;
 use llzk_sys::mlirGetDialectHandle__llzk__felt__;
 use melior::dialect::DialectHandle;
 pub use ops::{
@@ -26,5 +31,7 @@ pub fn handle() -> DialectHandle {
 /// Exports the common types of the felt dialect.
 pub mod prelude {
     pub use super::attrs::{FeltConstAttribute, FieldSpecAttribute};
+    #[cfg(feature = "ff")]
+    pub use super::field::LlzkField;
     pub use super::r#type::{FeltType, is_felt_type};
 }
@@ -0,0 +1,130 @@
+//! Helpers for reading the parameters of felt attributes and types from their textual form.
+//!
+//! The C API does not expose these parameters, so the accessors print the attribute or type and
+//! read the parameters back.
+
+/// Returns the unquoted integers in the text, in order. Each integer is paired with a flag that
+/// is true if it is immediately preceded by a minus sign.
+pub(super) fn integers(text: &str) -> Vec<(bool, &str)> {
+    let is_ident = |c: u8| c.is_ascii_alphanumeric() || c == b'_';
+    let bytes = text.as_bytes();
+    let mut integers = vec![];
+    let mut in_quote = false;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c == b'"' {
+            in_quote = !in_quote;
+            pos += 1;
+        } else if in_quote || !is_ident(c) {
+            pos += 1;
+        } else {
+            let start = pos;
+            while pos < bytes.len() && is_ident(bytes[pos]) {
+                pos += 1;
+            }
+            let token = &text[start..pos];
+            if token.bytes().all(|c| c.is_ascii_digit()) {
+                integers.push((start > 0 && bytes[start - 1] == b'-', token));
+            }
+        }
+    }
+    integers
+}
+
+/// Returns the contents of the first quoted string in the text.
+pub(super) fn first_quoted(text: &str) -> Option<&str> {
+    let (_, rest) = text.split_once('"')?;
+    rest.split_once('"').map(|(quoted, _)| quoted)
+}
+
+/// Returns the identifier that follows the first `<` in the text.
+pub(super) fn first_parameter(text: &str) -> Option<&str> {
+    let (_, rest) = text.split_once('<')?;
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+/// Converts a base 10 representation into 64-bit parts in LSB order, without trailing zero parts.
+pub(super) fn decimal_to_parts(digits: &str) -> Vec<u64> {
+    let mut parts: Vec<u64> = vec![];
+    // 10^19 is the largest power of 10 that fits in a u64.
+    for chunk in digits.as_bytes().chunks(19) {
+        let mut carry = chunk
+            .iter()
+            .fold(0u64, |acc, digit| acc * 10 + u64::from(digit - b'0'));
+        let scale = u128::from(10u64.pow(chunk.len() as u32));
+        for part in &mut parts {
+            let value = u128::from(*part) * scale + u128::from(carry);
+            *part = value as u64;
+            carry = (value >> 64) as u64;
+        }
+        if carry != 0 {
+            parts.push(carry);
+        }
+    }
+    parts
+}
+
+/// Returns the number of bits required to represent the number given by the parts in LSB order.
+pub(super) fn parts_bits(parts: &[u64]) -> u64 {
+    parts
+        .iter()
+        .rposition(|part| *part != 0)
+        .map_or(0, |index| {
+            64 * index as u64 + u64::from(u64::BITS - parts[index].leading_zeros())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn integers_skip_quoted_strings_and_identifiers() {
+        assert_eq!(integers(r#"!felt.type<"bn128">"#), vec![]);
+        assert_eq!(
+            integers(r#"#felt<const -42> : !felt.type<"m31">"#),
+            vec![(true, "42")]
+        );
+        assert_eq!(
+            integers("#felt.field<goldilocks, 18446744069414584321>"),
+            vec![(false, "18446744069414584321")]
+        );
+    }
+
+    #[test]
+    fn parameters() {
+        assert_eq!(first_quoted(r#"field<"foo", 7>"#), Some("foo"));
+        assert_eq!(first_quoted("field<foo, 7>"), None);
+        assert_eq!(first_parameter("field<foo, 7>"), Some("foo"));
+        assert_eq!(first_parameter("field<>"), None);
+    }
+
+    #[quickcheck]
+    fn decimal_to_parts_of_u64(value: u64) -> bool {
+        let expected = if value == 0 { vec![] } else { vec![value] };
+        decimal_to_parts(&value.to_string()) == expected
+    }
+
+    #[quickcheck]
+    fn decimal_to_parts_of_u128(value: u128) -> bool {
+        let mut expected = vec![value as u64, (value >> 64) as u64];
+        while expected.last() == Some(&0) {
+            expected.pop();
+        }
+        decimal_to_parts(&value.to_string()) == expected
+    }
+
+    #[test]
+    fn bits_of_parts() {
+        assert_eq!(parts_bits(&[]), 0);
+        assert_eq!(parts_bits(&[1]), 1);
+        assert_eq!(parts_bits(&[0, 1]), 65);
+        assert_eq!(parts_bits(&[u64::MAX, 0]), 64);
+    }
+}
@@ -35,6 +35,20 @@ impl<'c> FeltType<'c> {
         let ident = Identifier::new(ctx, name);
         unsafe { Self::from_raw(llzkFelt_FeltTypeGet(ctx.to_raw(), ident.to_raw())) }
     }
+
+    /// Returns the name of the field of the type, or `None` if it doesn't name one.
+    pub fn field_name(&self) -> Option<String> {
+        let text = self.r#type.to_string();
+        super::printed::first_quoted(&text)
+            .or_else(|| super::printed::first_parameter(&text))
+            .map(str::to_owned)
+    }
+
+    /// Creates a new felt type for the field of the given [`PrimeField`](ff::PrimeField) type.
+    #[cfg(feature = "ff")]
+    pub fn for_field<F: super::LlzkField>(ctx: &'c Context) -> Self {
+        Self::with_field(ctx, F::NAME)
+    }
 }
 
 impl<'c> TypeLike<'c> for FeltType<'c> {
@@ -0,0 +1,13 @@
+//! `include` dialect.
+
+mod ops;
+
+pub use ops::{IncludeOp, IncludeOpLike, IncludeOpRef, IncludeOpRefMut, from, is_include_op};
+
+use llzk_sys::mlirGetDialectHandle__llzk__include__;
+use melior::dialect::DialectHandle;
+
+/// Returns a handle to the `include` dialect.
+pub fn handle() -> DialectHandle {
+    unsafe { DialectHandle::from_raw(mlirGetDialectHandle__llzk__include__()) }
+}
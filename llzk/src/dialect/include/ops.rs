@@ -0,0 +1,81 @@
+//! `include` dialect ops.
+
+use llzk_sys::{llzkInclude_IncludeOpBuildInferredContext, llzkOperationIsA_Include_IncludeOp};
+use melior::{
+    StringRef,
+    ir::{Location, OperationRef, attribute::StringAttribute, operation::OperationLike},
+};
+
+use crate::{
+    builder::OpBuilderLike,
+    error::Error,
+    macros::{isa_fn, llzk_op_type},
+};
+
+//===----------------------------------------------------------------------===//
+// IncludeOpLike
+//===----------------------------------------------------------------------===//
+
+/// Defines the public API of the 'include.from' op.
+pub trait IncludeOpLike<'c: 'a, 'a>: OperationLike<'c, 'a> {
+    /// Returns the name of the symbol that the included module is imported as.
+    ///
+    /// # Panics
+    ///
+    /// If the 'include.from' op doesn't have a string attribute named `sym_name`.
+    fn sym_name(&self) -> &'c str {
+        self.attribute("sym_name")
+            .and_then(StringAttribute::try_from)
+            .expect("malformed 'include.from' op")
+            .value()
+    }
+
+    /// Returns the path of the included file.
+    ///
+    /// # Panics
+    ///
+    /// If the 'include.from' op doesn't have a string attribute named `path`.
+    fn path(&self) -> &'c str {
+        self.attribute("path")
+            .and_then(StringAttribute::try_from)
+            .expect("malformed 'include.from' op")
+            .value()
+    }
+}
+
+//===----------------------------------------------------------------------===//
+// IncludeOp
+//===----------------------------------------------------------------------===//
+
+llzk_op_type!(
+    IncludeOp,
+    llzkOperationIsA_Include_IncludeOp,
+    "include.from"
+);
+
+impl<'a, 'c: 'a> IncludeOpLike<'c, 'a> for IncludeOp<'c> {}
+impl<'a, 'c: 'a> IncludeOpLike<'c, 'a> for IncludeOpRef<'c, 'a> {}
+impl<'a, 'c: 'a> IncludeOpLike<'c, 'a> for IncludeOpRefMut<'c, 'a> {}
+
+/// Creates an `include.from` op that imports the module in the file at `path` as the symbol
+/// `name`.
+pub fn from<'c, 'a>(
+    builder: &impl OpBuilderLike<'c>,
+    location: Location<'c>,
+    name: &str,
+    path: &str,
+) -> Result<IncludeOpRef<'c, 'a>, Error> {
+    let name = StringRef::new(name);
+    let path = StringRef::new(path);
+    unsafe {
+        OperationRef::from_raw(llzkInclude_IncludeOpBuildInferredContext(
+            builder.to_raw(),
+            location.to_raw(),
+            name.to_raw(),
+            path.to_raw(),
+        ))
+    }
+    .try_into()
+}
+
+isa_fn!(include, include, "from", llzkOperationIsA_Include_IncludeOp);
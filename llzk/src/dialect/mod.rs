@@ -9,10 +9,13 @@ pub mod constrain;
 pub mod felt;
 pub mod function;
 pub mod global;
+pub mod include;
 pub mod llzk;
 pub mod pod;
 pub mod poly;
 pub mod ram;
+pub mod scf_ext;
+pub mod string;
 pub mod r#struct;
 pub mod verif;
 
@@ -26,7 +29,10 @@ pub fn empty_region<'c>(_: &impl OpBuilderLike<'c>) -> Result<(), Error> {
 
 /// Functions for working with `builtin.module` in LLZK.
 pub mod module {
+    mod load;
+
     use std::{
+        collections::HashSet,
         ffi::CStr,
         io::{self, Write},
         os::raw::c_void,
@@ -36,8 +42,8 @@ pub mod module {
     use melior::{
         Context,
         ir::{
-            Location, Module,
-            attribute::{Attribute, StringAttribute, TypeAttribute},
+            Location, Module, OperationRef,
+            attribute::{Attribute, AttributeLike as _, StringAttribute, TypeAttribute},
             operation::{OperationLike, OperationMutLike as _, OperationRefMut},
         },
     };
@@ -45,9 +51,14 @@ pub mod module {
 
     use crate::{
         attributes::array::ArrayAttribute,
-        prelude::{FieldSpecAttribute, StructType},
+        error::{DiagnosticError, Error},
+        operation::{Descendants, isa},
+        prelude::{FieldSpecAttribute, StructDefOpRef, StructType},
+        symbol_lookup::SymbolLookupResult,
     };
 
+    pub use load::{LlzkModule, LoadOptions};
+
     /// Creates a new `builtin.module` operation preconfigured to meet LLZK's specifications.
     #[deprecated(note = "Use LlzkModuleBuilder::create() instead")]
     pub fn llzk_module<'c>(location: Location<'c>, lang: Option<&str>) -> Module<'c> {
@@ -209,7 +220,63 @@ pub mod module {
             wrap.1
         }
 
-        /// Sets the main struct attribute.
+        /// Returns true if the module has the `llzk.lang` attribute, which identifies the root module
+        /// of an LLZK program.
+        fn is_llzk_module(&self) -> bool {
+            module_operation(self).has_attribute(LANG_ATTR_NAME.as_ref())
+        }
+
+        /// Returns the name of the source language of the module.
+        ///
+        /// Returns `None` if the `llzk.lang` attribute is missing or does not name a language.
+        fn language(&self) -> Option<&'c str> {
+            module_operation(self)
+                .attribute(LANG_ATTR_NAME.as_ref())
+                .ok()
+                .and_then(|attr| StringAttribute::try_from(attr).ok())
+                .map(|attr| attr.value())
+        }
+
+        /// Sets the `llzk.lang` attribute, replacing the existing one. If `lang` is `None` the
+        /// module is an LLZK module without a source language.
+        fn set_language(&mut self, lang: Option<&str>) {
+            let mut op = module_operation_mut(self);
+            let ctx = unsafe { op.context().to_ref() };
+            let attr = lang.map_or_else(
+                || Attribute::unit(ctx),
+                |lang| StringAttribute::new(ctx, lang).into(),
+            );
+            op.set_attribute(LANG_ATTR_NAME.as_ref(), attr);
+        }
+
+        /// Returns the type of the main struct, if the module has a valid `llzk.main` attribute.
+        fn main_struct(&self) -> Option<StructType<'c>> {
+            module_operation(self)
+                .attribute(MAIN_ATTR_NAME.as_ref())
+                .ok()
+                .and_then(|attr| TypeAttribute::try_from(attr).ok())
+                .and_then(|attr| StructType::try_from(attr.value()).ok())
+        }
+
+        /// Looks up the definition of the main struct.
+        ///
+        /// Returns `None` if the module does not have a main struct and [`Error::SymbolNotFound`]
+        /// if the main struct is not defined.
+        fn main_struct_def(&self) -> Result<Option<SymbolLookupResult<'c>>, Error> {
+            self.main_struct()
+                .map(|main| main.lookup_definition(&module_operation(self)))
+                .transpose()
+        }
+
+        /// Returns the struct definitions of the module, including the ones in nested modules.
+        fn structs<'a>(&'a self) -> Descendants<'c, 'a, StructDefOpRef<'c, 'a>>
+        where
+            'c: 'a,
+        {
+            Descendants::nested_in(module_operation(self), |op| isa(op, "builtin.module"))
+        }
+
+        /// Sets the main struct attribute, replacing the existing one.
         fn add_main(&mut self, main: StructType<'c>) {
             let mut op = unsafe {
                 let op = mlirModuleGetOperation(self.to_raw());
@@ -221,6 +288,51 @@ pub mod module {
             );
         }
 
+        /// Removes the main struct attribute. Returns false if the module did not have one.
+        fn remove_main(&mut self) -> bool {
+            module_operation_mut(self)
+                .remove_attribute(MAIN_ATTR_NAME.as_ref())
+                .is_ok()
+        }
+
+        /// Returns the field specs of the `llzk.fields` attribute, which can be a single spec or an
+        /// array of specs. Elements that are not field specs are skipped.
+        fn field_specs(&self) -> Vec<FieldSpecAttribute<'c>> {
+            let Ok(attr) = module_operation(self).attribute(fields_attr_name()) else {
+                return vec![];
+            };
+            match ArrayAttribute::try_from(attr) {
+                Ok(array) => array
+                    .into_iter()
+                    .filter_map(|attr| FieldSpecAttribute::try_from(attr).ok())
+                    .collect(),
+                Err(_) => FieldSpecAttribute::try_from(attr).into_iter().collect(),
+            }
+        }
+
+        /// Replaces the `llzk.fields` attribute with an array of the given specs. The attribute is
+        /// removed if there are no specs.
+        fn set_field_specs(&mut self, specs: &[FieldSpecAttribute<'c>]) {
+            if specs.is_empty() {
+                self.remove_field_specs();
+                return;
+            }
+            let mut op = module_operation_mut(self);
+            let elts = specs.iter().map(|spec| (*spec).into()).collect::<Vec<_>>();
+            let context = op.context();
+            op.set_attribute(
+                fields_attr_name(),
+                ArrayAttribute::new(unsafe { context.to_ref() }, &elts).into(),
+            );
+        }
+
+        /// Removes the `llzk.fields` attribute. Returns false if the module did not have one.
+        fn remove_field_specs(&mut self) -> bool {
+            module_operation_mut(self)
+                .remove_attribute(fields_attr_name())
+                .is_ok()
+        }
+
         /// Adds the spec attribute to the module, creating the `llzk.fields` attribute if
         /// necessary.
         ///
@@ -232,9 +344,7 @@ pub mod module {
                 let op = mlirModuleGetOperation(self.to_raw());
                 OperationRefMut::from_raw(op)
             };
-            let attr_name = unsafe { CStr::from_ptr(LLZK_FIELD_ATTR_NAME) }
-                .to_str()
-                .unwrap();
+            let attr_name = fields_attr_name();
             let elts = if op.has_attribute(attr_name) {
                 let array = ArrayAttribute::try_from(op.attribute(attr_name).unwrap()).unwrap();
                 array
@@ -257,13 +367,93 @@ pub mod module {
             self.to_raw()
         }
     }
-}
 
-/// Extensions for the 'scf' dialect.
-pub mod scf_ext {
-    crate::macros::isa_fn!(scf, if);
-    crate::macros::isa_fn!(scf, yield);
-    crate::macros::isa_fn!(scf, condition);
-    crate::macros::isa_fn!(scf, for);
-    crate::macros::isa_fn!(scf, while);
+    fn fields_attr_name() -> &'static str {
+        unsafe { CStr::from_ptr(LLZK_FIELD_ATTR_NAME) }
+            .to_str()
+            .unwrap()
+    }
+
+    fn module_operation<'c, 'a, M: ModuleExt<'c> + ?Sized>(module: &'a M) -> OperationRef<'c, 'a> {
+        unsafe { OperationRef::from_raw(mlirModuleGetOperation(module.to_raw())) }
+    }
+
+    fn module_operation_mut<'c, 'a, M: ModuleExt<'c> + ?Sized>(
+        module: &'a mut M,
+    ) -> OperationRefMut<'c, 'a> {
+        unsafe { OperationRefMut::from_raw(mlirModuleGetOperation(module.to_raw())) }
+    }
+
+    /// Checks that the module satisfies LLZK's requirements on the root module:
+    ///
+    /// - It has an `llzk.lang` attribute that is either a unit or a string attribute.
+    /// - If it has an `llzk.main` attribute, it is a struct type whose definition can be found.
+    /// - If it has an `llzk.fields` attribute, it is a field spec or an array of field specs, with
+    ///   distinct names and primes greater than 1.
+    ///
+    /// Returns [`Error::Diagnostics`] with one error per violation, located at the module.
+    pub fn validate_llzk_module(module: &Module) -> Result<(), Error> {
+        let op = module.as_operation();
+        let location = op.location().to_string();
+        let mut errors = vec![];
+        let mut error = |msg: String| errors.push(DiagnosticError::error(location.as_str(), msg));
+
+        let lang = *LANG_ATTR_NAME;
+        match op.attribute(lang) {
+            Err(_) => error(format!("missing the '{lang}' attribute")),
+            Ok(attr) if !attr.is_unit() && !attr.is_string() => error(format!(
+                "expected '{lang}' to be a unit or string attribute, found {attr}"
+            )),
+            Ok(_) => {}
+        }
+
+        let main = *MAIN_ATTR_NAME;
+        if let Ok(attr) = op.attribute(main) {
+            match TypeAttribute::try_from(attr)
+                .ok()
+                .and_then(|attr| StructType::try_from(attr.value()).ok())
+            {
+                None => error(format!(
+                    "expected '{main}' to be a struct type attribute, found {attr}"
+                )),
+                Some(ty) => {
+                    if ty.lookup_definition(&op).is_err() {
+                        error(format!("could not find the main struct {ty}"));
+                    }
+                }
+            }
+        }
+
+        let fields = fields_attr_name();
+        if let Ok(attr) = op.attribute(fields) {
+            let elements = match ArrayAttribute::try_from(attr) {
+                Ok(array) => array.into_iter().collect(),
+                Err(_) => vec![attr],
+            };
+            let mut names = HashSet::new();
+            for element in elements {
+                let Ok(spec) = FieldSpecAttribute::try_from(element) else {
+                    error(format!(
+                        "expected '{fields}' to contain field specs, found {element}"
+                    ));
+                    continue;
+                };
+                let name = spec.name();
+                if spec.prime_bits() < 2 {
+                    error(format!(
+                        "the prime of the field '{name}' must be greater than 1"
+                    ));
+                }
+                if !names.insert(name.clone()) {
+                    error(format!("the field '{name}' is specified more than once"));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into())
+        }
+    }
 }
@@ -0,0 +1,385 @@
+//! Loading of LLZK modules from files.
+
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+use melior::{
+    Context, StringRef,
+    diagnostic::DiagnosticSeverity,
+    ir::{
+        BlockLike as _, Module, Operation, OperationRef,
+        attribute::StringAttribute,
+        operation::{OperationLike as _, OperationMutLike as _, OperationRefMut},
+    },
+};
+use mlir_sys::{MlirStringRef, mlirModuleGetOperation, mlirOperationCreateParse};
+
+use crate::{
+    dialect::include::{IncludeOpLike as _, IncludeOpRef},
+    error::{DiagnosticError, Error},
+    operation::{OperationIterExt as _, detach_and_erase_op},
+};
+
+/// Options for loading modules with [`LlzkModule`].
+///
+/// By default there are no include directories and the included modules are inlined.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    include_dirs: Vec<PathBuf>,
+    inline_includes: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            include_dirs: vec![],
+            inline_includes: true,
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directory to search for included files. Directories are searched in the order they
+    /// were added.
+    pub fn add_include_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    /// Returns the directories searched for included files.
+    pub fn include_dirs(&self) -> &[PathBuf] {
+        &self.include_dirs
+    }
+
+    /// Sets whether the `include.from` ops are replaced by the modules they include. If not, their
+    /// paths are replaced by the resolved paths.
+    pub fn inline_includes(&mut self, inline: bool) -> &mut Self {
+        self.inline_includes = inline;
+        self
+    }
+}
+
+/// Loading of LLZK modules from files in text or bytecode format.
+///
+/// The format is detected from the content, so `.mlir` and `.llzk` files can be either. The path
+/// of an `include.from` op is resolved relative to the directory of the file that contains it
+/// first, then relative to the include directories of the [`LoadOptions`]. Absolute paths are used
+/// as they are.
+#[derive(Debug)]
+pub struct LlzkModule;
+
+impl LlzkModule {
+    /// Loads the module in the file.
+    ///
+    /// Returns [`Error::Diagnostics`] with the errors, which are located in the files that caused
+    /// them, if a file could not be read or parsed, or an include could not be resolved.
+    pub fn load<'c>(
+        context: &'c Context,
+        path: impl AsRef<Path>,
+        options: &LoadOptions,
+    ) -> Result<Module<'c>, Error> {
+        let errors = RefCell::new(vec![]);
+        let mut loader = Loader::new(context, options, &errors);
+        collect_errors(context, &errors, || loader.load(path.as_ref()))
+    }
+
+    /// Parses a module in text or bytecode format from memory.
+    ///
+    /// `source_name` is used in the locations of the parsed operations. The includes are resolved
+    /// relative to the include directories only.
+    pub fn parse<'c>(
+        context: &'c Context,
+        source: &[u8],
+        source_name: &str,
+        options: &LoadOptions,
+    ) -> Result<Module<'c>, Error> {
+        let errors = RefCell::new(vec![]);
+        let mut loader = Loader::new(context, options, &errors);
+        collect_errors(context, &errors, || loader.parse(source, source_name, None))
+    }
+}
+
+/// Returns the location of a file, in the same format as the locations of the parsed operations.
+fn file_location(path: &Path) -> String {
+    format!("loc({:?})", path.display().to_string())
+}
+
+/// Runs the callback while collecting the errors emitted by MLIR into `errors`.
+fn collect_errors<'c>(
+    context: &'c Context,
+    errors: &RefCell<Vec<DiagnosticError>>,
+    load: impl FnOnce() -> Option<Module<'c>>,
+) -> Result<Module<'c>, Error> {
+    let id = context.attach_diagnostic_handler(|diag| {
+        if matches!(diag.severity(), DiagnosticSeverity::Error) {
+            errors.borrow_mut().push(diag.into());
+        }
+        // Return false to propagate the diagnostic to other handlers.
+        false
+    });
+    let module = load();
+    context.detach_diagnostic_handler(id);
+
+    let errors = errors.take();
+    match module {
+        Some(module) if errors.is_empty() => Ok(module),
+        _ if errors.is_empty() => Err(Error::GeneralError("failed to load the module")),
+        _ => Err(errors.into()),
+    }
+}
+
+struct Loader<'c, 'o> {
+    context: &'c Context,
+    options: &'o LoadOptions,
+    errors: &'o RefCell<Vec<DiagnosticError>>,
+    /// Files being loaded, to detect include cycles.
+    stack: Vec<PathBuf>,
+}
+
+impl<'c, 'o> Loader<'c, 'o> {
+    fn new(
+        context: &'c Context,
+        options: &'o LoadOptions,
+        errors: &'o RefCell<Vec<DiagnosticError>>,
+    ) -> Self {
+        Self {
+            context,
+            options,
+            errors,
+            stack: vec![],
+        }
+    }
+
+    fn error(&self, location: String, msg: impl Into<String>) {
+        self.errors
+            .borrow_mut()
+            .push(DiagnosticError::error(location, msg));
+    }
+
+    fn load(&mut self, path: &Path) -> Option<Module<'c>> {
+        let source = match std::fs::read(path) {
+            Ok(source) => source,
+            Err(err) => {
+                self.error(
+                    file_location(path),
+                    format!("failed to read the file: {err}"),
+                );
+                return None;
+            }
+        };
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        if self.stack.contains(&canonical) {
+            self.error(file_location(path), "the file includes itself");
+            return None;
+        }
+        self.stack.push(canonical);
+        let module = self.parse(&source, &path.display().to_string(), path.parent());
+        self.stack.pop();
+        module
+    }
+
+    /// Parses the source and resolves its includes relative to `dir` and the include directories.
+    fn parse(
+        &mut self,
+        source: &[u8],
+        source_name: &str,
+        dir: Option<&Path>,
+    ) -> Option<Module<'c>> {
+        // MLIR detects bytecode by its magic number, so the source does not need to be UTF-8.
+        let source = MlirStringRef {
+            data: source.as_ptr().cast(),
+            length: source.len(),
+        };
+        let name = StringRef::new(source_name);
+        let op = unsafe {
+            Operation::from_option_raw(mlirOperationCreateParse(
+                self.context.to_raw(),
+                source,
+                name.to_raw(),
+            ))
+        }?;
+        let Some(module) = Module::from_operation(op) else {
+            self.error(
+                format!("loc({source_name:?})"),
+                "expected a 'builtin.module' op at the top level",
+            );
+            return None;
+        };
+        self.resolve_includes(&module, dir);
+        Some(module)
+    }
+
+    /// Returns the path of the file included with the given path.
+    fn resolve(&self, path: &str, dir: Option<&Path>) -> Option<PathBuf> {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return path.is_file().then(|| path.to_owned());
+        }
+        dir.into_iter()
+            .chain(self.options.include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(path))
+            .find(|candidate| candidate.is_file())
+    }
+
+    fn resolve_includes(&mut self, module: &Module<'c>, dir: Option<&Path>) {
+        // Collected first because inlining replaces the include ops.
+        let includes = module
+            .as_operation()
+            .descendants::<IncludeOpRef>()
+            .collect::<Vec<_>>();
+
+        for include in includes {
+            let raw = include.to_raw();
+            let Some(path) = self.resolve(include.path(), dir) else {
+                self.error(
+                    include.location().to_string(),
+                    format!("could not find the included file '{}'", include.path()),
+                );
+                continue;
+            };
+            if !self.options.inline_includes {
+                let mut op = unsafe { OperationRefMut::from_raw(raw) };
+                op.set_attribute(
+                    "path",
+                    StringAttribute::new(self.context, &path.display().to_string()).into(),
+                );
+                continue;
+            }
+            let Some(included) = self.load(&path) else {
+                continue;
+            };
+            let name = include.sym_name();
+            let block = include.block().expect("include ops are in a block");
+            // Takes the ownership of the operation of the included module.
+            let op = unsafe { Operation::from_raw(mlirModuleGetOperation(included.to_raw())) };
+            std::mem::forget(included);
+            let op = block.insert_operation_before(unsafe { OperationRef::from_raw(raw) }, op);
+            unsafe { OperationRefMut::from_raw(op.to_raw()) }
+                .set_attribute("sym_name", StringAttribute::new(self.context, name).into());
+            detach_and_erase_op(unsafe { OperationRef::from_raw(raw) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::LlzkContext, dialect::module::ModuleExt as _};
+
+    const INCLUDED: &str = r#"module attributes {llzk.lang} {
+  function.def @id(%a: !felt.type) -> !felt.type {
+    function.return %a : !felt.type
+  }
+}"#;
+
+    fn including(path: &str) -> String {
+        format!(
+            r#"module attributes {{llzk.lang}} {{
+  include.from "{path}" as @lib
+}}"#
+        )
+    }
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("llzk-load-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn load_text_and_bytecode() {
+        let ctx = LlzkContext::new();
+        let dir = tmp_dir("formats");
+        let text = dir.join("id.mlir");
+        std::fs::write(&text, INCLUDED).unwrap();
+        let module = LlzkModule::load(&ctx, &text, &LoadOptions::new()).unwrap();
+
+        let mut bytecode = vec![];
+        module.write_bytecode(&mut bytecode).unwrap();
+        let binary = dir.join("id.llzk");
+        std::fs::write(&binary, bytecode).unwrap();
+        let reloaded = LlzkModule::load(&ctx, &binary, &LoadOptions::new()).unwrap();
+        assert_eq!(
+            reloaded.as_operation().to_string(),
+            module.as_operation().to_string()
+        );
+    }
+
+    #[test]
+    fn inline_includes_from_search_dirs() {
+        let ctx = LlzkContext::new();
+        let lib = tmp_dir("lib");
+        std::fs::write(lib.join("id.mlir"), INCLUDED).unwrap();
+        let main = tmp_dir("main").join("main.mlir");
+        std::fs::write(&main, including("id.mlir")).unwrap();
+
+        let err = LlzkModule::load(&ctx, &main, &LoadOptions::new()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("could not find the included file 'id.mlir'"),
+            "unexpected error: {err}"
+        );
+        assert!(
+            err.to_string().contains("main.mlir"),
+            "unexpected error: {err}"
+        );
+
+        let module =
+            LlzkModule::load(&ctx, &main, LoadOptions::new().add_include_dir(&lib)).unwrap();
+        let ir = module.as_operation().to_string();
+        assert!(ir.contains("module @lib"), "{ir}");
+        assert!(ir.contains("function.def @id"), "{ir}");
+        assert!(!ir.contains("include.from"), "{ir}");
+    }
+
+    #[test]
+    fn resolve_includes_without_inlining() {
+        let ctx = LlzkContext::new();
+        let lib = tmp_dir("noinline");
+        std::fs::write(lib.join("id.mlir"), INCLUDED).unwrap();
+        let module = LlzkModule::parse(
+            &ctx,
+            including("id.mlir").as_bytes(),
+            "main.mlir",
+            LoadOptions::new()
+                .add_include_dir(&lib)
+                .inline_includes(false),
+        )
+        .unwrap();
+        let include = IncludeOpRef::try_from(module.body().first_operation().unwrap()).unwrap();
+        assert_eq!(Path::new(include.path()), lib.join("id.mlir"));
+    }
+
+    #[test]
+    fn include_cycles_are_errors() {
+        let ctx = LlzkContext::new();
+        let dir = tmp_dir("cycle");
+        let path = dir.join("self.mlir");
+        std::fs::write(&path, including("self.mlir")).unwrap();
+        let err = LlzkModule::load(&ctx, &path, &LoadOptions::new()).unwrap_err();
+        assert!(
+            err.to_string().contains("the file includes itself"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn parse_errors_have_file_locations() {
+        let ctx = LlzkContext::new();
+        let path = tmp_dir("invalid").join("invalid.mlir");
+        std::fs::write(&path, "module {\n  felt.bogus\n}").unwrap();
+        let err = LlzkModule::load(&ctx, &path, &LoadOptions::new()).unwrap_err();
+        assert!(matches!(err, Error::Diagnostics(_)));
+        assert!(
+            err.to_string().contains("invalid.mlir"),
+            "unexpected error: {err}"
+        );
+    }
+}
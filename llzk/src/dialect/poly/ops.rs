@@ -389,6 +389,14 @@ impl<'c, 'a> TryFrom<OperationRef<'c, 'a>> for TemplateSymbolBindingOpRef<'c, 'a
     }
 }
 
+impl<'c: 'a, 'a> crate::operation::TypedOperationRef<'c, 'a>
+    for TemplateSymbolBindingOpRef<'c, 'a>
+{
+    fn isa(op: &OperationRef<'c, 'a>) -> bool {
+        is_param_op(op) || is_expr_op(op)
+    }
+}
+
 /// Defines the public API of the `poly.expr` op.
 pub trait TemplateExprOpLike<'c: 'a, 'a>: OperationLike<'c, 'a> {
     /// Returns the initializer region.
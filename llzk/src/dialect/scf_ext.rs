@@ -0,0 +1,292 @@
+//! Extensions for the 'scf' dialect.
+//!
+//! The builders create the operation at the insertion point of the builder and fill its regions
+//! with the operations produced by the callbacks. The values returned by a callback are used as
+//! the operands of the terminator of its region, so the callbacks must not create the
+//! terminators themselves. If a callback fails the operation is erased and the error is returned.
+
+use crate::{
+    builder::OpBuilderLike, dialect::llzk::LoopBoundsAttribute, operation::detach_and_erase_op,
+};
+use melior::{
+    dialect::scf,
+    ir::{
+        Block, BlockLike as _, BlockRef, Identifier, Location, Operation, OperationRef, Region,
+        RegionLike as _, Type, Value, ValueLike as _,
+        operation::{OperationBuilder, OperationLike as _},
+    },
+};
+
+crate::macros::isa_fn!(scf, if);
+crate::macros::isa_fn!(scf, yield);
+crate::macros::isa_fn!(scf, condition);
+crate::macros::isa_fn!(scf, for);
+crate::macros::isa_fn!(scf, while);
+
+/// Name of the discardable attribute that holds the [`LoopBoundsAttribute`] of a `scf.for` op.
+pub const LOOP_BOUNDS_ATTR_NAME: &str = "llzk.loopbounds";
+
+/// Creates a region with a single block that has arguments of the given types.
+fn region_with_block<'c>(location: Location<'c>, types: &[Type<'c>]) -> Region<'c> {
+    let region = Region::new();
+    let args = types.iter().map(|t| (*t, location)).collect::<Vec<_>>();
+    region.append_block(Block::new(&args));
+    region
+}
+
+/// Returns the arguments of the block.
+fn block_args<'c, 'a>(block: BlockRef<'c, 'a>) -> Vec<Value<'c, 'a>> {
+    (0..block.argument_count())
+        .map(|idx| block.argument(idx).expect("argument in range").into())
+        .collect()
+}
+
+/// Returns the entry block of the region at `index`.
+fn entry_block<'c, 'a>(op: OperationRef<'c, 'a>, index: usize) -> BlockRef<'c, 'a> {
+    op.region(index)
+        .ok()
+        .and_then(|region| region.first_block())
+        .expect("scf region has an entry block")
+}
+
+/// Fills the block with the operations produced by the callback and terminates it with the
+/// operation made by `terminator` from the values returned by the callback.
+fn fill_block<'c, 'a, B, T, E>(
+    builder: &B,
+    location: Location<'c>,
+    block: BlockRef<'c, 'a>,
+    fill: impl FnOnce(&B, &[Value<'c, 'a>]) -> Result<T, E>,
+    terminator: impl FnOnce(T, Location<'c>) -> Operation<'c>,
+) -> Result<(), E>
+where
+    B: OpBuilderLike<'c>,
+{
+    let _guard = builder.insertion_guard();
+    builder.set_insertion_point_at_start(block);
+    let values = fill(builder, &block_args(block))?;
+    builder.set_insertion_point_at_end(block);
+    builder.insert(location, |_, location| terminator(values, location));
+    Ok(())
+}
+
+/// Erases the operation if the result is an error.
+fn erase_on_error<'c, 'a, E>(
+    op: OperationRef<'c, 'a>,
+    result: Result<(), E>,
+) -> Result<OperationRef<'c, 'a>, E> {
+    match result {
+        Ok(()) => Ok(op),
+        Err(err) => {
+            detach_and_erase_op(op);
+            Err(err)
+        }
+    }
+}
+
+/// Creates a `scf.for` op that iterates from `lower` to `upper` with the given `step`.
+///
+/// The callback receives the induction variable and the loop-carried values, which start as
+/// `iter_args`, and returns the loop-carried values of the next iteration. The results of the op
+/// are the loop-carried values after the last iteration.
+pub fn for_<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    lower: Value<'c, '_>,
+    upper: Value<'c, '_>,
+    step: Value<'c, '_>,
+    iter_args: &[Value<'c, '_>],
+    body: impl FnOnce(&B, Value<'c, 'a>, &[Value<'c, 'a>]) -> Result<Vec<Value<'c, 'a>>, E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    build_for(builder, location, lower, upper, step, iter_args, None, body)
+}
+
+/// Like [`for_`], but also attaches the [`LoopBoundsAttribute`] with the constant bounds of the
+/// loop to the op.
+#[allow(clippy::too_many_arguments)]
+pub fn for_with_bounds<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    lower: Value<'c, '_>,
+    upper: Value<'c, '_>,
+    step: Value<'c, '_>,
+    iter_args: &[Value<'c, '_>],
+    bounds: LoopBoundsAttribute<'c>,
+    body: impl FnOnce(&B, Value<'c, 'a>, &[Value<'c, 'a>]) -> Result<Vec<Value<'c, 'a>>, E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    build_for(
+        builder,
+        location,
+        lower,
+        upper,
+        step,
+        iter_args,
+        Some(bounds),
+        body,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_for<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    lower: Value<'c, '_>,
+    upper: Value<'c, '_>,
+    step: Value<'c, '_>,
+    iter_args: &[Value<'c, '_>],
+    bounds: Option<LoopBoundsAttribute<'c>>,
+    body: impl FnOnce(&B, Value<'c, 'a>, &[Value<'c, 'a>]) -> Result<Vec<Value<'c, 'a>>, E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    let types = iter_args.iter().map(|v| v.r#type()).collect::<Vec<_>>();
+    let block_types = std::iter::once(lower.r#type())
+        .chain(types.iter().copied())
+        .collect::<Vec<_>>();
+    let op = builder.insert(location, |ctx, location| {
+        let attrs = bounds
+            .map(|bounds| (Identifier::new(ctx, LOOP_BOUNDS_ATTR_NAME), bounds.into()))
+            .into_iter()
+            .collect::<Vec<_>>();
+        OperationBuilder::new("scf.for", location)
+            .add_operands(&[lower, upper, step])
+            .add_operands(iter_args)
+            .add_results(&types)
+            .add_attributes(&attrs)
+            .add_regions([region_with_block(location, &block_types)])
+            .build()
+            .expect("valid operation")
+    });
+    let result = fill_block(
+        builder,
+        location,
+        entry_block(op, 0),
+        |builder, args| body(builder, args[0], &args[1..]),
+        |values, location| scf::r#yield(&values, location),
+    );
+    erase_on_error(op, result)
+}
+
+/// Creates a `scf.if` op with results of the given types.
+///
+/// Each callback returns the values that its branch yields, which must match `result_types`.
+pub fn if_<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    condition: Value<'c, '_>,
+    result_types: &[Type<'c>],
+    then: impl FnOnce(&B) -> Result<Vec<Value<'c, 'a>>, E>,
+    r#else: impl FnOnce(&B) -> Result<Vec<Value<'c, 'a>>, E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    let op = builder.insert(location, |_, location| {
+        scf::r#if(
+            condition,
+            result_types,
+            region_with_block(location, &[]),
+            region_with_block(location, &[]),
+            location,
+        )
+    });
+    let result = fill_block(
+        builder,
+        location,
+        entry_block(op, 0),
+        |builder, _| then(builder),
+        |values, location| scf::r#yield(&values, location),
+    )
+    .and_then(|()| {
+        fill_block(
+            builder,
+            location,
+            entry_block(op, 1),
+            |builder, _| r#else(builder),
+            |values, location| scf::r#yield(&values, location),
+        )
+    });
+    erase_on_error(op, result)
+}
+
+/// Creates a `scf.if` op without results and without an else branch.
+pub fn if_then<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    condition: Value<'c, '_>,
+    then: impl FnOnce(&B) -> Result<(), E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    let op = builder.insert(location, |_, location| {
+        scf::r#if(
+            condition,
+            &[],
+            region_with_block(location, &[]),
+            Region::new(),
+            location,
+        )
+    });
+    let result = fill_block(
+        builder,
+        location,
+        entry_block(op, 0),
+        |builder, _| then(builder),
+        |(), location| scf::r#yield(&[], location),
+    );
+    erase_on_error(op, result)
+}
+
+/// Creates a `scf.while` op with results of the given types.
+///
+/// The `before` callback receives the loop-carried values, which start as `init`, and returns the
+/// condition of the loop together with the values forwarded to the `after` region, or to the
+/// results of the op when the condition is false. The forwarded values must match
+/// `result_types`. The `after` callback receives the forwarded values and returns the
+/// loop-carried values of the next iteration.
+pub fn while_<'c, 'a, B, E>(
+    builder: &B,
+    location: Location<'c>,
+    init: &[Value<'c, '_>],
+    result_types: &[Type<'c>],
+    before: impl FnOnce(&B, &[Value<'c, 'a>]) -> Result<(Value<'c, 'a>, Vec<Value<'c, 'a>>), E>,
+    after: impl FnOnce(&B, &[Value<'c, 'a>]) -> Result<Vec<Value<'c, 'a>>, E>,
+) -> Result<OperationRef<'c, 'a>, E>
+where
+    B: OpBuilderLike<'c>,
+{
+    let init_types = init.iter().map(|v| v.r#type()).collect::<Vec<_>>();
+    let op = builder.insert(location, |_, location| {
+        scf::r#while(
+            init,
+            result_types,
+            region_with_block(location, &init_types),
+            region_with_block(location, result_types),
+            location,
+        )
+    });
+    let result = fill_block(
+        builder,
+        location,
+        entry_block(op, 0),
+        before,
+        |(condition, values), location| scf::condition(condition, &values, location),
+    )
+    .and_then(|()| {
+        fill_block(
+            builder,
+            location,
+            entry_block(op, 1),
+            after,
+            |values, location| scf::r#yield(&values, location),
+        )
+    });
+    erase_on_error(op, result)
+}
@@ -0,0 +1,17 @@
+//! `string` dialect.
+
+pub mod r#type;
+pub use r#type::{StringType, is_string_type};
+
+use llzk_sys::mlirGetDialectHandle__llzk__string__;
+use melior::dialect::DialectHandle;
+
+/// Returns a handle to the `string` dialect.
+pub fn handle() -> DialectHandle {
+    unsafe { DialectHandle::from_raw(mlirGetDialectHandle__llzk__string__()) }
+}
+
+/// Exports the common types of the string dialect.
+pub mod prelude {
+    pub use super::r#type::{StringType, is_string_type};
+}
@@ -0,0 +1,64 @@
+//! Implementation of `!string.type` type.
+
+use crate::utils::IsA;
+use llzk_sys::{llzkString_StringTypeGet, llzkTypeIsA_String_StringType};
+use melior::{
+    Context,
+    ir::{Type, TypeLike},
+};
+use mlir_sys::MlirType;
+
+/// Represents the `!string.type` type.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct StringType<'c> {
+    r#type: Type<'c>,
+}
+
+impl<'c> StringType<'c> {
+    unsafe fn from_raw(raw: MlirType) -> Self {
+        Self {
+            r#type: unsafe { Type::from_raw(raw) },
+        }
+    }
+
+    /// Creates a new string type.
+    pub fn new(ctx: &'c Context) -> Self {
+        unsafe { Self::from_raw(llzkString_StringTypeGet(ctx.to_raw())) }
+    }
+}
+
+impl<'c> TypeLike<'c> for StringType<'c> {
+    fn to_raw(&self) -> MlirType {
+        self.r#type.to_raw()
+    }
+}
+
+impl<'c> TryFrom<Type<'c>> for StringType<'c> {
+    type Error = melior::Error;
+
+    fn try_from(t: Type<'c>) -> Result<Self, Self::Error> {
+        if unsafe { llzkTypeIsA_String_StringType(t.to_raw()) } {
+            Ok(unsafe { Self::from_raw(t.to_raw()) })
+        } else {
+            Err(Self::Error::TypeExpected("llzk string", t.to_string()))
+        }
+    }
+}
+
+impl<'c> std::fmt::Display for StringType<'c> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.r#type, formatter)
+    }
+}
+
+impl<'c> From<StringType<'c>> for Type<'c> {
+    fn from(t: StringType<'c>) -> Type<'c> {
+        t.r#type
+    }
+}
+
+/// Return `true` iff the given [Type] is a [StringType].
+#[inline]
+pub fn is_string_type(t: Type) -> bool {
+    t.isa::<StringType>()
+}
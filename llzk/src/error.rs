@@ -49,6 +49,55 @@ pub enum Error {
     ExpectedFunctionName(&'static str),
     /// General error containing only a message.
     GeneralError(&'static str),
+    /// Happens when an operation could not be evaluated on concrete values.
+    EvaluationFailed {
+        /// Operation name.
+        name: String,
+        /// Location of the operation.
+        location: String,
+        /// Reason of the failure.
+        msg: String,
+    },
+    /// Happens when an operation could not be translated into a target format.
+    TranslationFailed {
+        /// Name of the target format.
+        target: &'static str,
+        /// Operation name.
+        name: String,
+        /// Location of the operation.
+        location: String,
+        /// Reason of the failure.
+        msg: String,
+    },
+    /// Happens when an external solver could not be run or its answer could not be understood.
+    SolverFailed(String),
+    /// Happens when an input or an output of a circuit has a type that its ABI cannot describe.
+    UnsupportedAbiType {
+        /// Name of the input or output.
+        name: String,
+        /// Location of the operation that declares it.
+        location: String,
+        /// The unsupported type.
+        ty: String,
+    },
+    /// Happens when a JSON document could not be parsed.
+    InvalidJson {
+        /// Line of the error, starting at 1.
+        line: usize,
+        /// Column of the error in characters, starting at 1.
+        column: usize,
+        /// Reason of the failure.
+        msg: String,
+    },
+    /// Happens when a value of an input or a witness file does not match the type it is read as
+    /// or written from.
+    InvalidWitness {
+        /// Path of the value in the document, like `$.members.out[1].x`, or the member of a
+        /// struct whose type cannot be represented, like `@Main.out`.
+        path: String,
+        /// Reason of the failure.
+        msg: String,
+    },
     /// Error emitted by the PCL translation function.
     #[cfg(feature = "pcl-backend")]
     PclTranslationError,
@@ -133,6 +182,29 @@ impl Display for Error {
                 "expected user-defined function to have name: {expected_name}"
             ),
             Error::GeneralError(msg) => write!(f, "{msg}"),
+            Error::EvaluationFailed {
+                name,
+                location,
+                msg,
+            } => write!(f, "{location}: '{name}' op evaluation failed: {msg}"),
+            Error::TranslationFailed {
+                target,
+                name,
+                location,
+                msg,
+            } => write!(
+                f,
+                "{location}: '{name}' op could not be translated to {target}: {msg}"
+            ),
+            Error::SolverFailed(msg) => write!(f, "solver failed: {msg}"),
+            Error::UnsupportedAbiType { name, location, ty } => write!(
+                f,
+                "{location}: the type {ty} of '{name}' is not supported by the ABI"
+            ),
+            Error::InvalidJson { line, column, msg } => {
+                write!(f, "invalid JSON at line {line}, column {column}: {msg}")
+            }
+            Error::InvalidWitness { path, msg } => write!(f, "invalid value at '{path}': {msg}"),
             Error::SymbolNotFound(sym) => write!(f, "symbol was not found: {sym}"),
             Error::AttributeExpected(attr, actual) => write!(f, "{attr} attr expected: {actual}"),
             #[cfg(feature = "pcl-backend")]
@@ -154,6 +226,16 @@ pub struct DiagnosticError {
 }
 
 impl DiagnosticError {
+    /// Creates an error diagnostic that was not emitted by MLIR.
+    pub(crate) fn error(location: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self {
+            severity: mlir_sys::MlirDiagnosticSeverity_MlirDiagnosticError,
+            location: location.into(),
+            msg: msg.into(),
+            notes: DiagnosticErrors::default(),
+        }
+    }
+
     /// Returns the severity of the diagnostic, if valid.
     pub fn severity(&self) -> Option<DiagnosticSeverity> {
         DiagnosticSeverity::try_from(self.severity).ok()
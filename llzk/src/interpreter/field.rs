@@ -0,0 +1,303 @@
+//! Prime field arithmetic used for evaluating `felt` operations.
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// Primes of the fields that LLZK knows about without a `FieldSpecAttribute`.
+const BUILTIN_FIELDS: &[(&str, &str)] = &[
+    ("babybear", "2013265921"),
+    (
+        "bn128",
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    ),
+    (
+        "bn254",
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    ),
+    ("goldilocks", "18446744069414584321"),
+    (
+        "grumpkin",
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+    ),
+    ("koalabear", "2130706433"),
+    ("mersenne31", "2147483647"),
+];
+
+/// A prime field over which `felt` values are evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    prime: BigUint,
+}
+
+impl Field {
+    /// Creates a field with the given prime modulus.
+    ///
+    /// # Panics
+    ///
+    /// If the modulus is less than 2.
+    pub fn new(prime: BigUint) -> Self {
+        assert!(
+            prime > BigUint::from(1u8),
+            "field modulus must be at least 2"
+        );
+        Self { prime }
+    }
+
+    /// Returns the field for one of the fields built into LLZK, if the name is known.
+    pub fn builtin(name: &str) -> Option<Self> {
+        BUILTIN_FIELDS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .and_then(|(_, prime)| BigUint::parse_bytes(prime.as_bytes(), 10))
+            .map(Self::new)
+    }
+
+    /// Returns the names of the fields built into LLZK.
+    pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+        BUILTIN_FIELDS.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns the prime modulus of the field.
+    pub fn prime(&self) -> &BigUint {
+        &self.prime
+    }
+
+    /// Reduces the value into the canonical range `[0, p)`.
+    pub fn reduce(&self, value: &BigUint) -> BigUint {
+        value % &self.prime
+    }
+
+    /// Returns the canonical representative of a signed integer.
+    pub fn from_signed(&self, value: &BigInt) -> BigUint {
+        let magnitude = self.reduce(value.magnitude());
+        if value.sign() == Sign::Minus && magnitude != BigUint::ZERO {
+            &self.prime - magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Interprets the element as a signed integer, where values above `(p - 1) / 2` are
+    /// negative.
+    pub fn to_signed(&self, value: &BigUint) -> BigInt {
+        let value = self.reduce(value);
+        if value > (&self.prime - 1u8) >> 1 {
+            -BigInt::from_biguint(Sign::Plus, &self.prime - value)
+        } else {
+            BigInt::from_biguint(Sign::Plus, value)
+        }
+    }
+
+    /// Computes `lhs + rhs`.
+    pub fn add(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.reduce(&(lhs + rhs))
+    }
+
+    /// Computes `lhs - rhs`.
+    pub fn sub(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.add(lhs, &self.neg(rhs))
+    }
+
+    /// Computes `lhs * rhs`.
+    pub fn mul(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.reduce(&(lhs * rhs))
+    }
+
+    /// Computes `-value`.
+    pub fn neg(&self, value: &BigUint) -> BigUint {
+        let value = self.reduce(value);
+        if value == BigUint::ZERO {
+            value
+        } else {
+            &self.prime - value
+        }
+    }
+
+    /// Computes the multiplicative inverse. Returns `None` for zero.
+    pub fn inv(&self, value: &BigUint) -> Option<BigUint> {
+        self.reduce(value).modinv(&self.prime)
+    }
+
+    /// Computes `lhs / rhs` in the field. Returns `None` if `rhs` is zero.
+    pub fn div(&self, lhs: &BigUint, rhs: &BigUint) -> Option<BigUint> {
+        self.inv(rhs).map(|inv| self.mul(lhs, &inv))
+    }
+
+    /// Computes `base ^ exp`, where the exponent is the integer representative of the element.
+    pub fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        self.reduce(base).modpow(exp, &self.prime)
+    }
+
+    /// Integer division of the representatives. Returns `None` if `rhs` is zero.
+    pub fn uintdiv(&self, lhs: &BigUint, rhs: &BigUint) -> Option<BigUint> {
+        let rhs = self.reduce(rhs);
+        (rhs != BigUint::ZERO).then(|| self.reduce(lhs) / rhs)
+    }
+
+    /// Integer remainder of the representatives. Returns `None` if `rhs` is zero.
+    pub fn umod(&self, lhs: &BigUint, rhs: &BigUint) -> Option<BigUint> {
+        let rhs = self.reduce(rhs);
+        (rhs != BigUint::ZERO).then(|| self.reduce(lhs) % rhs)
+    }
+
+    /// Signed integer division, rounding towards zero. Returns `None` if `rhs` is zero.
+    pub fn sintdiv(&self, lhs: &BigUint, rhs: &BigUint) -> Option<BigUint> {
+        let rhs = self.to_signed(rhs);
+        (rhs != BigInt::ZERO).then(|| self.from_signed(&(self.to_signed(lhs) / rhs)))
+    }
+
+    /// Signed integer remainder, with the sign of `lhs`. Returns `None` if `rhs` is zero.
+    pub fn smod(&self, lhs: &BigUint, rhs: &BigUint) -> Option<BigUint> {
+        let rhs = self.to_signed(rhs);
+        (rhs != BigInt::ZERO).then(|| self.from_signed(&(self.to_signed(lhs) % rhs)))
+    }
+
+    /// Bitwise and of the representatives.
+    pub fn bit_and(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.reduce(&(self.reduce(lhs) & self.reduce(rhs)))
+    }
+
+    /// Bitwise or of the representatives.
+    pub fn bit_or(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.reduce(&(self.reduce(lhs) | self.reduce(rhs)))
+    }
+
+    /// Bitwise xor of the representatives.
+    pub fn bit_xor(&self, lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        self.reduce(&(self.reduce(lhs) ^ self.reduce(rhs)))
+    }
+
+    /// Bitwise negation of the representative within the bit width of the prime.
+    pub fn bit_not(&self, value: &BigUint) -> BigUint {
+        let mask = (BigUint::from(1u8) << self.prime.bits()) - 1u8;
+        self.reduce(&(self.reduce(value) ^ mask))
+    }
+
+    /// Shifts the representative left, modulo the prime. This is the product of the value and
+    /// `2^amount`.
+    pub fn shl(&self, value: &BigUint, amount: &BigUint) -> BigUint {
+        let factor = BigUint::from(2u8).modpow(&self.reduce(amount), &self.prime);
+        self.mul(value, &factor)
+    }
+
+    /// Shifts the representative right. Amounts of at least the bit width of the prime give zero.
+    pub fn shr(&self, value: &BigUint, amount: &BigUint) -> BigUint {
+        let amount = self.reduce(amount);
+        if amount >= BigUint::from(self.prime.bits()) {
+            return BigUint::ZERO;
+        }
+        let amount = usize::try_from(&amount).expect("amount is less than the bit width");
+        self.reduce(value) >> amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rstest::rstest;
+
+    fn babybear() -> Field {
+        Field::builtin("babybear").unwrap()
+    }
+
+    #[rstest]
+    fn builtin_fields_are_known(
+        #[values(
+            "babybear",
+            "bn128",
+            "bn254",
+            "goldilocks",
+            "grumpkin",
+            "koalabear",
+            "mersenne31"
+        )]
+        name: &str,
+    ) {
+        assert!(Field::builtin(name).is_some());
+    }
+
+    #[test]
+    fn unknown_field_is_none() {
+        assert!(Field::builtin("not_a_field").is_none());
+    }
+
+    #[quickcheck]
+    fn div_is_inverse_of_mul(a: u32, b: u32) -> bool {
+        let f = babybear();
+        let (a, b) = (BigUint::from(a), BigUint::from(b));
+        match f.div(&f.mul(&a, &b), &b) {
+            Some(q) => q == f.reduce(&a),
+            None => f.reduce(&b) == BigUint::ZERO,
+        }
+    }
+
+    #[quickcheck]
+    fn sub_is_inverse_of_add(a: u64, b: u64) -> bool {
+        let f = babybear();
+        let (a, b) = (BigUint::from(a), BigUint::from(b));
+        f.sub(&f.add(&a, &b), &b) == f.reduce(&a)
+    }
+
+    #[quickcheck]
+    fn signed_roundtrip(a: i32) -> bool {
+        let f = babybear();
+        let a = BigInt::from(a);
+        f.to_signed(&f.from_signed(&a)) == a
+    }
+
+    #[test]
+    fn signed_division_rounds_towards_zero() {
+        let f = babybear();
+        let minus_seven = f.from_signed(&BigInt::from(-7));
+        let two = BigUint::from(2u8);
+        assert_eq!(
+            f.to_signed(&f.sintdiv(&minus_seven, &two).unwrap()),
+            BigInt::from(-3)
+        );
+        assert_eq!(
+            f.to_signed(&f.smod(&minus_seven, &two).unwrap()),
+            BigInt::from(-1)
+        );
+    }
+
+    #[test]
+    fn shifts() {
+        let f = babybear();
+        let one = BigUint::from(1u8);
+        assert_eq!(
+            f.shl(&BigUint::from(3u8), &BigUint::from(4u8)),
+            BigUint::from(48u8)
+        );
+        // 2^31 = 16 * 2^27 = 2^27 - 1 (mod 15 * 2^27 + 1).
+        assert_eq!(
+            f.shl(&one, &BigUint::from(31u8)),
+            BigUint::from((1u32 << 27) - 1)
+        );
+        assert_eq!(
+            f.shl(&one, &f.prime().clone()),
+            f.shl(&one, &BigUint::ZERO),
+            "the amount is reduced modulo the prime"
+        );
+        assert_eq!(
+            f.shr(&BigUint::from(48u8), &BigUint::from(4u8)),
+            BigUint::from(3u8)
+        );
+        let max = f.sub(&BigUint::ZERO, &one);
+        assert_eq!(f.shr(&max, &BigUint::from(30u8)), one);
+        assert_eq!(f.shr(&max, &BigUint::from(31u8)), BigUint::ZERO);
+        assert_eq!(f.shr(&max, &f.sub(&BigUint::ZERO, &one)), BigUint::ZERO);
+    }
+
+    #[quickcheck]
+    fn shl_is_mul_by_power_of_two(a: u32, n: u8) -> bool {
+        let f = babybear();
+        let a = BigUint::from(a);
+        let power = (0..n).fold(BigUint::from(1u8), |power, _| f.add(&power, &power));
+        f.shl(&a, &BigUint::from(n)) == f.mul(&a, &power)
+    }
+
+    #[test]
+    fn inverse_of_zero_is_none() {
+        assert_eq!(babybear().inv(&BigUint::ZERO), None);
+    }
+}
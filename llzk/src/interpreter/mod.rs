@@ -0,0 +1,1093 @@
+//! Reference interpreter for LLZK IR.
+//!
+//! The interpreter executes the `@compute` function of a struct on concrete inputs and returns the
+//! populated struct instance, which makes it possible to test witness generation directly from
+//! Rust.
+//!
+//! Felt arithmetic is performed modulo the prime of the field named by the `!felt.type`. The
+//! prime is looked up in the fields registered with [`Interpreter::with_field`], the `llzk.fields`
+//! specifications of the root module, and the fields built into LLZK, in that order. Felt types
+//! that don't name a field use the [default field](Interpreter::with_default_field).
+//!
+//! The IR is executed as is, so template parameters must be instantiated before running it.
+//! Constraint operations (`constrain.eq`, `constrain.in` and `bool.assert`) are ignored while
+//! computing a witness. [`Interpreter::check_constraints`] evaluates the `@constrain` function on a
+//! witness and reports the constraint operations that do not hold.
+
+mod field;
+mod text;
+mod value;
+mod violation;
+
+pub use field::Field;
+pub(crate) use text::cmp_predicate;
+pub use value::{ArrayValue, PodValue, StructInstance, Value};
+pub use violation::ConstraintViolation;
+
+use crate::{
+    attributes::array::ArrayAttribute,
+    dialect::{
+        array::ArrayType,
+        bool::CmpPredicate,
+        felt::{FeltConstAttribute, FeltType, FieldSpecAttribute},
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _, FuncDefOpRef},
+        pod::r#type::PodType,
+        r#struct::{MemberDefOpLike as _, StructDefOpLike, StructDefOpRef, StructType},
+    },
+    error::Error,
+    symbol_ref::SymbolRefAttribute,
+};
+use llzk_sys::LLZK_FIELD_ATTR_NAME;
+use melior::ir::{
+    Attribute, BlockLike as _, BlockRef, OperationRef, RegionLike as _, Type, TypeLike as _,
+    ValueLike,
+    attribute::{FlatSymbolRefAttribute, IntegerAttribute, StringAttribute},
+    operation::{OperationLike, OperationResult},
+    r#type::IntegerType,
+};
+use mlir_sys::{MlirOperation, mlirOperationGetParentOperation};
+use num_bigint::BigUint;
+use std::{cell::RefCell, collections::HashMap, ffi::CStr, os::raw::c_void};
+
+/// Executes LLZK functions on concrete values.
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    default_field: Field,
+    fields: HashMap<String, Field>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    /// Creates an interpreter that uses `bn128` as the default field.
+    pub fn new() -> Self {
+        Self {
+            default_field: Field::builtin("bn128").expect("bn128 is a builtin field"),
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Sets the field used for felt types that don't name a field.
+    pub fn with_default_field(mut self, field: Field) -> Self {
+        self.default_field = field;
+        self
+    }
+
+    /// Registers a field by name. Takes precedence over the field specifications of the module
+    /// and the builtin fields.
+    pub fn with_field(mut self, name: impl Into<String>, field: Field) -> Self {
+        self.fields.insert(name.into(), field);
+        self
+    }
+
+    /// Executes the `@compute` function of the struct with the given inputs and returns the
+    /// struct instance it creates.
+    pub fn compute<'c: 'a, 'a>(
+        &self,
+        struct_def: &impl StructDefOpLike<'c, 'a>,
+        inputs: &[Value],
+    ) -> Result<StructInstance, Error> {
+        let func = struct_def.compute_func().ok_or_else(|| {
+            Error::SymbolNotFound(format!("{}::@compute", struct_def.fully_qualified_name()))
+        })?;
+        match self.call(&func, inputs)?.as_slice() {
+            [Value::Struct(instance)] => Ok(instance.clone()),
+            _ => Err(Error::GeneralError(
+                "@compute did not return a struct instance",
+            )),
+        }
+    }
+
+    /// Executes the function with the given arguments and returns the values it returns.
+    pub fn call<'c: 'a, 'a>(
+        &self,
+        func: &FuncDefOpRef<'c, 'a>,
+        args: &[Value],
+    ) -> Result<Vec<Value>, Error> {
+        Execution::new(self, func, false)?.call(*func, args.to_vec())
+    }
+
+    /// Executes the `@constrain` function of the struct on a witness and returns the constraints
+    /// that do not hold, in the order they were evaluated.
+    ///
+    /// The instance is usually the result of [`compute`](Self::compute) and the inputs are the
+    /// same ones the instance was computed from.
+    pub fn check_constraints<'c: 'a, 'a>(
+        &self,
+        struct_def: &impl StructDefOpLike<'c, 'a>,
+        instance: &StructInstance,
+        inputs: &[Value],
+    ) -> Result<Vec<ConstraintViolation>, Error> {
+        let func = struct_def.constrain_func().ok_or_else(|| {
+            Error::SymbolNotFound(format!("{}::@constrain", struct_def.fully_qualified_name()))
+        })?;
+        let execution = Execution::new(self, &func, true)?;
+        let mut args = vec![Value::Struct(instance.clone())];
+        args.extend_from_slice(inputs);
+        execution.call(func, args)?;
+        Ok(execution.violations.into_inner())
+    }
+}
+
+/// How control leaves a block.
+enum Flow {
+    Return(Vec<Value>),
+    Yield(Vec<Value>),
+    Condition(bool, Vec<Value>),
+}
+
+/// Creates an error pointing at the operation being evaluated.
+fn fail<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, msg: impl Into<String>) -> Error {
+    Error::EvaluationFailed {
+        name: op
+            .name()
+            .as_string_ref()
+            .as_str()
+            .unwrap_or("<unknown>")
+            .to_owned(),
+        location: op.location().to_string(),
+        msg: msg.into(),
+    }
+}
+
+/// Checks that the value has the expected kind.
+fn expect<'c: 'a, 'a, 'v, T>(
+    op: &impl OperationLike<'c, 'a>,
+    value: &'v Value,
+    f: impl FnOnce(&'v Value) -> Option<T>,
+    expected: &str,
+) -> Result<T, Error> {
+    f(value).ok_or_else(|| {
+        fail(
+            op,
+            format!("expected {expected} value but got {}", value.kind()),
+        )
+    })
+}
+
+fn is_i1(ty: Type) -> bool {
+    IntegerType::try_from(ty).is_ok_and(|ty| ty.width() == 1)
+}
+
+/// Returns the name held by a string or flat symbol reference attribute.
+fn attr_name(attr: Attribute) -> Option<String> {
+    StringAttribute::try_from(attr)
+        .map(|attr| attr.value().to_owned())
+        .or_else(|_| FlatSymbolRefAttribute::try_from(attr).map(|attr| attr.value().to_owned()))
+        .ok()
+}
+
+/// Returns the outermost ancestor of the operation.
+pub(crate) fn root_operation(op: MlirOperation) -> MlirOperation {
+    let mut root = op;
+    loop {
+        let parent = unsafe { mlirOperationGetParentOperation(root) };
+        if parent.ptr.is_null() {
+            return root;
+        }
+        root = parent;
+    }
+}
+
+/// Finds the operation nested directly in the given one that defines the symbol.
+fn find_child_symbol(op: MlirOperation, name: &str) -> Option<MlirOperation> {
+    let op = unsafe { OperationRef::from_raw(op) };
+    for region in op.regions() {
+        let mut block = region.first_block();
+        while let Some(current_block) = block {
+            let mut child = current_block.first_operation();
+            while let Some(current) = child {
+                let defines = current
+                    .attribute("sym_name")
+                    .ok()
+                    .and_then(|attr| StringAttribute::try_from(attr).ok())
+                    .is_some_and(|attr| attr.value() == name);
+                if defines {
+                    return Some(current.to_raw());
+                }
+                child = current.next_in_block();
+            }
+            block = current_block.next_in_region();
+        }
+    }
+    None
+}
+
+/// Resolves the symbol from the nearest ancestor of the operation where the whole path exists.
+fn resolve_symbol<'c, 'b>(
+    from: MlirOperation,
+    symbol: &SymbolRefAttribute<'c>,
+) -> Result<Option<OperationRef<'c, 'b>>, Error> {
+    let mut path = vec![symbol.root().as_str()?.to_owned()];
+    path.extend(symbol.nested().iter().map(|n| n.value().to_owned()));
+
+    let mut scope = unsafe { mlirOperationGetParentOperation(from) };
+    while !scope.ptr.is_null() {
+        let found = path
+            .iter()
+            .try_fold(scope, |op, name| find_child_symbol(op, name));
+        if let Some(found) = found {
+            return Ok(Some(unsafe { OperationRef::from_raw(found) }));
+        }
+        scope = unsafe { mlirOperationGetParentOperation(scope) };
+    }
+    Ok(None)
+}
+
+/// Returns the fields that felt types can name in the module that contains the operation.
+///
+/// These are the builtin fields and the fields declared in the `llzk.fields` attribute of the root
+/// module.
+pub(crate) fn module_fields<'c: 'a, 'a>(
+    op: &impl OperationLike<'c, 'a>,
+) -> Result<HashMap<String, Field>, Error> {
+    let mut fields: HashMap<String, Field> = Field::builtin_names()
+        .filter_map(|name| Some((name.to_owned(), Field::builtin(name)?)))
+        .collect();
+
+    let root = unsafe { OperationRef::from_raw(root_operation(op.to_raw())) };
+    let attr_name = unsafe { CStr::from_ptr(LLZK_FIELD_ATTR_NAME) }.to_str()?;
+    if let Ok(attr) = root.attribute(attr_name) {
+        let specs: Vec<Attribute> = match ArrayAttribute::try_from(attr) {
+            Ok(array) => array.into_iter().collect(),
+            Err(_) => vec![attr],
+        };
+        for spec in specs {
+            let spec = FieldSpecAttribute::try_from(spec)?;
+            let prime = spec.prime();
+            if prime <= BigUint::from(1u8) {
+                return Err(Error::AttributeExpected(
+                    "llzk field spec",
+                    spec.to_string(),
+                ));
+            }
+            fields.insert(spec.name(), Field::new(prime));
+        }
+    }
+    Ok(fields)
+}
+
+/// Values of the SSA values visible in the function being executed.
+#[derive(Default)]
+struct Frame {
+    values: HashMap<*const c_void, Value>,
+}
+
+impl Frame {
+    fn bind<'c>(&mut self, ssa: impl ValueLike<'c>, value: Value) {
+        self.values.insert(ssa.to_raw().ptr, value);
+    }
+
+    fn value<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        ssa: impl ValueLike<'c>,
+    ) -> Result<Value, Error> {
+        self.values
+            .get(&ssa.to_raw().ptr)
+            .cloned()
+            .ok_or_else(|| fail(op, "use of a value that was not computed"))
+    }
+
+    fn operand<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<Value, Error> {
+        self.value(op, op.operand(index)?)
+    }
+
+    fn operands<'c: 'a, 'a>(&self, op: &impl OperationLike<'c, 'a>) -> Result<Vec<Value>, Error> {
+        (0..op.operand_count())
+            .map(|index| self.operand(op, index))
+            .collect()
+    }
+
+    fn felt<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<BigUint, Error> {
+        let value = self.operand(op, index)?;
+        expect(op, &value, Value::as_felt, "felt").cloned()
+    }
+
+    fn bool<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<bool, Error> {
+        let value = self.operand(op, index)?;
+        expect(op, &value, Value::as_bool, "bool")
+    }
+
+    /// Reads an integer operand. Booleans are read as 0 or 1.
+    fn int<'c: 'a, 'a>(&self, op: &impl OperationLike<'c, 'a>, index: usize) -> Result<i64, Error> {
+        let value = self.operand(op, index)?;
+        expect(
+            op,
+            &value,
+            |value| match value {
+                Value::Int(value) => Some(*value),
+                Value::Bool(value) => Some(i64::from(*value)),
+                _ => None,
+            },
+            "integer",
+        )
+    }
+
+    /// Reads the operands in the given range as indices into an aggregate.
+    fn indices<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        range: std::ops::Range<usize>,
+    ) -> Result<Vec<usize>, Error> {
+        range
+            .map(|index| {
+                usize::try_from(self.int(op, index)?)
+                    .map_err(|_| fail(op, "negative index into an array"))
+            })
+            .collect()
+    }
+
+    fn array<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<ArrayValue, Error> {
+        let value = self.operand(op, index)?;
+        expect(op, &value, Value::as_array, "array").cloned()
+    }
+
+    fn pod<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<PodValue, Error> {
+        let value = self.operand(op, index)?;
+        expect(op, &value, Value::as_pod, "pod").cloned()
+    }
+
+    fn instance<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        index: usize,
+    ) -> Result<StructInstance, Error> {
+        let value = self.operand(op, index)?;
+        expect(op, &value, Value::as_struct, "struct").cloned()
+    }
+
+    fn set_results<'c: 'a, 'a>(
+        &mut self,
+        op: &impl OperationLike<'c, 'a>,
+        values: Vec<Value>,
+    ) -> Result<(), Error> {
+        if values.len() != op.result_count() {
+            return Err(fail(
+                op,
+                format!(
+                    "produced {} values but the op has {} results",
+                    values.len(),
+                    op.result_count()
+                ),
+            ));
+        }
+        for (index, value) in values.into_iter().enumerate() {
+            self.bind(op.result(index)?, value);
+        }
+        Ok(())
+    }
+}
+
+/// Checks whether two values are equal, comparing felts by their canonical representatives.
+fn same_value(field: &Field, lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Felt(lhs), Value::Felt(rhs)) => field.reduce(lhs) == field.reduce(rhs),
+        (Value::Array(lhs), Value::Array(rhs)) => {
+            lhs.dims() == rhs.dims()
+                && lhs
+                    .elements()
+                    .iter()
+                    .zip(rhs.elements().iter())
+                    .all(|pair| match pair {
+                        (Some(lhs), Some(rhs)) => same_value(field, lhs, rhs),
+                        (lhs, rhs) => lhs == rhs,
+                    })
+        }
+        (lhs, rhs) => lhs == rhs,
+    }
+}
+
+/// Checks whether the value is an element, or a subarray along the leading dimensions, of the
+/// array.
+fn array_contains(field: &Field, array: &ArrayValue, value: &Value) -> bool {
+    let Value::Array(sub) = value else {
+        return array
+            .elements()
+            .iter()
+            .flatten()
+            .any(|element| same_value(field, element, value));
+    };
+    let (dims, sub_dims) = (array.dims(), sub.dims());
+    let Some(outer) = dims.len().checked_sub(sub_dims.len()) else {
+        return false;
+    };
+    if dims[outer..] != sub_dims[..] {
+        return false;
+    }
+    (0..dims[..outer].iter().product::<usize>()).any(|flat| {
+        let mut indices = vec![0; outer];
+        let mut rest = flat;
+        for (index, dim) in indices.iter_mut().zip(&dims[..outer]).rev() {
+            *index = rest % dim;
+            rest /= dim;
+        }
+        array
+            .extract(&indices)
+            .is_some_and(|candidate| same_value(field, &Value::Array(candidate), value))
+    })
+}
+
+/// State shared by all the functions executed in a single call into the interpreter.
+struct Execution<'i> {
+    interpreter: &'i Interpreter,
+    fields: HashMap<String, Field>,
+    /// Whether constraint operations are evaluated.
+    check: bool,
+    /// Constraints that did not hold.
+    violations: RefCell<Vec<ConstraintViolation>>,
+    /// Members leading to the subcomponent whose constraints are being evaluated.
+    member_path: RefCell<Vec<String>>,
+}
+
+impl<'i> Execution<'i> {
+    fn new<'c: 'a, 'a>(
+        interpreter: &'i Interpreter,
+        op: &impl OperationLike<'c, 'a>,
+        check: bool,
+    ) -> Result<Self, Error> {
+        let mut fields = module_fields(op)?;
+        fields.extend(interpreter.fields.clone());
+        Ok(Self {
+            interpreter,
+            fields,
+            check,
+            violations: RefCell::default(),
+            member_path: RefCell::default(),
+        })
+    }
+
+    /// Returns the field of a felt type, or of the elements of an array type. Other types use the
+    /// default field.
+    fn field_of<'c: 'a, 'a>(
+        &self,
+        op: &impl OperationLike<'c, 'a>,
+        ty: Type<'c>,
+    ) -> Result<&Field, Error> {
+        let ty = ArrayType::try_from(ty).map_or(ty, |array| array.element_type());
+        let Ok(ty) = FeltType::try_from(ty) else {
+            return Ok(&self.interpreter.default_field);
+        };
+        match ty.field_name() {
+            None => Ok(&self.interpreter.default_field),
+            Some(name) => self
+                .fields
+                .get(&name)
+                .ok_or_else(|| fail(op, format!("unknown field '{name}'"))),
+        }
+    }
+
+    fn call<'c: 'a, 'a>(
+        &self,
+        func: FuncDefOpRef<'c, 'a>,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>, Error> {
+        if func.is_declaration() {
+            return Err(fail(&func, "cannot call a function without a body"));
+        }
+        let entry = func.body()?.first_block().ok_or(Error::BlockExpected(0))?;
+        let mut frame = Frame::default();
+        match self.exec_block(&mut frame, entry, args)? {
+            Flow::Return(values) => Ok(values),
+            _ => Err(fail(&func, "function body did not end with a return")),
+        }
+    }
+
+    fn exec_block<'c: 'a, 'a>(
+        &self,
+        frame: &mut Frame,
+        block: BlockRef<'c, 'a>,
+        args: Vec<Value>,
+    ) -> Result<Flow, Error> {
+        if args.len() != block.argument_count() {
+            return Err(Error::GeneralError(
+                "number of values does not match the block arguments",
+            ));
+        }
+        for (index, arg) in args.into_iter().enumerate() {
+            frame.bind(block.argument(index)?, arg);
+        }
+        let mut next = block.first_operation();
+        while let Some(op) = next {
+            if let Some(flow) = self.exec_op(frame, op)? {
+                return Ok(flow);
+            }
+            next = op.next_in_block();
+        }
+        Err(Error::GeneralError("block ended without a terminator"))
+    }
+
+    /// Executes a nested region that must end with a yield and returns the yielded values.
+    fn exec_nested<'c: 'a, 'a>(
+        &self,
+        frame: &mut Frame,
+        op: &OperationRef<'c, 'a>,
+        region: usize,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>, Error> {
+        let Some(block) = op.region(region)?.first_block() else {
+            return Ok(vec![]);
+        };
+        match self.exec_block(frame, block, args)? {
+            Flow::Yield(values) => Ok(values),
+            _ => Err(fail(op, "nested region did not end with a yield")),
+        }
+    }
+
+    /// Executes a single operation. Returns the control flow change if the op is a terminator.
+    fn exec_op<'c: 'a, 'a>(
+        &self,
+        frame: &mut Frame,
+        op: OperationRef<'c, 'a>,
+    ) -> Result<Option<Flow>, Error> {
+        let name = op.name();
+        let name = name.as_string_ref().as_str()?;
+        let results = match name {
+            "function.return" => return Ok(Some(Flow::Return(frame.operands(&op)?))),
+            "scf.yield" => return Ok(Some(Flow::Yield(frame.operands(&op)?))),
+            "scf.condition" => {
+                let cond = frame.bool(&op, 0)?;
+                let mut values = frame.operands(&op)?;
+                values.remove(0);
+                return Ok(Some(Flow::Condition(cond, values)));
+            }
+            "felt.const" => {
+                let field = self.field_of(&op, op.result(0)?.r#type())?;
+                let value = FeltConstAttribute::try_from(op.attribute("value")?)?.to_bigint();
+                vec![Value::Felt(field.from_signed(&value))]
+            }
+            "felt.add" | "felt.sub" | "felt.mul" | "felt.div" | "felt.pow" | "felt.uintdiv"
+            | "felt.sintdiv" | "felt.umod" | "felt.smod" | "felt.bit_and" | "felt.bit_or"
+            | "felt.bit_xor" | "felt.shl" | "felt.shr" => {
+                vec![self.felt_binary(frame, &op, name)?]
+            }
+            "felt.neg" | "felt.inv" | "felt.bit_not" => {
+                let field = self.field_of(&op, op.result(0)?.r#type())?;
+                let value = frame.felt(&op, 0)?;
+                let result = match name {
+                    "felt.neg" => field.neg(&value),
+                    "felt.bit_not" => field.bit_not(&value),
+                    _ => field
+                        .inv(&value)
+                        .ok_or_else(|| fail(&op, "inverse of zero"))?,
+                };
+                vec![Value::Felt(result)]
+            }
+            "bool.cmp" => {
+                let field = self.field_of(&op, op.operand(0)?.r#type())?;
+                let lhs = field.reduce(&frame.felt(&op, 0)?);
+                let rhs = field.reduce(&frame.felt(&op, 1)?);
+                let predicate = text::cmp_predicate(op.attribute("predicate")?)
+                    .ok_or_else(|| fail(&op, "malformed comparison predicate"))?;
+                vec![Value::Bool(match predicate {
+                    CmpPredicate::Eq => lhs == rhs,
+                    CmpPredicate::Ne => lhs != rhs,
+                    CmpPredicate::Lt => lhs < rhs,
+                    CmpPredicate::Le => lhs <= rhs,
+                    CmpPredicate::Gt => lhs > rhs,
+                    CmpPredicate::Ge => lhs >= rhs,
+                })]
+            }
+            "bool.and" | "bool.or" | "bool.xor" => {
+                let (lhs, rhs) = (frame.bool(&op, 0)?, frame.bool(&op, 1)?);
+                vec![Value::Bool(match name {
+                    "bool.and" => lhs && rhs,
+                    "bool.or" => lhs || rhs,
+                    _ => lhs ^ rhs,
+                })]
+            }
+            "bool.not" => vec![Value::Bool(!frame.bool(&op, 0)?)],
+            "bool.assert" | "constrain.eq" | "constrain.in" => {
+                if self.check {
+                    self.check_constraint(frame, &op, name)?;
+                }
+                vec![]
+            }
+            "cast.tofelt" => {
+                let field = self.field_of(&op, op.result(0)?.r#type())?;
+                let value = frame.int(&op, 0)?;
+                vec![Value::Felt(field.from_signed(&value.into()))]
+            }
+            "cast.toindex" => {
+                let field = self.field_of(&op, op.operand(0)?.r#type())?;
+                let value = field.reduce(&frame.felt(&op, 0)?);
+                let value = i64::try_from(&value)
+                    .map_err(|_| fail(&op, format!("{value} does not fit in an index")))?;
+                vec![Value::Int(value)]
+            }
+            "llzk.nondet" => vec![self.default_value(&op, op.result(0)?.r#type())?],
+            "arith.constant" => {
+                let value = IntegerAttribute::try_from(op.attribute("value")?)
+                    .map_err(|_| fail(&op, "only integer constants are supported"))?
+                    .value();
+                vec![if is_i1(op.result(0)?.r#type()) {
+                    Value::Bool(value & 1 != 0)
+                } else {
+                    Value::Int(value)
+                }]
+            }
+            "arith.addi" | "arith.subi" | "arith.muli" | "arith.divsi" | "arith.divui"
+            | "arith.remsi" | "arith.remui" | "arith.andi" | "arith.ori" | "arith.xori" => {
+                vec![self.int_binary(frame, &op, name)?]
+            }
+            "arith.cmpi" => {
+                let (lhs, rhs) = (frame.int(&op, 0)?, frame.int(&op, 1)?);
+                let predicate = IntegerAttribute::try_from(op.attribute("predicate")?)
+                    .map_err(|_| fail(&op, "malformed comparison predicate"))?
+                    .value();
+                let (ulhs, urhs) = (lhs as u64, rhs as u64);
+                vec![Value::Bool(match predicate {
+                    0 => lhs == rhs,
+                    1 => lhs != rhs,
+                    2 => lhs < rhs,
+                    3 => lhs <= rhs,
+                    4 => lhs > rhs,
+                    5 => lhs >= rhs,
+                    6 => ulhs < urhs,
+                    7 => ulhs <= urhs,
+                    8 => ulhs > urhs,
+                    9 => ulhs >= urhs,
+                    _ => return Err(fail(&op, "unknown comparison predicate")),
+                })]
+            }
+            "arith.select" => {
+                let index = if frame.bool(&op, 0)? { 1 } else { 2 };
+                vec![frame.operand(&op, index)?]
+            }
+            "arith.index_cast" | "arith.index_castui" | "arith.extui" | "arith.extsi"
+            | "arith.trunci" => {
+                let value = frame.int(&op, 0)?;
+                vec![if is_i1(op.result(0)?.r#type()) {
+                    Value::Bool(value & 1 != 0)
+                } else {
+                    Value::Int(value)
+                }]
+            }
+            "array.new" => {
+                let dims = self.array_dims(&op, op.result(0)?.r#type())?;
+                let elements = frame.operands(&op)?;
+                if elements.is_empty() {
+                    vec![ArrayValue::uninit(dims).into()]
+                } else if elements.len() == dims.iter().product::<usize>() {
+                    vec![ArrayValue::new(dims, elements).into()]
+                } else {
+                    return Err(fail(
+                        &op,
+                        "arrays with dimensions given by map operands are not supported",
+                    ));
+                }
+            }
+            "array.read" => {
+                let array = frame.array(&op, 0)?;
+                let indices = frame.indices(&op, 1..op.operand_count())?;
+                if indices.len() != array.dims().len() || array.slice_range(&indices).is_none() {
+                    return Err(fail(&op, format!("index {indices:?} out of bounds")));
+                }
+                vec![
+                    array
+                        .get(&indices)
+                        .ok_or_else(|| fail(&op, "read of an uninitialized array element"))?,
+                ]
+            }
+            "array.extract" => {
+                let array = frame.array(&op, 0)?;
+                let indices = frame.indices(&op, 1..op.operand_count())?;
+                vec![
+                    array
+                        .extract(&indices)
+                        .ok_or_else(|| fail(&op, format!("index {indices:?} out of bounds")))?
+                        .into(),
+                ]
+            }
+            "array.write" | "array.insert" => {
+                let last = op.operand_count() - 1;
+                let array = frame.array(&op, 0)?;
+                let indices = frame.indices(&op, 1..last)?;
+                let value = frame.operand(&op, last)?;
+                let written = match (name, value) {
+                    ("array.write", value) => array.set(&indices, value),
+                    (_, Value::Array(sub)) => array.insert(&indices, &sub),
+                    (_, value) => {
+                        return Err(fail(
+                            &op,
+                            format!("expected array value but got {}", value.kind()),
+                        ));
+                    }
+                };
+                if !written {
+                    return Err(fail(&op, format!("index {indices:?} out of bounds")));
+                }
+                vec![]
+            }
+            "array.len" => {
+                let dims = frame.array(&op, 0)?.dims();
+                let dim = frame.indices(&op, 1..2)?[0];
+                let len = dims
+                    .get(dim)
+                    .ok_or_else(|| fail(&op, format!("array has no dimension {dim}")))?;
+                vec![Value::Int(
+                    i64::try_from(*len).expect("array length fits in i64"),
+                )]
+            }
+            "pod.new" => vec![self.new_pod(frame, &op)?.into()],
+            "pod.read" => {
+                let pod = frame.pod(&op, 0)?;
+                let record = self.symbol_attr(&op, "record_name")?;
+                vec![
+                    pod.get(&record).ok_or_else(|| {
+                        fail(&op, format!("read of uninitialized record '{record}'"))
+                    })?,
+                ]
+            }
+            "pod.write" => {
+                let pod = frame.pod(&op, 0)?;
+                let record = self.symbol_attr(&op, "record_name")?;
+                if !pod.set(&record, frame.operand(&op, 1)?) {
+                    return Err(fail(&op, format!("pod has no record '{record}'")));
+                }
+                vec![]
+            }
+            "struct.new" => {
+                let ty = StructType::try_from(op.result(0)?.r#type())?;
+                let root = unsafe { OperationRef::from_raw(root_operation(op.to_raw())) };
+                let lookup = ty.lookup_definition(&root)?;
+                let def = lookup
+                    .operation()
+                    .ok_or_else(|| Error::SymbolNotFound(ty.name().to_string()))?;
+                let members = StructDefOpRef::try_from(def)?
+                    .member_defs()
+                    .iter()
+                    .map(|member| member.member_name().to_owned())
+                    .collect::<Vec<_>>();
+                vec![StructInstance::new(ty.name().to_string(), members).into()]
+            }
+            "struct.readm" => {
+                let instance = frame.instance(&op, 0)?;
+                let member = self.symbol_attr(&op, "member_name")?;
+                vec![instance.member(&member).ok_or_else(|| {
+                    if instance.has_member(&member) {
+                        fail(&op, format!("read of @{member} before it was written"))
+                    } else {
+                        fail(&op, format!("{} has no member @{member}", instance.name()))
+                    }
+                })?]
+            }
+            "struct.writem" => {
+                let instance = frame.instance(&op, 0)?;
+                let member = self.symbol_attr(&op, "member_name")?;
+                if !instance.set_member(&member, frame.operand(&op, 1)?) {
+                    return Err(fail(
+                        &op,
+                        format!("{} has no member @{member}", instance.name()),
+                    ));
+                }
+                vec![]
+            }
+            "function.call" => {
+                let call = CallOpRef::try_from(op)?;
+                let callee = call.callee()?;
+                let target = resolve_symbol(op.to_raw(), &callee)?
+                    .ok_or_else(|| Error::SymbolNotFound(callee.to_string()))?;
+                let args = (0..call.arg_operand_count())
+                    .map(|index| frame.value(&op, call.arg_operand_at(index)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Calls to the `@constrain` function of a subcomponent extend the member path
+                // with the members the subcomponent was read from.
+                let path = if call.callee_is_struct_constrain() && call.arg_operand_count() > 0 {
+                    self.subcomponent_path(frame, call.arg_operand_at(0))?
+                } else {
+                    vec![]
+                };
+                let pushed = path.len();
+                self.member_path.borrow_mut().extend(path);
+                let results = self.call(FuncDefOpRef::try_from(target)?, args);
+                let len = self.member_path.borrow().len() - pushed;
+                self.member_path.borrow_mut().truncate(len);
+                results?
+            }
+            "scf.if" => {
+                let region = if frame.bool(&op, 0)? { 0 } else { 1 };
+                self.exec_nested(frame, &op, region, vec![])?
+            }
+            "scf.for" => {
+                let (lower, upper, step) =
+                    (frame.int(&op, 0)?, frame.int(&op, 1)?, frame.int(&op, 2)?);
+                if step <= 0 {
+                    return Err(fail(&op, "loop step must be positive"));
+                }
+                let mut iter_args = (3..op.operand_count())
+                    .map(|index| frame.operand(&op, index))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut iv = lower;
+                while iv < upper {
+                    let mut args = vec![Value::Int(iv)];
+                    args.extend(iter_args);
+                    iter_args = self.exec_nested(frame, &op, 0, args)?;
+                    let Some(next) = iv.checked_add(step) else {
+                        break;
+                    };
+                    iv = next;
+                }
+                iter_args
+            }
+            "scf.while" => {
+                let before = op.region(0)?.first_block().ok_or(Error::BlockExpected(0))?;
+                let mut args = frame.operands(&op)?;
+                loop {
+                    match self.exec_block(frame, before, args)? {
+                        Flow::Condition(true, values) => {
+                            args = self.exec_nested(frame, &op, 1, values)?;
+                        }
+                        Flow::Condition(false, values) => break values,
+                        _ => {
+                            return Err(fail(&op, "condition region did not end with a condition"));
+                        }
+                    }
+                }
+            }
+            _ => return Err(fail(&op, "operation is not supported by the interpreter")),
+        };
+        frame.set_results(&op, results)?;
+        Ok(None)
+    }
+
+    /// Evaluates a constraint operation and records it if it does not hold.
+    fn check_constraint<'c: 'a, 'a>(
+        &self,
+        frame: &Frame,
+        op: &OperationRef<'c, 'a>,
+        name: &str,
+    ) -> Result<(), Error> {
+        let holds = match name {
+            "bool.assert" => frame.bool(op, 0)?,
+            "constrain.eq" => {
+                let field = self.field_of(op, op.operand(0)?.r#type())?;
+                same_value(field, &frame.operand(op, 0)?, &frame.operand(op, 1)?)
+            }
+            _ => {
+                let field = self.field_of(op, op.operand(0)?.r#type())?;
+                array_contains(field, &frame.array(op, 0)?, &frame.operand(op, 1)?)
+            }
+        };
+        if !holds {
+            self.violations.borrow_mut().push(ConstraintViolation {
+                op: name.to_owned(),
+                location: op.location().to_string(),
+                operands: frame.operands(op)?,
+                member_path: self.member_path.borrow().clone(),
+                message: op.attribute("msg").ok().and_then(attr_name),
+            });
+        }
+        Ok(())
+    }
+
+    fn felt_binary<'c: 'a, 'a>(
+        &self,
+        frame: &Frame,
+        op: &OperationRef<'c, 'a>,
+        name: &str,
+    ) -> Result<Value, Error> {
+        let field = self.field_of(op, op.result(0)?.r#type())?;
+        let (lhs, rhs) = (frame.felt(op, 0)?, frame.felt(op, 1)?);
+        let result = match name {
+            "felt.add" => Some(field.add(&lhs, &rhs)),
+            "felt.sub" => Some(field.sub(&lhs, &rhs)),
+            "felt.mul" => Some(field.mul(&lhs, &rhs)),
+            "felt.pow" => Some(field.pow(&lhs, &rhs)),
+            "felt.bit_and" => Some(field.bit_and(&lhs, &rhs)),
+            "felt.bit_or" => Some(field.bit_or(&lhs, &rhs)),
+            "felt.bit_xor" => Some(field.bit_xor(&lhs, &rhs)),
+            "felt.div" => field.div(&lhs, &rhs),
+            "felt.uintdiv" => field.uintdiv(&lhs, &rhs),
+            "felt.sintdiv" => field.sintdiv(&lhs, &rhs),
+            "felt.umod" => field.umod(&lhs, &rhs),
+            "felt.smod" => field.smod(&lhs, &rhs),
+            "felt.shl" => Some(field.shl(&lhs, &rhs)),
+            _ => Some(field.shr(&lhs, &rhs)),
+        };
+        result
+            .map(Value::Felt)
+            .ok_or_else(|| fail(op, "division by zero"))
+    }
+
+    fn int_binary<'c: 'a, 'a>(
+        &self,
+        frame: &Frame,
+        op: &OperationRef<'c, 'a>,
+        name: &str,
+    ) -> Result<Value, Error> {
+        if let (Value::Bool(lhs), Value::Bool(rhs)) = (frame.operand(op, 0)?, frame.operand(op, 1)?)
+        {
+            return match name {
+                "arith.andi" | "arith.muli" => Ok(Value::Bool(lhs && rhs)),
+                "arith.ori" => Ok(Value::Bool(lhs || rhs)),
+                "arith.xori" | "arith.addi" | "arith.subi" => Ok(Value::Bool(lhs ^ rhs)),
+                _ => Err(fail(op, "unsupported operation on i1 values")),
+            };
+        }
+        let (lhs, rhs) = (frame.int(op, 0)?, frame.int(op, 1)?);
+        let unsigned = |f: fn(u64, u64) -> Option<u64>| f(lhs as u64, rhs as u64).map(|v| v as i64);
+        let result = match name {
+            "arith.addi" => Some(lhs.wrapping_add(rhs)),
+            "arith.subi" => Some(lhs.wrapping_sub(rhs)),
+            "arith.muli" => Some(lhs.wrapping_mul(rhs)),
+            "arith.andi" => Some(lhs & rhs),
+            "arith.ori" => Some(lhs | rhs),
+            "arith.xori" => Some(lhs ^ rhs),
+            "arith.divsi" => lhs.checked_div(rhs),
+            "arith.remsi" => lhs.checked_rem(rhs),
+            "arith.divui" => unsigned(u64::checked_div),
+            _ => unsigned(u64::checked_rem),
+        };
+        result
+            .map(Value::Int)
+            .ok_or_else(|| fail(op, "division by zero"))
+    }
+
+    /// Returns the members that lead to a subcomponent, following the chain of `struct.readm`,
+    /// `array.read` and `array.extract` operations it was read through. Elements of arrays of
+    /// subcomponents are written with their indices, like `subs[1]`. The chain stops at the first
+    /// value computed in another way, such as an argument of the function.
+    fn subcomponent_path<'c: 'a, 'a>(
+        &self,
+        frame: &Frame,
+        mut value: melior::ir::Value<'c, 'a>,
+    ) -> Result<Vec<String>, Error> {
+        let (mut path, mut indices) = (vec![], vec![]);
+        while let Ok(result) = OperationResult::try_from(value) {
+            let owner = result.owner();
+            if crate::operation::isa(&owner, "struct.readm") {
+                let member = self.symbol_attr(&owner, "member_name")?;
+                let suffix = indices
+                    .drain(..)
+                    .map(|index| format!("[{index}]"))
+                    .collect::<String>();
+                path.push(format!("{member}{suffix}"));
+            } else if crate::operation::isa(&owner, "array.read")
+                || crate::operation::isa(&owner, "array.extract")
+            {
+                let Ok(mut outer) = frame.indices(&owner, 1..owner.operand_count()) else {
+                    break;
+                };
+                outer.append(&mut indices);
+                indices = outer;
+            } else {
+                break;
+            }
+            value = owner.operand(0)?;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Reads an attribute that holds the name of a member or record.
+    fn symbol_attr<'c: 'a, 'a>(
+        &self,
+        op: &OperationRef<'c, 'a>,
+        name: &str,
+    ) -> Result<String, Error> {
+        attr_name(op.attribute(name)?)
+            .ok_or_else(|| fail(op, format!("malformed '{name}' attribute")))
+    }
+
+    /// Returns the dimensions of an array type, which must be concrete.
+    fn array_dims<'c: 'a, 'a>(
+        &self,
+        op: &OperationRef<'c, 'a>,
+        ty: Type<'c>,
+    ) -> Result<Vec<usize>, Error> {
+        let ty = ArrayType::try_from(ty)?;
+        (0..ty.num_dims())
+            .map(|index| {
+                IntegerAttribute::try_from(ty.dim(index))
+                    .ok()
+                    .and_then(|dim| usize::try_from(dim.value()).ok())
+                    .ok_or_else(|| fail(op, "array dimensions must be concrete"))
+            })
+            .collect()
+    }
+
+    /// Creates a pod with the records of the type and initializes the records given as operands.
+    fn new_pod<'c: 'a, 'a>(
+        &self,
+        frame: &Frame,
+        op: &OperationRef<'c, 'a>,
+    ) -> Result<PodValue, Error> {
+        let ty = PodType::try_from(op.result(0)?.r#type())?;
+        let records = ty
+            .records()
+            .iter()
+            .map(|record| Ok(record.name().as_string_ref().as_str()?.to_owned()))
+            .collect::<Result<Vec<String>, Error>>()?;
+        let values = frame.operands(op)?;
+        let pod = PodValue::new(records.iter().map(|name| (name.clone(), None)).collect());
+        if values.is_empty() {
+            return Ok(pod);
+        }
+
+        // The names of the initialized records are held in an array attribute.
+        let names = op
+            .attributes()
+            .filter_map(|(_, attr)| ArrayAttribute::try_from(attr).ok())
+            .filter(|array| array.len() == values.len())
+            .find_map(|array| array.into_iter().map(attr_name).collect::<Option<Vec<_>>>())
+            .or_else(|| (records.len() == values.len()).then_some(records))
+            .ok_or_else(|| fail(op, "cannot determine the initialized records"))?;
+        for (name, value) in names.iter().zip(values) {
+            if !pod.set(name, value) {
+                return Err(fail(op, format!("pod has no record '{name}'")));
+            }
+        }
+        Ok(pod)
+    }
+
+    /// Returns the value of an unconstrained value of the given type.
+    fn default_value<'c: 'a, 'a>(
+        &self,
+        op: &OperationRef<'c, 'a>,
+        ty: Type<'c>,
+    ) -> Result<Value, Error> {
+        if crate::dialect::felt::is_felt_type(ty) {
+            Ok(Value::Felt(BigUint::ZERO))
+        } else if is_i1(ty) {
+            Ok(Value::Bool(false))
+        } else if ty.is_index() || ty.is_integer() {
+            Ok(Value::Int(0))
+        } else if crate::dialect::array::is_array_type(ty) {
+            Ok(ArrayValue::uninit(self.array_dims(op, ty)?).into())
+        } else if let Ok(pod) = PodType::try_from(ty) {
+            let records = pod
+                .records()
+                .iter()
+                .map(|record| Ok((record.name().as_string_ref().as_str()?.to_owned(), None)))
+                .collect::<Result<Vec<_>, Error>>()?;
+            Ok(PodValue::new(records).into())
+        } else {
+            Err(fail(op, format!("cannot create a value of type {ty}")))
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Helpers for reading parameters of attributes from their textual form.
+//!
+//! Used for parameters that are not exposed through the C API.
+
+use crate::dialect::bool::CmpPredicate;
+use melior::ir::Attribute;
+
+/// Splits the text into identifier-like tokens, skipping the contents of quoted strings.
+fn tokens(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let is_ident = |c: u8| c.is_ascii_alphanumeric() || c == b'_';
+    let mut tokens = vec![];
+    let mut in_quote = false;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c == b'"' {
+            in_quote = !in_quote;
+            pos += 1;
+        } else if in_quote || !is_ident(c) {
+            pos += 1;
+        } else {
+            let start = pos;
+            while pos < bytes.len() && is_ident(bytes[pos]) {
+                pos += 1;
+            }
+            tokens.push(&text[start..pos]);
+        }
+    }
+    tokens
+}
+
+/// Reads the predicate of a `FeltCmpPredicateAttr`.
+pub(crate) fn cmp_predicate(attr: Attribute) -> Option<CmpPredicate> {
+    let text = attr.to_string();
+    tokens(&text)
+        .into_iter()
+        .rev()
+        .find_map(|token| match token {
+            "eq" => Some(CmpPredicate::Eq),
+            "ne" => Some(CmpPredicate::Ne),
+            "lt" => Some(CmpPredicate::Lt),
+            "le" => Some(CmpPredicate::Le),
+            "gt" => Some(CmpPredicate::Gt),
+            "ge" => Some(CmpPredicate::Ge),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_skip_quoted_strings() {
+        assert_eq!(tokens(r#"!felt.type<"bn128">"#), vec!["felt", "type"]);
+        assert_eq!(tokens("#bool<cmp ge>"), vec!["bool", "cmp", "ge"]);
+    }
+}
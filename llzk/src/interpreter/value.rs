@@ -0,0 +1,454 @@
+//! Runtime values manipulated by the interpreter.
+
+use num_bigint::BigUint;
+use std::{cell::RefCell, fmt, rc::Rc};
+
+/// A concrete value of an LLZK type.
+///
+/// Aggregates (arrays, pods and structs) have reference semantics, same as in the IR: cloning the
+/// value yields another handle to the same storage, so writes through one handle are visible
+/// through the others.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A field element (`!felt.type`). Not necessarily reduced.
+    Felt(BigUint),
+    /// A boolean (`i1`).
+    Bool(bool),
+    /// An `index` or builtin integer value.
+    Int(i64),
+    /// An `!array.type` value.
+    Array(ArrayValue),
+    /// A `!pod.type` value.
+    Pod(PodValue),
+    /// A `!struct.type` value.
+    Struct(StructInstance),
+}
+
+impl Value {
+    /// Creates a felt value.
+    pub fn felt(value: impl Into<BigUint>) -> Self {
+        Self::Felt(value.into())
+    }
+
+    /// Returns the felt value, if this is a felt.
+    pub fn as_felt(&self) -> Option<&BigUint> {
+        match self {
+            Self::Felt(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean value, if this is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer value, if this is an integer.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the array, if this is an array.
+    pub fn as_array(&self) -> Option<&ArrayValue> {
+        match self {
+            Self::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the pod, if this is a pod.
+    pub fn as_pod(&self) -> Option<&PodValue> {
+        match self {
+            Self::Pod(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the struct instance, if this is a struct.
+    pub fn as_struct(&self) -> Option<&StructInstance> {
+        match self {
+            Self::Struct(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a short name of the kind of value, used in error messages.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Felt(_) => "felt",
+            Self::Bool(_) => "bool",
+            Self::Int(_) => "integer",
+            Self::Array(_) => "array",
+            Self::Pod(_) => "pod",
+            Self::Struct(_) => "struct",
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(value: BigUint) -> Self {
+        Self::Felt(value)
+    }
+}
+
+impl From<ArrayValue> for Value {
+    fn from(value: ArrayValue) -> Self {
+        Self::Array(value)
+    }
+}
+
+impl From<PodValue> for Value {
+    fn from(value: PodValue) -> Self {
+        Self::Pod(value)
+    }
+}
+
+impl From<StructInstance> for Value {
+    fn from(value: StructInstance) -> Self {
+        Self::Struct(value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Felt(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Array(value) => fmt::Display::fmt(value, f),
+            Self::Pod(value) => fmt::Display::fmt(value, f),
+            Self::Struct(value) => fmt::Display::fmt(value, f),
+        }
+    }
+}
+
+/// Writes an optional element, using `_` for uninitialized ones.
+fn fmt_slot(slot: &Option<Value>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match slot {
+        Some(value) => fmt::Display::fmt(value, f),
+        None => write!(f, "_"),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ArrayData {
+    dims: Vec<usize>,
+    elements: Vec<Option<Value>>,
+}
+
+/// A multi-dimensional array with elements stored in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayValue(Rc<RefCell<ArrayData>>);
+
+impl ArrayValue {
+    /// Creates an array with the given dimensions and elements in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements does not match the dimensions.
+    pub fn new(dims: Vec<usize>, elements: Vec<Value>) -> Self {
+        assert_eq!(
+            dims.iter().product::<usize>(),
+            elements.len(),
+            "number of elements does not match the array dimensions"
+        );
+        Self::from_slots(dims, elements.into_iter().map(Some).collect())
+    }
+
+    /// Creates an array with the given dimensions where all the elements are uninitialized.
+    pub fn uninit(dims: Vec<usize>) -> Self {
+        let len = dims.iter().product();
+        Self::from_slots(dims, vec![None; len])
+    }
+
+    pub(crate) fn from_slots(dims: Vec<usize>, elements: Vec<Option<Value>>) -> Self {
+        Self(Rc::new(RefCell::new(ArrayData { dims, elements })))
+    }
+
+    /// Returns the dimensions of the array.
+    pub fn dims(&self) -> Vec<usize> {
+        self.0.borrow().dims.clone()
+    }
+
+    /// Returns the total number of elements.
+    pub fn len(&self) -> usize {
+        self.0.borrow().elements.len()
+    }
+
+    /// Returns true if the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the elements in row-major order. Uninitialized elements are `None`.
+    pub fn elements(&self) -> Vec<Option<Value>> {
+        self.0.borrow().elements.clone()
+    }
+
+    /// Returns the range of flat positions addressed by a prefix of indices, or `None` if any
+    /// index is out of bounds or there are more indices than dimensions.
+    pub(crate) fn slice_range(&self, indices: &[usize]) -> Option<std::ops::Range<usize>> {
+        let data = self.0.borrow();
+        if indices.len() > data.dims.len() {
+            return None;
+        }
+        let mut start = 0;
+        let mut stride: usize = data.dims.iter().product();
+        for (index, dim) in indices.iter().zip(&data.dims) {
+            if index >= dim {
+                return None;
+            }
+            stride /= dim;
+            start += index * stride;
+        }
+        Some(start..start + stride)
+    }
+
+    /// Returns the element at the given indices, or `None` if the indices are out of bounds or
+    /// the element is uninitialized.
+    pub fn get(&self, indices: &[usize]) -> Option<Value> {
+        if indices.len() != self.0.borrow().dims.len() {
+            return None;
+        }
+        let range = self.slice_range(indices)?;
+        self.0.borrow().elements[range.start].clone()
+    }
+
+    /// Writes the element at the given indices. Returns `false` if the indices are out of bounds.
+    pub fn set(&self, indices: &[usize], value: Value) -> bool {
+        if indices.len() != self.0.borrow().dims.len() {
+            return false;
+        }
+        match self.slice_range(indices) {
+            Some(range) => {
+                self.0.borrow_mut().elements[range.start] = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Copies the sub-array at the given prefix of indices into a new array.
+    pub(crate) fn extract(&self, indices: &[usize]) -> Option<Self> {
+        let range = self.slice_range(indices)?;
+        let data = self.0.borrow();
+        Some(Self::from_slots(
+            data.dims[indices.len()..].to_vec(),
+            data.elements[range].to_vec(),
+        ))
+    }
+
+    /// Copies the elements of `sub` into the sub-array at the given prefix of indices.
+    pub(crate) fn insert(&self, indices: &[usize], sub: &Self) -> bool {
+        let Some(range) = self.slice_range(indices) else {
+            return false;
+        };
+        let elements = sub.elements();
+        if elements.len() != range.len() {
+            return false;
+        }
+        self.0.borrow_mut().elements[range].clone_from_slice(&elements);
+        true
+    }
+}
+
+impl fmt::Display for ArrayValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (n, slot) in self.0.borrow().elements.iter().enumerate() {
+            if n > 0 {
+                write!(f, ", ")?;
+            }
+            fmt_slot(slot, f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// A plain-old-data record with named fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodValue(Rc<RefCell<Vec<(String, Option<Value>)>>>);
+
+impl PodValue {
+    /// Creates a pod with the given records. Records without a value are uninitialized.
+    pub fn new(records: Vec<(String, Option<Value>)>) -> Self {
+        Self(Rc::new(RefCell::new(records)))
+    }
+
+    /// Returns the names of the records in declaration order.
+    pub fn record_names(&self) -> Vec<String> {
+        self.0
+            .borrow()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Returns the value of the record, or `None` if there is no such record or it is
+    /// uninitialized.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.0
+            .borrow()
+            .iter()
+            .find(|(record, _)| record == name)
+            .and_then(|(_, value)| value.clone())
+    }
+
+    /// Writes the value of the record. Returns `false` if there is no such record.
+    pub fn set(&self, name: &str, value: Value) -> bool {
+        match self
+            .0
+            .borrow_mut()
+            .iter_mut()
+            .find(|(record, _)| record == name)
+        {
+            Some((_, slot)) => {
+                *slot = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for PodValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (n, (name, slot)) in self.0.borrow().iter().enumerate() {
+            if n > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{name}: ")?;
+            fmt_slot(slot, f)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct StructData {
+    name: String,
+    members: Vec<(String, Option<Value>)>,
+}
+
+/// An instance of a struct, as created by `struct.new` and filled by `struct.writem`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructInstance(Rc<RefCell<StructData>>);
+
+impl StructInstance {
+    /// Creates an instance of the named struct with all the given members uninitialized.
+    pub fn new(name: impl Into<String>, members: impl IntoIterator<Item = String>) -> Self {
+        Self(Rc::new(RefCell::new(StructData {
+            name: name.into(),
+            members: members.into_iter().map(|member| (member, None)).collect(),
+        })))
+    }
+
+    /// Returns the name of the struct type this is an instance of.
+    pub fn name(&self) -> String {
+        self.0.borrow().name.clone()
+    }
+
+    /// Returns the value of the member, or `None` if there is no such member or it was not
+    /// written.
+    pub fn member(&self, name: &str) -> Option<Value> {
+        self.0
+            .borrow()
+            .members
+            .iter()
+            .find(|(member, _)| member == name)
+            .and_then(|(_, value)| value.clone())
+    }
+
+    /// Returns true if the struct has a member with the given name.
+    pub fn has_member(&self, name: &str) -> bool {
+        self.0
+            .borrow()
+            .members
+            .iter()
+            .any(|(member, _)| member == name)
+    }
+
+    /// Writes the value of the member. Returns `false` if there is no such member.
+    pub fn set_member(&self, name: &str, value: Value) -> bool {
+        match self
+            .0
+            .borrow_mut()
+            .members
+            .iter_mut()
+            .find(|(member, _)| member == name)
+        {
+            Some((_, slot)) => {
+                *slot = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a copy of the members in declaration order. Members that were not written are
+    /// `None`.
+    pub fn members(&self) -> Vec<(String, Option<Value>)> {
+        self.0.borrow().members.clone()
+    }
+}
+
+impl fmt::Display for StructInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.0.borrow();
+        write!(f, "{} {{", data.name)?;
+        for (n, (name, slot)) in data.members.iter().enumerate() {
+            if n > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, " {name}: ")?;
+            fmt_slot(slot, f)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_row_major_indexing() {
+        let array = ArrayValue::new(vec![2, 3], (0u8..6).map(Value::felt).collect());
+        assert_eq!(array.get(&[1, 0]), Some(Value::felt(3u8)));
+        assert_eq!(array.get(&[0, 2]), Some(Value::felt(2u8)));
+        assert_eq!(array.get(&[2, 0]), None);
+        assert_eq!(array.get(&[0]), None);
+    }
+
+    #[test]
+    fn array_extract_and_insert() {
+        let array = ArrayValue::new(vec![2, 2], (0u8..4).map(Value::felt).collect());
+        let row = array.extract(&[1]).unwrap();
+        assert_eq!(row.dims(), vec![2]);
+        assert_eq!(row.get(&[0]), Some(Value::felt(2u8)));
+        assert!(array.insert(&[0], &row));
+        assert_eq!(array.get(&[0, 1]), Some(Value::felt(3u8)));
+    }
+
+    #[test]
+    fn aggregates_share_storage() {
+        let instance = StructInstance::new("@A", ["x".to_string()]);
+        let alias = instance.clone();
+        assert!(alias.set_member("x", Value::felt(7u8)));
+        assert_eq!(instance.member("x"), Some(Value::felt(7u8)));
+        assert!(!alias.set_member("y", Value::felt(7u8)));
+    }
+}
@@ -0,0 +1,80 @@
+//! Constraints that fail when evaluated on a witness.
+
+use super::Value;
+use std::fmt;
+
+/// A constraint operation that does not hold for the values it was evaluated on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// Name of the constraint operation (`constrain.eq`, `constrain.in` or `bool.assert`).
+    pub op: String,
+    /// Location of the constraint operation.
+    pub location: String,
+    /// Values of the operands of the constraint.
+    pub operands: Vec<Value>,
+    /// Members that lead from the checked struct to the subcomponent whose `@constrain` function
+    /// contains the constraint, with the indices of the elements of arrays of subcomponents, like
+    /// `subs[1]`. Empty if the constraint is in the checked struct itself.
+    pub member_path: Vec<String>,
+    /// Message attached to the constraint, if any.
+    pub message: Option<String>,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: '{}' does not hold", self.location, self.op)?;
+        if !self.member_path.is_empty() {
+            let path = self
+                .member_path
+                .iter()
+                .map(|member| format!("@{member}"))
+                .collect::<Vec<_>>();
+            write!(f, " in {}", path.join("."))?;
+        }
+        let operands = self
+            .operands
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(f, " for ({})", operands.join(", "))?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_includes_path_and_operands() {
+        let violation = ConstraintViolation {
+            op: "constrain.eq".to_owned(),
+            location: "loc(unknown)".to_owned(),
+            operands: vec![Value::felt(1u8), Value::felt(2u8)],
+            member_path: vec!["a".to_owned(), "b".to_owned()],
+            message: None,
+        };
+        assert_eq!(
+            violation.to_string(),
+            "loc(unknown): 'constrain.eq' does not hold in @a.@b for (1, 2)"
+        );
+    }
+
+    #[test]
+    fn display_includes_message() {
+        let violation = ConstraintViolation {
+            op: "bool.assert".to_owned(),
+            location: "loc(unknown)".to_owned(),
+            operands: vec![Value::Bool(false)],
+            member_path: vec![],
+            message: Some("must be set".to_owned()),
+        };
+        assert_eq!(
+            violation.to_string(),
+            "loc(unknown): 'bool.assert' does not hold for (false): must be set"
+        );
+    }
+}
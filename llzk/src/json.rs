@@ -0,0 +1,461 @@
+//! Minimal JSON documents for the serializable descriptions of circuits.
+//!
+//! Objects keep the order of their keys, so the output only depends on the order in which the
+//! values are built. Parsing is only needed by the readers of witness files, so it requires the
+//! `bigint` feature.
+
+use std::fmt::{self, Display, Formatter, Write as _};
+
+#[cfg(feature = "bigint")]
+use crate::error::Error;
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    /// A number, stored as its text.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Creates an object with the entries in the given order.
+    pub(crate) fn object<K: Into<String>>(entries: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Self::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    /// Creates an array.
+    pub(crate) fn array<T: Into<Json>>(items: impl IntoIterator<Item = T>) -> Self {
+        Self::Array(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Parses a JSON document.
+    #[cfg(feature = "bigint")]
+    pub(crate) fn parse(text: &str) -> Result<Self, Error> {
+        let mut parser = Parser { text, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error("unexpected characters after the document"));
+        }
+        Ok(value)
+    }
+
+    /// Returns the name of the kind of value, used in error messages.
+    #[cfg(feature = "bigint")]
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "a boolean",
+            Self::Number(_) => "a number",
+            Self::String(_) => "a string",
+            Self::Array(_) => "an array",
+            Self::Object(_) => "an object",
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Self::Array(_) | Self::Object(_))
+    }
+
+    fn write(&self, f: &mut Formatter, indent: usize) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Number(value) => write!(f, "{value}"),
+            Self::String(value) => write_string(f, value),
+            Self::Array(items) if items.is_empty() => write!(f, "[]"),
+            // Arrays of scalars, like dimensions, are printed on a single line.
+            Self::Array(items) if items.iter().all(Self::is_scalar) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write(f, indent)?;
+                }
+                write!(f, "]")
+            }
+            Self::Array(items) => {
+                writeln!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    write!(f, "{:width$}", "", width = indent + 2)?;
+                    item.write(f, indent + 2)?;
+                    writeln!(f, "{}", if index + 1 < items.len() { "," } else { "" })?;
+                }
+                write!(f, "{:indent$}]", "")
+            }
+            Self::Object(entries) if entries.is_empty() => write!(f, "{{}}"),
+            Self::Object(entries) => {
+                writeln!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{:width$}", "", width = indent + 2)?;
+                    write_string(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 2)?;
+                    writeln!(f, "{}", if index + 1 < entries.len() { "," } else { "" })?;
+                }
+                write!(f, "{:indent$}}}", "")
+            }
+        }
+    }
+}
+
+/// Recursive descent parser of JSON documents.
+#[cfg(feature = "bigint")]
+struct Parser<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+#[cfg(feature = "bigint")]
+impl Parser<'_> {
+    fn error(&self, msg: impl Into<String>) -> Error {
+        let before = &self.text[..self.pos];
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        Error::InvalidJson {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            msg: msg.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips the whitespace and consumes the character if it is next.
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Json, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => self.keyword(),
+            None => Err(self.error("unexpected end of the document")),
+        }
+    }
+
+    fn keyword(&mut self) -> Result<Json, Error> {
+        for (keyword, value) in [
+            ("null", Json::Null),
+            ("true", Json::Bool(true)),
+            ("false", Json::Bool(false)),
+        ] {
+            if self.text[self.pos..].starts_with(keyword) {
+                self.pos += keyword.len();
+                return Ok(value);
+            }
+        }
+        Err(self.error("expected a value"))
+    }
+
+    fn digits(&mut self) -> Result<(), Error> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("expected a digit"));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Json, Error> {
+        let start = self.pos;
+        self.eat(b'-');
+        self.digits()?;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.digits()?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            self.digits()?;
+        }
+        Ok(Json::Number(self.text[start..self.pos].to_owned()))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let code = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .filter(|digits| digits.bytes().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn escape(&mut self) -> Result<char, Error> {
+        let Some(escape) = self.peek() else {
+            return Err(self.error("unterminated string"));
+        };
+        self.pos += 1;
+        let c = match escape {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let mut code = self.hex4()?;
+                // Characters outside of the BMP are escaped as surrogate pairs.
+                if (0xd800..0xdc00).contains(&code) {
+                    if !self.text[self.pos..].starts_with("\\u") {
+                        return Err(self.error("unpaired surrogate in unicode escape"));
+                    }
+                    self.pos += 2;
+                    let low = self.hex4()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.error("unpaired surrogate in unicode escape"));
+                    }
+                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                }
+                char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        };
+        Ok(c)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if !self.eat(b'"') {
+            return Err(self.error("expected a string"));
+        }
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.text[self.pos..].chars().next() else {
+                return Err(self.error("unterminated string"));
+            };
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    out.push(self.escape()?);
+                }
+                c if u32::from(c) < 0x20 => {
+                    return Err(self.error("control character in string"));
+                }
+                c => {
+                    self.pos += c.len_utf8();
+                    out.push(c);
+                }
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, Error> {
+        self.eat(b'[');
+        let mut items = vec![];
+        if self.eat(b']') {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat(b']') {
+                return Ok(Json::Array(items));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("expected ',' or ']'"));
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, Error> {
+        self.eat(b'{');
+        let mut entries: Vec<(String, Json)> = vec![];
+        if self.eat(b'}') {
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let key = self.string()?;
+            if entries.iter().any(|(existing, _)| *existing == key) {
+                self.pos = start;
+                return Err(self.error(format!("duplicate key '{key}'")));
+            }
+            if !self.eat(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            entries.push((key, self.value()?));
+            if self.eat(b'}') {
+                return Ok(Json::Object(entries));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("expected ',' or '}'"));
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Pretty-prints the value with an indentation of two spaces.
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+impl From<i64> for Json {
+    fn from(value: i64) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn parse_documents() {
+        let json = Json::parse(
+            r#" {"a": [1, -2.5e3, true, null], "b\u00e9\ud83d\ude00": {}, "c": "x\"y"} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            Json::object([
+                (
+                    "a",
+                    Json::Array(vec![
+                        Json::Number("1".to_owned()),
+                        Json::Number("-2.5e3".to_owned()),
+                        Json::Bool(true),
+                        Json::Null,
+                    ])
+                ),
+                ("b\u{e9}\u{1f600}", Json::Object(vec![])),
+                ("c", Json::from("x\"y")),
+            ])
+        );
+        assert_eq!(Json::parse(&json.to_string()).unwrap(), json);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn parse_errors_have_positions() {
+        let err = Json::parse("{\n  \"a\": [1,]\n}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid JSON at line 2, column 11: expected a value"
+        );
+        let err = Json::parse(r#"{"a": 1, "a": 2}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid JSON at line 1, column 10: duplicate key 'a'"
+        );
+        assert!(Json::parse("[1] 2").is_err());
+        assert!(Json::parse("\"abc").is_err());
+    }
+
+    #[test]
+    fn pretty_print() {
+        let json = Json::object([
+            ("name", Json::from("a \"quoted\"\nname")),
+            ("dims", Json::array([2usize, 3])),
+            ("empty", Json::array(Vec::<Json>::new())),
+            (
+                "items",
+                Json::array([
+                    Json::object([("public", Json::from(true))]),
+                    Json::from(1u64),
+                ]),
+            ),
+        ]);
+        similar_asserts::assert_eq!(
+            json.to_string(),
+            r#"{
+  "name": "a \"quoted\"\nname",
+  "dims": [2, 3],
+  "empty": [],
+  "items": [
+    {
+      "public": true
+    },
+    1
+  ]
+}"#
+        );
+    }
+}
@@ -4,18 +4,26 @@
 use llzk_sys::llzkRegisterAllDialects;
 use melior::dialect::DialectRegistry;
 
+pub mod abi;
 pub mod affine;
+pub mod analysis;
 pub mod attributes;
 pub mod builder;
 pub mod context;
 mod diagnostics;
 pub mod dialect;
 pub mod error;
+#[cfg(feature = "bigint")]
+pub mod interpreter;
+mod json;
 mod macros;
 pub mod map_operands;
 pub mod operation;
 pub mod passes;
+#[cfg(feature = "bigint")]
+pub mod polynomial;
 pub mod prelude;
+pub mod rewrite;
 pub mod symbol_lookup;
 pub mod symbol_ref;
 pub mod symbol_table;
@@ -23,9 +31,13 @@ pub mod targets;
 #[cfg(test)]
 mod test;
 pub mod type_ext;
+pub mod typed_value;
 pub mod typing;
 pub mod utils;
 pub mod value_ext;
+pub mod visitor;
+#[cfg(feature = "bigint")]
+pub mod witness;
 
 /// Adds all LLZK dialects into the given registry.
 pub fn register_all_llzk_dialects(registry: &DialectRegistry) {
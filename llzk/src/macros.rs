@@ -233,6 +233,12 @@ macro_rules! llzk_op_type {
                 }
             }
 
+            impl<'c: 'a, 'a> $crate::operation::TypedOperationRef<'c, 'a> for [<$type Ref>]<'c, 'a> {
+                fn isa(op: &melior::ir::operation::OperationRef<'c, 'a>) -> bool {
+                    unsafe { $isa(melior::ir::operation::OperationLike::to_raw(op)) }
+                }
+            }
+
             // Mutable reference type
 
             #[doc = concat!("Represents a non-owned mutable reference to a '", $opname, "' op.")]
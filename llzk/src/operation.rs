@@ -8,14 +8,14 @@ use melior::{
     Context,
     diagnostic::DiagnosticSeverity,
     ir::{
-        Block, Operation, ValueLike,
+        Block, BlockLike, Operation, RegionLike as _, ValueLike,
         operation::{
             OperationLike, OperationMutLike, OperationRef, OperationRefMut, WalkOrder, WalkResult,
         },
     },
 };
 use mlir_sys::{MlirOperation, MlirWalkResult, mlirOperationWalk};
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData};
 
 /// Shared by non-owned operation reference wrapper types.
 pub trait OperationRefLike<'c: 'a, 'a> {
@@ -94,6 +94,141 @@ where
     impl_walk_method!(walk_rev_mut, mlirOperationWalkReverse);
 }
 
+/// Operation references that can be obtained from an [`OperationRef`] with [`TryFrom`].
+///
+/// [`isa`](Self::isa) checks whether the conversion would succeed without building its error,
+/// which makes it cheap to filter the operations of the traversal iterators.
+pub trait TypedOperationRef<'c: 'a, 'a>: TryFrom<OperationRef<'c, 'a>> {
+    /// Returns `true` iff the operation can be converted to this type.
+    fn isa(op: &OperationRef<'c, 'a>) -> bool;
+}
+
+impl<'c: 'a, 'a> TypedOperationRef<'c, 'a> for OperationRef<'c, 'a> {
+    #[inline]
+    fn isa(_: &OperationRef<'c, 'a>) -> bool {
+        true
+    }
+}
+
+/// Lazy iterator over nested operations, in pre-order, that can be converted to `T`.
+///
+/// Created by [`OperationIterExt::descendants`], [`BlockIterExt::ops_of`] and
+/// [`ModuleExt::structs`](crate::dialect::module::ModuleExt::structs). The operations that
+/// don't convert to `T` are skipped, but the operations nested in them are still visited.
+pub struct Descendants<'c, 'a, T> {
+    /// Next operation to visit on top, followed by the operations that come after its ancestors.
+    stack: Vec<OperationRef<'c, 'a>>,
+    /// Whether to visit the operations nested in an operation.
+    descend: fn(&OperationRef<'c, 'a>) -> bool,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<'c: 'a, 'a, T> Descendants<'c, 'a, T> {
+    /// Visits the operations nested in `op`, and the operations nested in those for which
+    /// `descend` returns `true`.
+    pub(crate) fn nested_in(
+        op: OperationRef<'c, 'a>,
+        descend: fn(&OperationRef<'c, 'a>) -> bool,
+    ) -> Self {
+        let mut iter = Self::new(None, descend);
+        push_nested(&mut iter.stack, op);
+        iter
+    }
+
+    fn new(
+        first: Option<OperationRef<'c, 'a>>,
+        descend: fn(&OperationRef<'c, 'a>) -> bool,
+    ) -> Self {
+        Self {
+            stack: first.into_iter().collect(),
+            descend,
+            _target: PhantomData,
+        }
+    }
+}
+
+impl<'c: 'a, 'a, T: TypedOperationRef<'c, 'a>> Iterator for Descendants<'c, 'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(op) = self.stack.pop() {
+            if let Some(next) = op.next_in_block() {
+                self.stack.push(next);
+            }
+            if (self.descend)(&op) {
+                push_nested(&mut self.stack, op);
+            }
+            if T::isa(&op) {
+                if let Ok(op) = T::try_from(op) {
+                    return Some(op);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T> fmt::Debug for Descendants<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Descendants")
+            .field("pending", &self.stack.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Pushes the first operation of each block of `op` on a stack of operations to visit in
+/// pre-order, such that the first block is on top.
+pub(crate) fn push_nested<'c: 'a, 'a>(
+    stack: &mut Vec<OperationRef<'c, 'a>>,
+    op: OperationRef<'c, 'a>,
+) {
+    let firsts = op
+        .regions()
+        .flat_map(|region| {
+            std::iter::successors(region.first_block(), |block| block.next_in_region())
+        })
+        .filter_map(|block| block.first_operation())
+        .collect::<Vec<_>>();
+    stack.extend(firsts.into_iter().rev());
+}
+
+/// Iterators over the operations nested in an operation.
+pub trait OperationIterExt<'c: 'a, 'a>: OperationLike<'c, 'a> {
+    /// Returns the operations nested in this operation, at any depth and in pre-order, that can be
+    /// converted to `T`. The operation itself is not included.
+    ///
+    /// ```ignore
+    /// let members = def.descendants::<MemberDefOpRef>().collect::<Vec<_>>();
+    /// ```
+    fn descendants<T: TypedOperationRef<'c, 'a>>(&self) -> Descendants<'c, 'a, T> {
+        Descendants::nested_in(unsafe { OperationRef::from_raw(self.to_raw()) }, |_| true)
+    }
+
+    /// Returns the operations in the regions of this operation, without descending into them, that
+    /// can be converted to `T`.
+    fn children<T: TypedOperationRef<'c, 'a>>(&self) -> Descendants<'c, 'a, T> {
+        Descendants::nested_in(unsafe { OperationRef::from_raw(self.to_raw()) }, |_| false)
+    }
+}
+
+impl<'c: 'a, 'a, O: OperationLike<'c, 'a>> OperationIterExt<'c, 'a> for O {}
+
+/// Iterators over the operations of a block.
+pub trait BlockIterExt<'c: 'a, 'a>: BlockLike<'c, 'a> {
+    /// Returns the operations of the block, in order.
+    fn ops(&self) -> Descendants<'c, 'a, OperationRef<'c, 'a>> {
+        self.ops_of()
+    }
+
+    /// Returns the operations of the block, in order, that can be converted to `T`. The operations
+    /// nested in them are not included.
+    fn ops_of<T: TypedOperationRef<'c, 'a>>(&self) -> Descendants<'c, 'a, T> {
+        Descendants::new(self.first_operation(), |_| false)
+    }
+}
+
+impl<'c: 'a, 'a, B: BlockLike<'c, 'a>> BlockIterExt<'c, 'a> for B {}
+
 /// Verifies the operation, returning an error if it failed.
 pub fn verify_operation<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>) -> Result<(), Error> {
     if op.verify() {
@@ -134,6 +269,22 @@ pub fn verify_operation_with_diags<'c: 'a, 'a>(
     result
 }
 
+/// Evaluates the `@constrain` function of the struct on a witness and returns the constraints that
+/// do not hold.
+///
+/// The witness is given by the struct instance and the inputs it was computed from. Uses the
+/// default [`Interpreter`](crate::interpreter::Interpreter); use
+/// [`Interpreter::check_constraints`](crate::interpreter::Interpreter::check_constraints) to
+/// configure the fields the constraints are evaluated over.
+#[cfg(feature = "bigint")]
+pub fn check_constraints<'c: 'a, 'a>(
+    struct_def: &impl crate::dialect::r#struct::StructDefOpLike<'c, 'a>,
+    instance: &crate::interpreter::StructInstance,
+    inputs: &[crate::interpreter::Value],
+) -> Result<Vec<crate::interpreter::ConstraintViolation>, Error> {
+    crate::interpreter::Interpreter::new().check_constraints(struct_def, instance, inputs)
+}
+
 /// Replace uses of 'of' value with the 'with' value inside the 'op' operation.
 #[inline]
 pub fn replace_uses_of_with<'c: 'a, 'a>(
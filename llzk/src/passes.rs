@@ -2,6 +2,8 @@
 
 use llzk_macro::passes;
 
+pub mod custom;
+
 passes!(
     "LLZKTransformation",
     [
@@ -0,0 +1,208 @@
+//! Custom passes written in Rust.
+//!
+//! A type that implements [`LlzkPass`] can be turned into a [`Pass`] with [`create_pass`] and
+//! added to a [`PassManager`](melior::pass::PassManager) like the passes provided by LLZK.
+//!
+//! ```ignore
+//! #[derive(Clone, Debug)]
+//! struct RemoveNondet;
+//!
+//! impl LlzkPass for RemoveNondet {
+//!     fn name(&self) -> &str {
+//!         "RemoveNondet"
+//!     }
+//!
+//!     fn argument(&self) -> &str {
+//!         "remove-nondet"
+//!     }
+//!
+//!     fn run(&mut self, op: OperationRefMut) -> Result<(), Error> {
+//!         // ...
+//!         Ok(())
+//!     }
+//! }
+//!
+//! pass_manager.add_pass(create_pass(RemoveNondet));
+//! ```
+
+use crate::error::Error;
+use melior::{
+    ContextRef,
+    dialect::DialectHandle,
+    ir::{OperationRef, OperationRefMut, operation::OperationLike as _, r#type::TypeId},
+    pass::{ExternalPass, Pass, RunExternalPass, create_external},
+};
+use mlir_sys::mlirEmitError;
+use std::{
+    any,
+    collections::HashMap,
+    ffi::CString,
+    sync::{LazyLock, Mutex},
+};
+
+/// A pass written in Rust.
+///
+/// MLIR clones the pass for every operation it runs on in parallel, so the state of the pass is
+/// not shared between runs.
+pub trait LlzkPass: Clone {
+    /// Returns the name of the pass.
+    fn name(&self) -> &str;
+
+    /// Returns the command line argument of the pass.
+    fn argument(&self) -> &str;
+
+    /// Returns a description of the pass.
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Returns the name of the operation the pass runs on, for example `builtin.module` or
+    /// `struct.def`. The default is an empty name, which lets the pass run on any operation.
+    fn anchor(&self) -> &str {
+        ""
+    }
+
+    /// Returns the dialects whose operations the pass may create.
+    fn dependent_dialects(&self) -> Vec<DialectHandle> {
+        vec![]
+    }
+
+    /// Called once before the pass runs, with the context of the IR it runs on.
+    fn initialize(&mut self, _context: ContextRef) {}
+
+    /// Runs the pass on the anchor operation.
+    ///
+    /// Returning an error emits it as a diagnostic at the location of the operation and makes the
+    /// pass manager fail.
+    fn run(&mut self, op: OperationRefMut) -> Result<(), Error>;
+}
+
+/// Creates a [`Pass`] that runs the given [`LlzkPass`].
+pub fn create_pass<P: LlzkPass + 'static>(pass: P) -> Pass {
+    let name = pass.name().to_owned();
+    let argument = pass.argument().to_owned();
+    let description = pass.description().to_owned();
+    let anchor = pass.anchor().to_owned();
+    let dialects = pass.dependent_dialects();
+    create_external(
+        Adaptor(pass),
+        pass_id::<P>(),
+        &name,
+        &argument,
+        &description,
+        &anchor,
+        &dialects,
+    )
+}
+
+/// Storage whose address identifies a pass type.
+#[repr(align(8))]
+struct PassId(#[allow(dead_code)] u8);
+
+/// Returns the MLIR type id of the pass type, which must be the same for every instance.
+fn pass_id<P: 'static>() -> TypeId<'static> {
+    static IDS: LazyLock<Mutex<HashMap<any::TypeId, &'static PassId>>> =
+        LazyLock::new(Default::default);
+
+    let mut ids = IDS.lock().unwrap_or_else(|err| err.into_inner());
+    let id = ids
+        .entry(any::TypeId::of::<P>())
+        .or_insert_with(|| Box::leak(Box::new(PassId(0))));
+    TypeId::create(*id)
+}
+
+/// Implements melior's external pass interface on top of [`LlzkPass`].
+#[derive(Clone)]
+struct Adaptor<P>(P);
+
+impl<'c, P: LlzkPass> RunExternalPass<'c> for Adaptor<P> {
+    fn initialize(&mut self, context: ContextRef<'c>) {
+        self.0.initialize(context);
+    }
+
+    fn run(&mut self, operation: OperationRef<'c, '_>, pass: ExternalPass<'_>) {
+        let op = unsafe { OperationRefMut::from_raw(operation.to_raw()) };
+        if let Err(err) = self.0.run(op) {
+            let msg =
+                CString::new(err.to_string().replace('\0', " ")).expect("nul bytes were replaced");
+            unsafe { mlirEmitError(operation.location().to_raw(), msg.as_ptr()) };
+            pass.signal_failure();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+    use melior::{
+        ir::{Attribute, Module, operation::OperationMutLike as _},
+        pass::PassManager,
+    };
+
+    /// Sets an attribute on every operation it runs on.
+    #[derive(Clone, Debug)]
+    struct Mark;
+
+    impl LlzkPass for Mark {
+        fn name(&self) -> &str {
+            "Mark"
+        }
+
+        fn argument(&self) -> &str {
+            "mark"
+        }
+
+        fn anchor(&self) -> &str {
+            "builtin.module"
+        }
+
+        fn run(&mut self, mut op: OperationRefMut) -> Result<(), Error> {
+            let ctx = op.context();
+            op.set_attribute("test.marked", Attribute::unit(unsafe { ctx.to_ref() }));
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Fail;
+
+    impl LlzkPass for Fail {
+        fn name(&self) -> &str {
+            "Fail"
+        }
+
+        fn argument(&self) -> &str {
+            "fail"
+        }
+
+        fn run(&mut self, _op: OperationRefMut) -> Result<(), Error> {
+            Err(Error::GeneralError("pass failed"))
+        }
+    }
+
+    #[test]
+    fn run_pass() {
+        let ctx = LlzkContext::new();
+        let mut module = Module::parse(&ctx, "module {}").unwrap();
+        let pm = PassManager::new(&ctx);
+        pm.add_pass(create_pass(Mark));
+        pm.run(&mut module).unwrap();
+        assert!(module.as_operation().has_attribute("test.marked"));
+    }
+
+    #[test]
+    fn failing_pass() {
+        let ctx = LlzkContext::new();
+        let mut module = Module::parse(&ctx, "module {}").unwrap();
+        let pm = PassManager::new(&ctx);
+        pm.add_pass(create_pass(Fail));
+        assert!(pm.run(&mut module).is_err());
+    }
+
+    #[test]
+    fn pass_ids() {
+        assert_eq!(pass_id::<Mark>(), pass_id::<Mark>());
+        assert_ne!(pass_id::<Mark>(), pass_id::<Fail>());
+    }
+}
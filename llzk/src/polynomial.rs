@@ -0,0 +1,739 @@
+//! Symbolic evaluation of `@constrain` functions into systems of multivariate polynomials.
+//!
+//! [`PolynomialSystem::from_struct`] evaluates the `@constrain` function of a struct, including
+//! the `@constrain` functions of the subcomponents it calls, and turns each `constrain.eq` into a
+//! [`Polynomial`] `lhs - rhs` that must be zero. The polynomials are defined over the field of the
+//! felts of the struct and have one [`Variable`] per felt member and per input.
+//!
+//! Variables are named after the path that leads to them, like in the SMT translation: `main.c`
+//! for member `@c` of the struct, `main.sub.x` for member `@x` of its subcomponent `@sub`, and
+//! `main.arg0` for its first input, unless the input has a source-level name. Members come first,
+//! depth first in the order of their definitions, followed by the inputs. An input whose name is
+//! already taken, for example by a member `@arg0`, gets primes appended until it is unique, like
+//! `main.arg0'`.
+//!
+//! A read of a column member at a row offset, like the ones created by
+//! [`readm_with_offset`](crate::dialect::r#struct::readm_with_offset), refers to another row of the
+//! table and gets its own variable, with the name of the member and the offset. Those variables
+//! come last, in the order they are first read. Offsets must be literals.
+//!
+//! The module must be flattened and its arrays lowered to scalars beforehand, for example with
+//! the flattening and array-to-scalar passes. Operations other than felt arithmetic, member reads,
+//! constraints and calls to `@constrain` functions are rejected. Divisions and powers are only
+//! supported when the divisor or the exponent is a constant, and powers of non-constant bases only
+//! up to the exponent [`MAX_EXPONENT`].
+
+use crate::{
+    dialect::{
+        felt::FeltConstAttribute,
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _},
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef},
+    },
+    error::Error,
+    interpreter::Field,
+    targets::symbolic::{self, Components, Member, constrain_func, symbol_attr, walk_constrain},
+};
+use melior::ir::{
+    Attribute, Location, OperationRef, ValueLike as _, attribute::IntegerAttribute,
+    operation::OperationLike,
+};
+use num_bigint::{BigUint, Sign};
+use std::{
+    collections::{BTreeMap, HashMap, btree_map::Entry},
+    fmt,
+    os::raw::c_void,
+};
+
+const TARGET: &str = "polynomial";
+
+/// Largest exponent of a `felt.pow` whose base is not a constant.
+pub const MAX_EXPONENT: u32 = 64;
+
+/// A product of variables, mapping variable indices to their non-zero exponents.
+pub type Monomial = BTreeMap<usize, u32>;
+
+/// Whether a variable stands for an input or a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    /// An argument of the `@constrain` function of the struct.
+    Input,
+    /// A felt member of the struct or of one of its subcomponents.
+    Member,
+}
+
+/// A variable of a polynomial system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    /// Path of the member or input, like `main.sub.x`.
+    pub name: String,
+    /// Whether the variable is an input or a member.
+    pub kind: VariableKind,
+    /// Whether the variable is a public input or a public member of the struct. Members of
+    /// subcomponents are never public.
+    pub public: bool,
+    /// Whether the variable is a member marked as a column.
+    pub column: bool,
+    /// Row offset of the read of a column, zero for the current row and for other variables.
+    pub offset: i64,
+}
+
+impl fmt::Display for Variable {
+    /// Prints the name, followed by the offset if it is not zero, like `main.x@+1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.offset == 0 {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}@{:+}", self.name, self.offset)
+        }
+    }
+}
+
+/// A polynomial that a `constrain.eq` requires to be zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<'c> {
+    terms: BTreeMap<Monomial, BigUint>,
+    location: Location<'c>,
+    attributes: Vec<(String, Attribute<'c>)>,
+}
+
+impl<'c> Polynomial<'c> {
+    /// Returns the non-zero coefficients of the polynomial, by monomial. The constant term is the
+    /// coefficient of the empty monomial.
+    pub fn terms(&self) -> &BTreeMap<Monomial, BigUint> {
+        &self.terms
+    }
+
+    /// Returns the location of the `constrain.eq` the polynomial comes from.
+    pub fn location(&self) -> Location<'c> {
+        self.location
+    }
+
+    /// Returns the attribute with the given name of the `constrain.eq` the polynomial comes from,
+    /// like a discardable attribute that a target interprets.
+    pub fn attribute(&self, name: &str) -> Option<Attribute<'c>> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, attribute)| *attribute)
+    }
+
+    /// Returns the total degree of the polynomial, zero for constant polynomials.
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().map(degree).max().unwrap_or(0)
+    }
+
+    /// Returns true if all the coefficients are zero, which means the constraint always holds.
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Evaluates the polynomial on the given values of the variables, indexed by variable.
+    ///
+    /// # Panics
+    ///
+    /// If a variable of the polynomial does not have a value.
+    pub fn evaluate(&self, field: &Field, values: &[BigUint]) -> BigUint {
+        self.terms
+            .iter()
+            .fold(BigUint::ZERO, |sum, (monomial, coeff)| {
+                let product = monomial.iter().fold(coeff.clone(), |product, (var, exp)| {
+                    field.mul(&product, &field.pow(&values[*var], &BigUint::from(*exp)))
+                });
+                field.add(&sum, &product)
+            })
+    }
+}
+
+/// The polynomials of the constraints of a struct.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolynomialSystem<'c> {
+    field: Field,
+    variables: Vec<Variable>,
+    polynomials: Vec<Polynomial<'c>>,
+}
+
+impl<'c> PolynomialSystem<'c> {
+    /// Evaluates the `@constrain` function of the struct into polynomials.
+    ///
+    /// Felt types that don't name a field are interpreted in the default field. Fails if the felts
+    /// don't all belong to the same field or if an operation is not supported.
+    pub fn from_struct<'m>(
+        def: StructDefOpRef<'c, 'm>,
+        default_field: &Field,
+    ) -> Result<Self, Error> {
+        let mut extractor = Extractor {
+            components: Components::new(TARGET, &def, default_field)?,
+            variables: vec![],
+            offsets: HashMap::new(),
+            polynomials: vec![],
+        };
+        let (main, inputs) = extractor.allocate_main(def)?;
+        extractor.evaluate_constrain(main, inputs)?;
+        Ok(Self {
+            field: extractor.components.field().clone(),
+            variables: extractor.variables,
+            polynomials: extractor.polynomials,
+        })
+    }
+
+    /// Returns the field the polynomials are defined over.
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    /// Returns the variables, indexed by variable.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Returns the polynomials, in the order of the constraints.
+    pub fn polynomials(&self) -> &[Polynomial<'c>] {
+        &self.polynomials
+    }
+
+    /// Formats the polynomial with the names of the variables, from the terms of highest degree
+    /// to the constant term. Coefficients are printed as signed integers.
+    pub fn format(&self, polynomial: &Polynomial) -> String {
+        let mut terms = polynomial.terms.iter().collect::<Vec<_>>();
+        terms.sort_by(|(a, _), (b, _)| degree(b).cmp(&degree(a)).then_with(|| a.cmp(b)));
+        let mut text = String::new();
+        for (monomial, coeff) in terms {
+            let coeff = self.field.to_signed(coeff);
+            let (sign, abs) = (coeff.sign() == Sign::Minus, coeff.magnitude());
+            text.push_str(match (text.is_empty(), sign) {
+                (true, false) => "",
+                (true, true) => "-",
+                (false, false) => " + ",
+                (false, true) => " - ",
+            });
+            let factors = monomial.iter().map(|(var, exp)| {
+                let name = &self.variables[*var];
+                if *exp == 1 {
+                    name.to_string()
+                } else {
+                    format!("{name}^{exp}")
+                }
+            });
+            let factors = (monomial.is_empty() || *abs != BigUint::from(1u8))
+                .then(|| abs.to_string())
+                .into_iter()
+                .chain(factors)
+                .collect::<Vec<_>>();
+            text.push_str(&factors.join("*"));
+        }
+        if text.is_empty() {
+            text.push('0');
+        }
+        text
+    }
+}
+
+/// Prints one polynomial per line, as an equation `... = 0`.
+impl fmt::Display for PolynomialSystem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for polynomial in &self.polynomials {
+            writeln!(f, "{} = 0", self.format(polynomial))?;
+        }
+        Ok(())
+    }
+}
+
+fn degree(monomial: &Monomial) -> u32 {
+    monomial.values().sum()
+}
+
+fn fail<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, msg: impl Into<String>) -> Error {
+    symbolic::fail(TARGET, op, msg)
+}
+
+/// Coefficients of a polynomial under construction.
+type Terms = BTreeMap<Monomial, BigUint>;
+
+fn constant(value: BigUint) -> Terms {
+    let mut terms = Terms::new();
+    if value != BigUint::ZERO {
+        terms.insert(Monomial::new(), value);
+    }
+    terms
+}
+
+fn variable(var: usize) -> Terms {
+    Terms::from([(Monomial::from([(var, 1)]), BigUint::from(1u8))])
+}
+
+/// Returns the value of the polynomial if it is constant.
+fn as_constant(terms: &Terms) -> Option<BigUint> {
+    match terms.iter().next_back() {
+        None => Some(BigUint::ZERO),
+        Some((monomial, value)) if monomial.is_empty() => Some(value.clone()),
+        Some(_) => None,
+    }
+}
+
+/// Adds the term to the coefficients, dropping the monomial if its coefficient becomes zero.
+fn add_term(field: &Field, terms: &mut Terms, monomial: Monomial, coeff: &BigUint) {
+    match terms.entry(monomial) {
+        Entry::Vacant(entry) => {
+            if *coeff != BigUint::ZERO {
+                entry.insert(coeff.clone());
+            }
+        }
+        Entry::Occupied(mut entry) => {
+            let value = field.add(entry.get(), coeff);
+            if value == BigUint::ZERO {
+                entry.remove();
+            } else {
+                *entry.get_mut() = value;
+            }
+        }
+    }
+}
+
+fn add(field: &Field, mut lhs: Terms, rhs: &Terms) -> Terms {
+    for (monomial, coeff) in rhs {
+        add_term(field, &mut lhs, monomial.clone(), coeff);
+    }
+    lhs
+}
+
+fn negate(field: &Field, terms: &Terms) -> Terms {
+    terms
+        .iter()
+        .map(|(monomial, coeff)| (monomial.clone(), field.neg(coeff)))
+        .collect()
+}
+
+fn mul(field: &Field, lhs: &Terms, rhs: &Terms) -> Terms {
+    let mut product = Terms::new();
+    for (a, x) in lhs {
+        for (b, y) in rhs {
+            let mut monomial = a.clone();
+            for (var, exp) in b {
+                *monomial.entry(*var).or_default() += exp;
+            }
+            add_term(field, &mut product, monomial, &field.mul(x, y));
+        }
+    }
+    product
+}
+
+/// Symbolic value of an SSA value.
+#[derive(Clone)]
+enum Term {
+    Felt(Terms),
+    Component(usize),
+}
+
+struct Extractor<'c, 'm> {
+    /// Components whose felt members are variables.
+    components: Components<'c, 'm, usize>,
+    variables: Vec<Variable>,
+    /// Variables of the reads of columns at non-zero row offsets, by column variable and offset.
+    offsets: HashMap<(usize, i64), usize>,
+    polynomials: Vec<Polynomial<'c>>,
+}
+
+impl<'c, 'm> Extractor<'c, 'm> {
+    fn new_variable(
+        &mut self,
+        name: String,
+        kind: VariableKind,
+        public: bool,
+        column: bool,
+    ) -> usize {
+        self.variables.push(Variable {
+            name,
+            kind,
+            public,
+            column,
+            offset: 0,
+        });
+        self.variables.len() - 1
+    }
+
+    /// Returns the variable of the column at the given row offset.
+    fn offset_variable(&mut self, var: usize, offset: i64) -> usize {
+        if offset == 0 {
+            return var;
+        }
+        *self.offsets.entry((var, offset)).or_insert_with(|| {
+            self.variables.push(Variable {
+                offset,
+                ..self.variables[var].clone()
+            });
+            self.variables.len() - 1
+        })
+    }
+
+    /// Allocates the variables of the main struct. Returns the id of its component and the
+    /// symbolic values of its inputs.
+    fn allocate_main(&mut self, def: StructDefOpRef<'c, 'm>) -> Result<(usize, Vec<Term>), Error> {
+        let variables = &mut self.variables;
+        let main = self
+            .components
+            .allocate_component("main", def, &mut |name, member| {
+                variables.push(Variable {
+                    name,
+                    kind: VariableKind::Member,
+                    public: false,
+                    column: member.column(),
+                    offset: 0,
+                });
+                variables.len() - 1
+            })?;
+        // Only the members of the main struct itself can be public.
+        for member in def.member_defs() {
+            if let Some(Member::Felt(var)) = self.components[main].members.get(member.member_name())
+            {
+                self.variables[*var].public = member.has_public_attr();
+            }
+        }
+
+        let func = constrain_func(def)?;
+        let mut inputs = vec![];
+        for index in 1..func.arg_count()? {
+            let arg = func.argument(index)?;
+            self.components.field_of(&func, arg.r#type())?;
+            let name = func
+                .arg_name(index)?
+                .unwrap_or_else(|| format!("arg{}", index - 1));
+            let mut name = format!("main.{name}");
+            while self.variables.iter().any(|var| var.name == name) {
+                name.push('\'');
+            }
+            let public = func.arg_is_pub(u32::try_from(index).expect("argument index fits in u32"));
+            let var = self.new_variable(name, VariableKind::Input, public, false);
+            inputs.push(Term::Felt(variable(var)));
+        }
+        Ok((main, inputs))
+    }
+
+    /// Evaluates the constraints of the `@constrain` function of a component.
+    fn evaluate_constrain(&mut self, component: usize, inputs: Vec<Term>) -> Result<(), Error> {
+        let mut args = vec![Term::Component(component)];
+        args.extend(inputs);
+        walk_constrain(
+            TARGET,
+            self.components[component].def,
+            args,
+            |values, op| self.evaluate_op(values, op),
+        )
+    }
+
+    fn evaluate_op(
+        &mut self,
+        values: &mut HashMap<*const c_void, Term>,
+        op: OperationRef<'c, 'm>,
+    ) -> Result<(), Error> {
+        let term = |index: usize| -> Result<Term, Error> {
+            values
+                .get(&op.operand(index)?.to_raw().ptr)
+                .cloned()
+                .ok_or_else(|| fail(&op, "use of a value that was not evaluated"))
+        };
+        let felt = |index: usize| match term(index)? {
+            Term::Felt(terms) => Ok(terms),
+            Term::Component(_) => Err(fail(&op, "expected a felt value")),
+        };
+        let constant_operand = |index: usize, what: &str| {
+            as_constant(&felt(index)?).ok_or_else(|| fail(&op, format!("{what} must be constant")))
+        };
+
+        let name = op.name();
+        let result = match name.as_string_ref().as_str()? {
+            "felt.const" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let value = FeltConstAttribute::try_from(op.attribute("value")?)?.to_bigint();
+                constant(field.from_signed(&value))
+            }
+            "felt.add" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                add(&field, felt(0)?, &felt(1)?)
+            }
+            "felt.sub" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                add(&field, felt(0)?, &negate(&field, &felt(1)?))
+            }
+            "felt.neg" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                negate(&field, &felt(0)?)
+            }
+            "felt.mul" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                mul(&field, &felt(0)?, &felt(1)?)
+            }
+            "felt.div" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let inverse = field
+                    .inv(&constant_operand(1, "the divisor")?)
+                    .ok_or_else(|| fail(&op, "division by zero"))?;
+                mul(&field, &felt(0)?, &constant(inverse))
+            }
+            "felt.inv" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let inverse = field
+                    .inv(&constant_operand(0, "the operand")?)
+                    .ok_or_else(|| fail(&op, "division by zero"))?;
+                constant(inverse)
+            }
+            "felt.pow" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let exp = constant_operand(1, "the exponent")?;
+                let base = felt(0)?;
+                if let Some(base) = as_constant(&base) {
+                    constant(field.pow(&base, &exp))
+                } else {
+                    let msg = format!(
+                        "the exponent of a non-constant base must be at most {MAX_EXPONENT}"
+                    );
+                    let exp = u32::try_from(&exp)
+                        .ok()
+                        .filter(|exp| *exp <= MAX_EXPONENT)
+                        .ok_or_else(|| fail(&op, msg))?;
+                    (0..exp).fold(constant(BigUint::from(1u8)), |product, _| {
+                        mul(&field, &product, &base)
+                    })
+                }
+            }
+            "struct.readm" => {
+                let Term::Component(component) = term(0)? else {
+                    return Err(fail(&op, "expected a struct value"));
+                };
+                let member = symbol_attr(TARGET, &op, "member_name")?;
+                let offset = match op.attribute("tableOffset") {
+                    Err(_) => 0,
+                    Ok(attr) => IntegerAttribute::try_from(attr)
+                        .map_err(|_| fail(&op, "only literal row offsets are supported"))?
+                        .value(),
+                };
+                let value = match self.components[component].members.get(&member).copied() {
+                    Some(Member::Felt(var)) if offset != 0 && !self.variables[var].column => {
+                        return Err(fail(&op, format!("member @{member} is not a column")));
+                    }
+                    Some(Member::Felt(var)) => {
+                        Term::Felt(variable(self.offset_variable(var, offset)))
+                    }
+                    Some(Member::Component(_)) if offset != 0 => {
+                        return Err(fail(&op, "only felt columns can be read at a row offset"));
+                    }
+                    Some(Member::Component(id)) => Term::Component(id),
+                    None => return Err(fail(&op, format!("struct has no member @{member}"))),
+                };
+                values.insert(op.result(0)?.to_raw().ptr, value);
+                return Ok(());
+            }
+            "constrain.eq" => {
+                let field = self.components.field_of(&op, op.operand(0)?.r#type())?;
+                let terms = add(&field, felt(0)?, &negate(&field, &felt(1)?));
+                let attributes = op
+                    .attributes()
+                    .map(|(name, attribute)| {
+                        Ok((name.as_string_ref().as_str()?.to_owned(), attribute))
+                    })
+                    .collect::<Result<_, Error>>()?;
+                self.polynomials.push(Polynomial {
+                    terms,
+                    location: op.location(),
+                    attributes,
+                });
+                return Ok(());
+            }
+            "function.call" => {
+                let call = CallOpRef::try_from(op)?;
+                if !call.callee_is_struct_constrain() {
+                    return Err(fail(
+                        &op,
+                        "only calls to @constrain functions of subcomponents are supported",
+                    ));
+                }
+                let Some(Term::Component(component)) =
+                    values.get(&call.arg_operand_at(0).to_raw().ptr).cloned()
+                else {
+                    return Err(fail(&op, "expected a subcomponent as first argument"));
+                };
+                let inputs = (1..call.arg_operand_count())
+                    .map(|index| {
+                        values
+                            .get(&call.arg_operand_at(index).to_raw().ptr)
+                            .cloned()
+                            .ok_or_else(|| fail(&op, "use of a value that was not evaluated"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                return self.evaluate_constrain(component, inputs);
+            }
+            _ => {
+                return Err(fail(
+                    &op,
+                    "operation is not supported by the polynomial extraction",
+                ));
+            }
+        };
+        values.insert(op.result(0)?.to_raw().ptr, Term::Felt(result));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::LlzkContext, targets::collect_structs};
+    use melior::ir::Module;
+
+    fn main_struct<'c>(module: &Module<'c>) -> StructDefOpRef<'c, '_> {
+        let mut structs = HashMap::new();
+        collect_structs(module.body(), &mut structs).unwrap();
+        structs["@Main"]
+    }
+
+    #[test]
+    fn extract_polynomials() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("polynomial/test_files/cube.mlir")).unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let system = PolynomialSystem::from_struct(main_struct(&module), &field).unwrap();
+
+        let names = system
+            .variables()
+            .iter()
+            .map(|var| (var.name.as_str(), var.kind, var.public))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("main.c", VariableKind::Member, true),
+                ("main.sq.y", VariableKind::Member, false),
+                ("main.arg0", VariableKind::Input, false),
+                ("main.arg1", VariableKind::Input, false),
+            ]
+        );
+
+        let [square, cube] = system.polynomials() else {
+            panic!("unexpected polynomials:\n{system}");
+        };
+        assert_eq!(system.format(square), "-main.arg0^2 + main.sq.y");
+        assert_eq!(square.degree(), 2);
+        assert_eq!(cube.degree(), 3);
+        assert_eq!(cube.terms().len(), 5);
+        assert_ne!(square.location(), cube.location());
+
+        // (1 + 2 / 2)^3 = 8
+        let values = [8u8, 1, 1, 2].map(BigUint::from);
+        assert_eq!(square.evaluate(&field, &values), BigUint::ZERO);
+        assert_eq!(cube.evaluate(&field, &values), BigUint::ZERO);
+        let values = [7u8, 1, 1, 2].map(BigUint::from);
+        assert_ne!(cube.evaluate(&field, &values), BigUint::ZERO);
+    }
+
+    #[test]
+    fn powers_and_input_names() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    struct.member @arg0 : !felt.type
+    function.def @compute(%a: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type) {
+      %x = struct.readm %self[@arg0] : <@Main>, !felt.type
+      %two = felt.const 2
+      %huge = felt.const 1099511627776
+      %c = felt.pow %two, %huge : !felt.type, !felt.type
+      %three = felt.const 3
+      %cube = felt.pow %a, %three : !felt.type, !felt.type
+      %sum = felt.add %c, %cube : !felt.type, !felt.type
+      constrain.eq %x, %sum : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let field = Field::builtin("babybear").unwrap();
+        let system = PolynomialSystem::from_struct(main_struct(&module), &field).unwrap();
+        let names = system
+            .variables()
+            .iter()
+            .map(|var| (var.name.as_str(), var.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("main.arg0", VariableKind::Member),
+                ("main.arg0'", VariableKind::Input),
+            ]
+        );
+
+        let [polynomial] = system.polynomials() else {
+            panic!("unexpected polynomials:\n{system}");
+        };
+        assert_eq!(polynomial.degree(), 3);
+        let c = field.pow(&BigUint::from(2u8), &BigUint::from(1u64 << 40));
+        let a = BigUint::from(5u8);
+        let values = [field.add(&c, &BigUint::from(125u8)), a];
+        assert_eq!(polynomial.evaluate(&field, &values), BigUint::ZERO);
+    }
+
+    #[test]
+    fn large_powers_of_variables_fail() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    function.def @compute(%a: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type) {
+      %exp = felt.const 65
+      %pow = felt.pow %a, %exp : !felt.type, !felt.type
+      constrain.eq %pow, %a : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let err = PolynomialSystem::from_struct(main_struct(&module), &field).unwrap_err();
+        let Error::TranslationFailed { name, msg, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(name, "felt.pow");
+        assert_eq!(
+            msg,
+            "the exponent of a non-constant base must be at most 64"
+        );
+    }
+
+    #[test]
+    fn unsupported_ops_fail() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    function.def @compute(%a: !felt.type, %b: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type, %b: !felt.type) {
+      %c = felt.bit_and %a, %b : !felt.type, !felt.type
+      constrain.eq %c, %a : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let err = PolynomialSystem::from_struct(main_struct(&module), &field).unwrap_err();
+        assert!(
+            matches!(&err, Error::TranslationFailed { name, .. } if name == "felt.bit_and"),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+module attributes {llzk.lang} {
+  struct.def @Square {
+    struct.member @y : !felt.type
+    function.def @compute(%x: !felt.type) -> !struct.type<@Square> {
+      %self = struct.new : <@Square>
+      %y = felt.mul %x, %x : !felt.type, !felt.type
+      struct.writem %self[@y] = %y : <@Square>, !felt.type
+      function.return %self : !struct.type<@Square>
+    }
+    function.def @constrain(%self: !struct.type<@Square>, %x: !felt.type) {
+      %y = struct.readm %self[@y] : <@Square>, !felt.type
+      %square = felt.mul %x, %x : !felt.type, !felt.type
+      constrain.eq %y, %square : !felt.type, !felt.type
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    struct.member @sq : !struct.type<@Square>
+    function.def @compute(%a: !felt.type, %b: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %sq = function.call @Square::@compute(%a) : (!felt.type) -> !struct.type<@Square>
+      struct.writem %self[@sq] = %sq : <@Main>, !struct.type<@Square>
+      %two = felt.const 2
+      %half = felt.div %b, %two : !felt.type, !felt.type
+      %sum = felt.add %a, %half : !felt.type, !felt.type
+      %three = felt.const 3
+      %c = felt.pow %sum, %three : !felt.type, !felt.type
+      struct.writem %self[@c] = %c : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type, %b: !felt.type) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      %sq = struct.readm %self[@sq] : <@Main>, !struct.type<@Square>
+      function.call @Square::@constrain(%sq, %a) : (!struct.type<@Square>, !felt.type) -> ()
+      %two = felt.const 2
+      %half = felt.div %b, %two : !felt.type, !felt.type
+      %sum = felt.add %a, %half : !felt.type, !felt.type
+      %three = felt.const 3
+      %cube = felt.pow %sum, %three : !felt.type, !felt.type
+      constrain.eq %c, %cube : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
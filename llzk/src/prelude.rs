@@ -6,19 +6,30 @@ pub use crate::dialect::bool::prelude::*;
 pub use crate::dialect::felt::prelude::*;
 pub use crate::dialect::function::prelude::*;
 pub use crate::dialect::llzk::prelude::*;
-pub use crate::dialect::module::{LlzkModuleBuilder, ModuleExt, llzk_module};
+pub use crate::dialect::module::{
+    LlzkModule, LlzkModuleBuilder, LoadOptions, ModuleExt, llzk_module, validate_llzk_module,
+};
 pub use crate::dialect::pod::prelude::*;
 pub use crate::dialect::poly::prelude::*;
+pub use crate::dialect::string::prelude::*;
 pub use crate::dialect::r#struct::prelude::*;
 pub use crate::dialect::verif::prelude::*;
 pub use crate::error::Error as LlzkError;
-pub use crate::operation::{replace_uses_of_with, verify_operation, verify_operation_with_diags};
+#[cfg(feature = "bigint")]
+pub use crate::operation::check_constraints;
+pub use crate::operation::{
+    BlockIterExt, OperationIterExt, replace_uses_of_with, verify_operation,
+    verify_operation_with_diags,
+};
 pub use crate::passes as llzk_passes;
+pub use crate::rewrite::{PatternSet, RewritePattern, Rewriter, apply_patterns_greedily};
 pub use crate::symbol_ref::{SymbolRefAttrLike, SymbolRefAttribute};
-pub use crate::symbol_table;
+pub use crate::symbol_table::{self, SymbolTable};
 pub use crate::type_ext::*;
+pub use crate::typed_value::{BoolValue, FeltValue, IndexValue};
 pub use crate::typing::{types_unify, types_unify_with_prefix};
 pub use crate::utils::{IntoRef, print_block, print_operation, print_region};
+pub use crate::visitor::{LlzkOpRef, LlzkVisitor};
 
 /// Exports from the various llzk dialects.
 pub mod dialect {
@@ -80,6 +91,11 @@ pub mod dialect {
         pub use crate::dialect::global::{is_def_op, is_read_op, is_write_op};
     }
 
+    /// Exports functions from the 'include' dialect
+    pub mod include {
+        pub use crate::dialect::include::{from, is_include_op};
+    }
+
     /// Exports functions from the 'llzk' dialect
     pub mod llzk {
         pub use crate::dialect::llzk::{is_nondet_op, nondet};
@@ -105,6 +121,11 @@ pub mod dialect {
         pub use crate::dialect::ram::{load, store};
     }
 
+    /// Exports functions from the 'string' dialect
+    pub mod string {
+        pub use crate::dialect::string::is_string_type;
+    }
+
     /// Exports functions from the 'struct' dialect
     pub mod r#struct {
         pub use crate::dialect::r#struct::helpers;
@@ -165,7 +186,8 @@ pub mod melior_dialects {
     /// Exports functions from the 'scf' dialect and extensions for LLZK.
     pub mod scf {
         pub use crate::dialect::scf_ext::{
-            is_condition_op, is_for_op, is_if_op, is_while_op, is_yield_op,
+            for_, for_with_bounds, if_, if_then, is_condition_op, is_for_op, is_if_op, is_while_op,
+            is_yield_op, while_,
         };
         pub use melior::dialect::scf::*;
     }
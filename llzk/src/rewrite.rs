@@ -0,0 +1,353 @@
+//! Pattern rewriting of LLZK IR.
+//!
+//! A [`RewritePattern`] matches a root operation and rewrites it through a [`Rewriter`], which
+//! builds the new operations before the root and keeps track of the operations it inserts,
+//! modifies and erases. [`apply_patterns_greedily`] applies a [`PatternSet`] to the operations
+//! nested in an operation until no pattern applies anymore.
+//!
+//! Patterns must change the IR only through the rewriter. Erasing or replacing operations by
+//! other means leaves the driver with dangling operations.
+
+use crate::{
+    builder::{EntryPoint, InsertPoint, OpBuilder, OpBuilderLike, OpBuilderListener},
+    error::Error,
+    operation::{detach_and_erase_op, replace_uses_of_with},
+    value_ext::{has_uses, users_of},
+};
+use llzk_sys::MlirOpBuilder;
+use melior::ir::{
+    BlockRef, OperationRef, RegionRef, Value, ValueLike as _,
+    operation::{OperationLike, OperationResult, WalkOrder, WalkResult},
+};
+use mlir_sys::{MlirOperation, mlirOperationGetParentOperation};
+use std::{
+    cell::RefCell,
+    collections::{HashSet, VecDeque},
+    ffi::c_void,
+    fmt,
+    rc::Rc,
+};
+
+/// A rewrite of operations that match a root operation.
+pub trait RewritePattern {
+    /// Returns true if the pattern may apply to the operation, for example because it is a
+    /// `felt.add` op. This is a cheap check done before calling [`rewrite`](Self::rewrite).
+    fn matches(&self, op: OperationRef) -> bool;
+
+    /// Rewrites the operation, which satisfies [`matches`](Self::matches), and returns true if
+    /// the IR changed. All changes must go through the rewriter.
+    fn rewrite<'c>(
+        &self,
+        op: OperationRef<'c, '_>,
+        rewriter: &mut Rewriter<'c>,
+    ) -> Result<bool, Error>;
+
+    /// Returns the benefit of the pattern. Patterns with a higher benefit are tried first.
+    fn benefit(&self) -> u16 {
+        1
+    }
+}
+
+/// A set of rewrite patterns.
+#[derive(Default)]
+pub struct PatternSet {
+    patterns: Vec<Box<dyn RewritePattern>>,
+}
+
+impl PatternSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pattern to the set.
+    pub fn add(&mut self, pattern: impl RewritePattern + 'static) -> &mut Self {
+        self.patterns.push(Box::new(pattern));
+        // Keeps the patterns with the same benefit in insertion order.
+        self.patterns
+            .sort_by_key(|pattern| std::cmp::Reverse(pattern.benefit()));
+        self
+    }
+
+    /// Returns the number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Returns true if the set has no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+impl fmt::Debug for PatternSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternSet")
+            .field("patterns", &self.patterns.len())
+            .finish()
+    }
+}
+
+/// A change made by a [`Rewriter`].
+///
+/// The changes are kept in the order they were made because a new operation may reuse the memory
+/// of an operation erased before it.
+enum Change {
+    /// The operation was inserted or modified, or its operands changed.
+    Touched(MlirOperation),
+    /// The operation was erased, directly or as part of an erased operation.
+    Erased(MlirOperation),
+}
+
+/// Records the operations inserted by the builder of a [`Rewriter`] in the scope of the driver.
+struct Listener(Rc<RefCell<Vec<Change>>>, MlirOperation);
+
+impl OpBuilderListener for Listener {
+    fn notify_operation_inserted<'ctx, 'blk>(
+        &mut self,
+        op: OperationRef<'ctx, 'blk>,
+        _point: InsertPoint<'ctx, 'blk>,
+    ) {
+        if is_nested_in(op, self.1) {
+            self.0.borrow_mut().push(Change::Touched(op.to_raw()));
+        }
+    }
+
+    fn notify_block_inserted<'ctx, 'blk>(
+        &mut self,
+        _block: BlockRef<'ctx, 'blk>,
+        _region: Option<RegionRef<'ctx, 'blk>>,
+        _point: Option<BlockRef<'ctx, 'blk>>,
+    ) {
+    }
+}
+
+/// Builder used by the patterns to change the IR.
+///
+/// Operations built with the rewriter are inserted right before the root operation of the
+/// pattern, unless the insertion point is moved.
+pub struct Rewriter<'c> {
+    builder: OpBuilder<'c, 'static>,
+    changes: Rc<RefCell<Vec<Change>>>,
+    /// Operation the driver applies the patterns to. Only the operations nested in it are
+    /// rewritten again.
+    scope: MlirOperation,
+}
+
+impl<'c> Rewriter<'c> {
+    fn new(root: OperationRef<'c, '_>, scope: MlirOperation) -> Self {
+        let changes = Rc::new(RefCell::new(vec![]));
+        let ctx = root.context();
+        let builder = OpBuilder::new_with_listener(
+            unsafe { ctx.to_ref() },
+            EntryPoint::Before(root),
+            Listener(changes.clone(), scope),
+        );
+        Self {
+            builder,
+            changes,
+            scope,
+        }
+    }
+
+    fn touch(&self, op: OperationRef<'c, '_>) {
+        if is_nested_in(op, self.scope) {
+            self.changes.borrow_mut().push(Change::Touched(op.to_raw()));
+        }
+    }
+
+    /// Replaces all the uses of `from` with `to`.
+    pub fn replace_all_uses_with(&mut self, from: Value<'c, '_>, to: Value<'c, '_>) {
+        for user in users_of(from) {
+            replace_uses_of_with(&user, from, to);
+            self.touch(user);
+        }
+    }
+
+    /// Replaces the results of the operation with the given values and erases it.
+    pub fn replace_op(
+        &mut self,
+        op: OperationRef<'c, '_>,
+        values: &[Value<'c, '_>],
+    ) -> Result<(), Error> {
+        if values.len() != op.result_count() {
+            return Err(Error::GeneralError(
+                "number of replacement values does not match the number of results",
+            ));
+        }
+        for (result, value) in op.results().zip(values) {
+            self.replace_all_uses_with(result.into(), *value);
+        }
+        self.erase_op(op)
+    }
+
+    /// Replaces the results of the operation with the results of `new_op` and erases it.
+    pub fn replace_op_with(
+        &mut self,
+        op: OperationRef<'c, '_>,
+        new_op: OperationRef<'c, '_>,
+    ) -> Result<(), Error> {
+        let values = new_op.results().map(Value::from).collect::<Vec<_>>();
+        self.replace_op(op, &values)
+    }
+
+    /// Erases the operation, which must not have results with uses.
+    pub fn erase_op(&mut self, op: OperationRef<'c, '_>) -> Result<(), Error> {
+        if op.results().any(has_uses) {
+            return Err(Error::GeneralError(
+                "cannot erase an operation whose results are still used",
+            ));
+        }
+        // The defining operations of the operands may become unused.
+        for operand in op.operands() {
+            if let Ok(result) = OperationResult::try_from(operand) {
+                self.touch(result.owner());
+            }
+        }
+        let mut changes = self.changes.borrow_mut();
+        op.walk(WalkOrder::PostOrder, |nested| {
+            changes.push(Change::Erased(nested.to_raw()));
+            WalkResult::Advance
+        });
+        drop(changes);
+        // Keeps the insertion point valid when it is the erased operation.
+        if self
+            .builder
+            .insertion_point()
+            .is_some_and(|point| point.to_raw().ptr == op.to_raw().ptr)
+        {
+            self.builder.set_insertion_point_after(op);
+        }
+        detach_and_erase_op(op);
+        Ok(())
+    }
+
+    /// Modifies the operation in place with the callback.
+    pub fn modify_op_in_place<T>(
+        &mut self,
+        op: OperationRef<'c, '_>,
+        modify: impl FnOnce(OperationRef<'c, '_>) -> T,
+    ) -> T {
+        let result = modify(op);
+        self.touch(op);
+        result
+    }
+}
+
+impl<'c> OpBuilderLike<'c> for Rewriter<'c> {
+    fn to_raw(&self) -> MlirOpBuilder {
+        self.builder.to_raw()
+    }
+}
+
+impl fmt::Debug for Rewriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rewriter")
+            .field("builder", &self.builder)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Default limit on the number of rewrites done by [`apply_patterns_greedily`].
+pub const DEFAULT_MAX_REWRITES: usize = 100_000;
+
+/// Applies the patterns to the operations nested in `op` until none of them applies anymore.
+///
+/// Every nested operation is put in a worklist. The driver takes the operations from the
+/// worklist one at a time and applies the first pattern that matches it and rewrites it. The
+/// operations nested in `op` that the rewrite inserts, modifies or leaves unused are added to the
+/// worklist again, while the ones outside of it are left alone. Returns true if the IR changed, or an error if a pattern failed or the patterns did not
+/// converge within [`DEFAULT_MAX_REWRITES`] rewrites.
+pub fn apply_patterns_greedily<'c: 'a, 'a>(
+    op: &impl OperationLike<'c, 'a>,
+    patterns: &PatternSet,
+) -> Result<bool, Error> {
+    apply_patterns_greedily_with_limit(op, patterns, DEFAULT_MAX_REWRITES)
+}
+
+/// Like [`apply_patterns_greedily`], but with a custom limit on the number of rewrites.
+pub fn apply_patterns_greedily_with_limit<'c: 'a, 'a>(
+    op: &impl OperationLike<'c, 'a>,
+    patterns: &PatternSet,
+    max_rewrites: usize,
+) -> Result<bool, Error> {
+    let root = op.to_raw().ptr;
+    let mut worklist = Worklist::default();
+    op.walk(WalkOrder::PostOrder, |nested| {
+        if nested.to_raw().ptr != root {
+            worklist.push(nested.to_raw());
+        }
+        WalkResult::Advance
+    });
+
+    let mut erased = HashSet::<*mut c_void>::new();
+    let mut rewrites = 0;
+    while let Some(raw) = worklist.pop() {
+        if erased.contains(&raw.ptr) {
+            continue;
+        }
+        let current = unsafe { OperationRef::from_raw(raw) };
+        for pattern in &patterns.patterns {
+            if !pattern.matches(current) {
+                continue;
+            }
+            let mut rewriter = Rewriter::new(current, op.to_raw());
+            let changed = pattern.rewrite(current, &mut rewriter)?;
+            for change in rewriter.changes.take() {
+                match change {
+                    Change::Touched(raw) => {
+                        erased.remove(&raw.ptr);
+                        worklist.push(raw);
+                    }
+                    Change::Erased(raw) => {
+                        erased.insert(raw.ptr);
+                    }
+                }
+            }
+            if changed {
+                rewrites += 1;
+                if rewrites > max_rewrites {
+                    return Err(Error::GeneralError(
+                        "greedy pattern rewrite did not converge",
+                    ));
+                }
+                break;
+            }
+        }
+    }
+    Ok(rewrites > 0)
+}
+
+/// Returns true if the operation is nested in `scope`, at any depth. `scope` itself is not nested
+/// in itself.
+fn is_nested_in(op: OperationRef, scope: MlirOperation) -> bool {
+    let mut parent = unsafe { mlirOperationGetParentOperation(op.to_raw()) };
+    while !parent.ptr.is_null() {
+        if parent.ptr == scope.ptr {
+            return true;
+        }
+        parent = unsafe { mlirOperationGetParentOperation(parent) };
+    }
+    false
+}
+
+/// Queue of operations that ignores the operations that are already queued.
+#[derive(Default)]
+struct Worklist {
+    queue: VecDeque<MlirOperation>,
+    queued: HashSet<*mut c_void>,
+}
+
+impl Worklist {
+    fn push(&mut self, op: MlirOperation) {
+        if self.queued.insert(op.ptr) {
+            self.queue.push_back(op);
+        }
+    }
+
+    fn pop(&mut self) -> Option<MlirOperation> {
+        let op = self.queue.pop_front()?;
+        self.queued.remove(&op.ptr);
+        Some(op)
+    }
+}
@@ -1,10 +1,33 @@
 //! Utilities related to symbol tables.
 
-use melior::ir::{
-    Operation,
-    operation::{OperationLike, OperationRef},
+use crate::{
+    attributes::array::ArrayAttribute,
+    dialect::{
+        array::ArrayType,
+        pod::{attrs::PodRecordAttribute, r#type::PodType},
+        r#struct::StructType,
+    },
+    error::Error,
+    symbol_ref::SymbolRefAttribute,
 };
-use std::mem;
+use melior::{
+    StringRef,
+    ir::{
+        Attribute, AttributeLike as _, BlockLike as _, Operation, RegionLike as _, Type,
+        TypeLike as _, ValueLike as _,
+        attribute::{StringAttribute, TypeAttribute},
+        operation::{OperationLike, OperationRef, WalkOrder, WalkResult},
+        r#type::FunctionType,
+    },
+};
+use mlir_sys::{
+    MlirOperation, MlirSymbolTable, mlirOperationGetParentOperation,
+    mlirOperationSetAttributeByName, mlirSymbolTableCreate, mlirSymbolTableDestroy,
+    mlirSymbolTableErase, mlirSymbolTableGetSymbolAttributeName, mlirSymbolTableInsert,
+    mlirSymbolTableLookup, mlirSymbolTableReplaceAllSymbolUses, mlirSymbolTableWalkSymbolTables,
+    mlirValueSetType,
+};
+use std::{ffi::c_void, fmt, marker::PhantomData, mem};
 
 /// Insert a new symbol operation into the symbol table owned by `sym_table_op`.
 ///
@@ -25,3 +48,470 @@ pub fn insert<'c: 'a, 'a>(
 
     unsafe { OperationRef::from_raw(raw) }
 }
+
+/// The symbol table of an operation with the `SymbolTable` trait, such as `builtin.module` or
+/// `struct.def`.
+///
+/// The table caches the symbols defined in the region of the operation. Symbols must be added,
+/// renamed and removed through the table while it is alive.
+pub struct SymbolTable<'c, 'a> {
+    op: MlirOperation,
+    raw: MlirSymbolTable,
+    _op: PhantomData<OperationRef<'c, 'a>>,
+}
+
+impl<'c: 'a, 'a> SymbolTable<'c, 'a> {
+    /// Creates the symbol table of the operation, or returns an error if the operation is not a
+    /// symbol table.
+    pub fn new(op: &impl OperationLike<'c, 'a>) -> Result<Self, Error> {
+        let raw = unsafe { mlirSymbolTableCreate(op.to_raw()) };
+        if raw.ptr.is_null() {
+            return Err(Error::OperationExpected(
+                "symbol table",
+                op.name().as_string_ref().as_str()?.to_owned(),
+            ));
+        }
+        Ok(Self {
+            op: op.to_raw(),
+            raw,
+            _op: PhantomData,
+        })
+    }
+
+    /// Returns the operation that owns the symbol table.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        unsafe { OperationRef::from_raw(self.op) }
+    }
+
+    /// Returns the symbol with the given name defined directly in this table.
+    pub fn lookup(&self, name: &str) -> Option<OperationRef<'c, 'a>> {
+        unsafe {
+            OperationRef::from_option_raw(mlirSymbolTableLookup(
+                self.raw,
+                StringRef::new(name).to_raw(),
+            ))
+        }
+    }
+
+    /// Returns the symbol that the reference points to, starting from this table.
+    ///
+    /// The root of the reference is looked up in this table and every nested reference in the
+    /// symbol table of the symbol found before it.
+    pub fn lookup_nested(&self, symbol: SymbolRefAttribute<'c>) -> Option<OperationRef<'c, 'a>> {
+        let mut op = self.lookup(symbol.root().as_str().ok()?)?;
+        for nested in symbol.nested() {
+            op = SymbolTable::new(&op).ok()?.lookup(nested.value())?;
+        }
+        Some(op)
+    }
+
+    /// Inserts the symbol operation at the end of the table and returns it.
+    ///
+    /// The symbol is renamed if its name is already taken. Ownership of the operation is
+    /// transferred to the symbol table.
+    pub fn insert(&mut self, symbol: Operation<'c>) -> OperationRef<'c, 'a> {
+        let raw = symbol.to_raw();
+        unsafe { mlirSymbolTableInsert(self.raw, raw) };
+        // The symbol table now owns the operation.
+        mem::forget(symbol);
+        unsafe { OperationRef::from_raw(raw) }
+    }
+
+    /// Removes the symbol with the given name from the table and erases it.
+    ///
+    /// The uses of the symbol are not updated. Use [`is_symbol_unused`](Self::is_symbol_unused)
+    /// first to make sure no dangling references are left behind.
+    pub fn erase(&mut self, name: &str) -> Result<(), Error> {
+        let op = self
+            .lookup(name)
+            .ok_or_else(|| Error::SymbolNotFound(name.to_owned()))?;
+        unsafe { mlirSymbolTableErase(self.raw, op.to_raw()) };
+        Ok(())
+    }
+
+    /// Renames the symbol and updates all the references to it.
+    ///
+    /// The references relative to this table, like `@old`, are replaced by MLIR in the attributes
+    /// of the operations of this table and of the symbol tables nested in it. MLIR does not update
+    /// the types of results and block arguments nor the references that reach the symbol through
+    /// the names of the enclosing symbol tables, like `@Struct::@old`, so those are replaced here.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), Error> {
+        let op = self
+            .lookup(name)
+            .ok_or_else(|| Error::SymbolNotFound(name.to_owned()))?;
+        if self.lookup(new_name).is_some() {
+            return Err(Error::GeneralError("the new symbol name is already in use"));
+        }
+
+        for table in self.nested_tables(name) {
+            let result = unsafe {
+                mlirSymbolTableReplaceAllSymbolUses(
+                    StringRef::new(name).to_raw(),
+                    StringRef::new(new_name).to_raw(),
+                    table,
+                )
+            };
+            if result.value == 0 {
+                return Err(Error::GeneralError(
+                    "failed to replace the uses of the symbol",
+                ));
+            }
+        }
+
+        for (scope, path) in self.scopes(name) {
+            let index = path.len() - 1;
+            let mut replace = |symbol: SymbolRefAttribute<'c>| {
+                let mut components = components(symbol)?;
+                if !components.starts_with(&path) {
+                    return None;
+                }
+                components[index] = new_name.to_owned();
+                Some(from_components(symbol, &components))
+            };
+            let outer = scope.ptr != self.op.ptr;
+            walk_scope(scope, &path, |op| {
+                if outer {
+                    replace_refs_in_attrs(op, &mut replace);
+                }
+                replace_refs_in_values(op, &mut replace);
+            });
+        }
+
+        let ctx = unsafe { op.context().to_ref() };
+        unsafe {
+            mlirOperationSetAttributeByName(
+                op.to_raw(),
+                mlirSymbolTableGetSymbolAttributeName(),
+                StringAttribute::new(ctx, new_name).to_raw(),
+            );
+        }
+        // The cached symbols are stale after the rename.
+        unsafe {
+            mlirSymbolTableDestroy(self.raw);
+            self.raw = mlirSymbolTableCreate(self.op);
+        }
+        Ok(())
+    }
+
+    /// Returns the operations that reference the symbol with the given name, in the same places
+    /// that [`rename`](Self::rename) updates.
+    pub fn symbol_uses(&self, name: &str) -> Vec<OperationRef<'c, 'a>> {
+        let mut uses = vec![];
+        for (scope, path) in self.scopes(name) {
+            walk_scope(scope, &path, |op| {
+                let mut used = false;
+                let mut find = |symbol: SymbolRefAttribute<'c>| -> Option<SymbolRefAttribute<'c>> {
+                    used |= components(symbol).is_some_and(|c| c.starts_with(&path));
+                    None
+                };
+                replace_refs_in_attrs(op, &mut find);
+                replace_refs_in_values(op, &mut find);
+                if used
+                    && !uses
+                        .iter()
+                        .any(|u: &MlirOperation| u.ptr == op.to_raw().ptr)
+                {
+                    uses.push(op.to_raw());
+                }
+            });
+        }
+        uses.into_iter()
+            .map(|op| unsafe { OperationRef::from_raw(op) })
+            .collect()
+    }
+
+    /// Returns true if nothing references the symbol with the given name.
+    pub fn is_symbol_unused(&self, name: &str) -> bool {
+        self.symbol_uses(name).is_empty()
+    }
+
+    /// Returns this table and the symbol tables nested in it where the name refers to the symbol,
+    /// which are the ones that are not nested in a table that defines a symbol with the same name.
+    fn nested_tables(&self, name: &str) -> Vec<MlirOperation> {
+        unsafe extern "C" fn push_table(op: MlirOperation, _: bool, data: *mut c_void) {
+            unsafe { (*(data as *mut Vec<MlirOperation>)).push(op) };
+        }
+
+        let mut tables = vec![];
+        unsafe {
+            mlirSymbolTableWalkSymbolTables(
+                self.op,
+                false,
+                Some(push_table),
+                &mut tables as *mut Vec<MlirOperation> as *mut c_void,
+            )
+        };
+        tables
+            .into_iter()
+            .filter(|table| {
+                let mut current = *table;
+                while current.ptr != self.op.ptr {
+                    let table = unsafe { OperationRef::from_raw(current) };
+                    if SymbolTable::new(&table).is_ok_and(|table| table.lookup(name).is_some()) {
+                        return false;
+                    }
+                    current = unsafe { mlirOperationGetParentOperation(current) };
+                }
+                true
+            })
+            .collect()
+    }
+
+    /// Returns the operations in which the symbol can be referenced, together with the path of
+    /// the reference from each of them.
+    ///
+    /// The first scope is this table, where the symbol is referenced by its name. The symbol can
+    /// also be referenced from the enclosing symbol tables by prefixing the names of the tables in
+    /// between, as long as they have a name.
+    fn scopes(&self, name: &str) -> Vec<(MlirOperation, Vec<String>)> {
+        let mut path = vec![name.to_owned()];
+        let mut scopes = vec![(self.op, path.clone())];
+        let mut table = self.operation();
+        let mut parent = unsafe { mlirOperationGetParentOperation(self.op) };
+        while !parent.ptr.is_null() {
+            if is_symbol_table(parent) {
+                let Some(table_name) = symbol_name(table) else {
+                    break;
+                };
+                path.insert(0, table_name.to_owned());
+                scopes.push((parent, path.clone()));
+                table = unsafe { OperationRef::from_raw(parent) };
+            }
+            parent = unsafe { mlirOperationGetParentOperation(parent) };
+        }
+        scopes
+    }
+}
+
+impl Drop for SymbolTable<'_, '_> {
+    fn drop(&mut self) {
+        unsafe { mlirSymbolTableDestroy(self.raw) }
+    }
+}
+
+impl fmt::Debug for SymbolTable<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SymbolTable")
+            .field("op", &self.operation().name())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns true if the operation has the `SymbolTable` trait.
+fn is_symbol_table(op: MlirOperation) -> bool {
+    let table = unsafe { mlirSymbolTableCreate(op) };
+    if table.ptr.is_null() {
+        return false;
+    }
+    unsafe { mlirSymbolTableDestroy(table) };
+    true
+}
+
+/// Returns the name of the symbol defined by the operation.
+fn symbol_name<'c>(op: OperationRef<'c, '_>) -> Option<&'c str> {
+    let attr = op.attribute("sym_name").ok()?;
+    Some(StringAttribute::try_from(attr).ok()?.value())
+}
+
+/// Visits the operations nested in `scope`, including `scope` itself, where a reference that
+/// starts with the first component of `path` means the symbol of the path.
+///
+/// Nested symbol tables that define a symbol with that name shadow it, so they are skipped.
+fn walk_scope<'c>(
+    scope: MlirOperation,
+    path: &[String],
+    mut visit: impl FnMut(OperationRef<'c, '_>),
+) {
+    let scope = unsafe { OperationRef::<'c, '_>::from_raw(scope) };
+    scope.walk(WalkOrder::PreOrder, |op| {
+        if op != scope {
+            if let Ok(table) = SymbolTable::new(&op) {
+                if table.lookup(&path[0]).is_some() {
+                    return WalkResult::Skip;
+                }
+            }
+        }
+        visit(op);
+        WalkResult::Advance
+    });
+}
+
+/// Returns the root and nested references of the symbol reference.
+fn components(symbol: SymbolRefAttribute) -> Option<Vec<String>> {
+    let mut components = vec![symbol.root().as_str().ok()?.to_owned()];
+    components.extend(symbol.nested().iter().map(|r| r.value().to_owned()));
+    Some(components)
+}
+
+/// Creates a symbol reference in the context of `symbol` from its components.
+fn from_components<'c>(
+    symbol: SymbolRefAttribute<'c>,
+    components: &[String],
+) -> SymbolRefAttribute<'c> {
+    let nested = components[1..]
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    SymbolRefAttribute::new_from_str(
+        unsafe { symbol.context().to_ref() },
+        &components[0],
+        &nested,
+    )
+}
+
+/// Calls `replace` on the symbol references in the attributes of the operation and replaces the
+/// references for which it returns a new one.
+fn replace_refs_in_attrs<'c, F>(op: OperationRef<'c, '_>, replace: &mut F)
+where
+    F: FnMut(SymbolRefAttribute<'c>) -> Option<SymbolRefAttribute<'c>>,
+{
+    for (name, attr) in op.attributes().collect::<Vec<_>>() {
+        if let Some(attr) = replace_refs_in_attr(attr, replace) {
+            unsafe {
+                mlirOperationSetAttributeByName(
+                    op.to_raw(),
+                    name.as_string_ref().to_raw(),
+                    attr.to_raw(),
+                )
+            };
+        }
+    }
+}
+
+/// Calls `replace` on the symbol references in the result types and block argument types of the
+/// operation and replaces the references for which it returns a new one.
+fn replace_refs_in_values<'c, F>(op: OperationRef<'c, '_>, replace: &mut F)
+where
+    F: FnMut(SymbolRefAttribute<'c>) -> Option<SymbolRefAttribute<'c>>,
+{
+    for result in op.results() {
+        if let Some(r#type) = replace_refs_in_type(result.r#type(), replace) {
+            unsafe { mlirValueSetType(result.to_raw(), r#type.to_raw()) };
+        }
+    }
+    for region in op.regions() {
+        let mut block = region.first_block();
+        while let Some(current) = block {
+            for index in 0..current.argument_count() {
+                let arg = current.argument(index).expect("argument in range");
+                if let Some(r#type) = replace_refs_in_type(arg.r#type(), replace) {
+                    arg.set_type(r#type);
+                }
+            }
+            block = current.next_in_region();
+        }
+    }
+}
+
+/// Replaces the symbol references in the attribute. Returns `None` if nothing was replaced.
+fn replace_refs_in_attr<'c, F>(attr: Attribute<'c>, replace: &mut F) -> Option<Attribute<'c>>
+where
+    F: FnMut(SymbolRefAttribute<'c>) -> Option<SymbolRefAttribute<'c>>,
+{
+    if let Ok(symbol) = SymbolRefAttribute::try_from(attr) {
+        return replace(symbol).map(Into::into);
+    }
+    if let Ok(array) = ArrayAttribute::try_from(attr) {
+        let elements = array.into_iter().collect::<Vec<_>>();
+        return replace_all(&elements, |attr| replace_refs_in_attr(attr, replace)).map(
+            |elements| ArrayAttribute::new(unsafe { attr.context().to_ref() }, &elements).into(),
+        );
+    }
+    if let Ok(type_attr) = TypeAttribute::try_from(attr) {
+        return replace_refs_in_type(type_attr.value(), replace)
+            .map(|r#type| TypeAttribute::new(r#type).into());
+    }
+    None
+}
+
+/// Replaces the symbol references in the type. Returns `None` if nothing was replaced.
+fn replace_refs_in_type<'c, F>(r#type: Type<'c>, replace: &mut F) -> Option<Type<'c>>
+where
+    F: FnMut(SymbolRefAttribute<'c>) -> Option<SymbolRefAttribute<'c>>,
+{
+    if let Ok(struct_type) = StructType::try_from(r#type) {
+        let name = replace(struct_type.name());
+        let params = struct_type.params_vec();
+        let new_params = replace_all(&params, |attr| replace_refs_in_attr(attr, replace));
+        if name.is_none() && new_params.is_none() {
+            return None;
+        }
+        return Some(
+            StructType::new(
+                name.unwrap_or(struct_type.name()),
+                &new_params.unwrap_or(params),
+            )
+            .into(),
+        );
+    }
+    if let Ok(array_type) = ArrayType::try_from(r#type) {
+        let element = replace_refs_in_type(array_type.element_type(), replace);
+        let dims = array_type.dims();
+        let new_dims = replace_all(&dims, |attr| replace_refs_in_attr(attr, replace));
+        if element.is_none() && new_dims.is_none() {
+            return None;
+        }
+        return Some(
+            ArrayType::new(
+                element.unwrap_or(array_type.element_type()),
+                &new_dims.unwrap_or(dims),
+            )
+            .into(),
+        );
+    }
+    if let Ok(pod_type) = PodType::try_from(r#type) {
+        let records = pod_type.records();
+        let types = records
+            .iter()
+            .map(|record| record.r#type())
+            .collect::<Vec<_>>();
+        let new_types = replace_all(&types, |t| replace_refs_in_type(t, replace))?;
+        let records = records
+            .iter()
+            .zip(new_types)
+            .map(|(record, r#type)| {
+                let name = record.name().as_string_ref().as_str().ok()?;
+                Some(PodRecordAttribute::new(name, r#type))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        return Some(PodType::new(unsafe { r#type.context().to_ref() }, &records).into());
+    }
+    if let Ok(function_type) = FunctionType::try_from(r#type) {
+        let inputs = (0..function_type.input_count())
+            .map(|i| function_type.input(i).expect("input in range"))
+            .collect::<Vec<_>>();
+        let results = (0..function_type.result_count())
+            .map(|i| function_type.result(i).expect("result in range"))
+            .collect::<Vec<_>>();
+        let new_inputs = replace_all(&inputs, |t| replace_refs_in_type(t, replace));
+        let new_results = replace_all(&results, |t| replace_refs_in_type(t, replace));
+        if new_inputs.is_none() && new_results.is_none() {
+            return None;
+        }
+        return Some(
+            FunctionType::new(
+                unsafe { r#type.context().to_ref() },
+                &new_inputs.unwrap_or(inputs),
+                &new_results.unwrap_or(results),
+            )
+            .into(),
+        );
+    }
+    None
+}
+
+/// Calls `replace` on every item and returns the new items if any of them was replaced.
+fn replace_all<T: Copy>(items: &[T], mut replace: impl FnMut(T) -> Option<T>) -> Option<Vec<T>> {
+    let mut replaced = false;
+    let items = items
+        .iter()
+        .map(|item| match replace(*item) {
+            Some(new) => {
+                replaced = true;
+                new
+            }
+            None => *item,
+        })
+        .collect();
+    replaced.then_some(items)
+}
@@ -1,4 +1,43 @@
 //! Functions for translating LLZK IR into target output formats.
 
+#[cfg(feature = "bigint")]
+pub mod air;
+pub mod dot;
 #[cfg(feature = "pcl-backend")]
 pub mod pcl;
+#[cfg(feature = "bigint")]
+pub mod r1cs;
+#[cfg(feature = "bigint")]
+pub mod rust;
+#[cfg(feature = "bigint")]
+pub mod smt;
+#[cfg(feature = "bigint")]
+pub(crate) mod symbolic;
+
+use crate::{
+    dialect::r#struct::{StructDefOpLike as _, StructDefOpRef},
+    error::Error,
+    operation::isa,
+};
+use melior::ir::{BlockLike as _, BlockRef, RegionLike as _, operation::OperationLike as _};
+use std::collections::HashMap;
+
+/// Collects the struct definitions in the block and in the modules nested in it, by fully
+/// qualified name.
+pub(crate) fn collect_structs<'c, 'm>(
+    block: BlockRef<'c, 'm>,
+    structs: &mut HashMap<String, StructDefOpRef<'c, 'm>>,
+) -> Result<(), Error> {
+    let mut next = block.first_operation();
+    while let Some(op) = next {
+        if let Ok(def) = StructDefOpRef::try_from(op) {
+            structs.insert(def.fully_qualified_name().to_string(), def);
+        } else if isa(&op, "builtin.module") {
+            if let Some(block) = op.region(0)?.first_block() {
+                collect_structs(block, structs)?;
+            }
+        }
+        next = op.next_in_block();
+    }
+    Ok(())
+}
@@ -0,0 +1,623 @@
+//! Translation of column structs into AIR descriptions for STARK provers.
+//!
+//! The translation works on a struct with members marked as columns, whose values form the rows
+//! of an execution trace. Its `@constrain` function is evaluated into polynomials with
+//! [`PolynomialSystem`], and each `constrain.eq` becomes one constraint with the [`Domain`] of
+//! rows it applies to:
+//!
+//! - a transition constraint by default. It refers to other rows through the reads at a row
+//!   offset, and applies to every row except the ones whose reads would wrap around the trace:
+//!   reads at offset `1` exclude the last row;
+//! - a boundary constraint if the `constrain.eq` has the [`ROW_ATTRIBUTE`] attribute, which is
+//!   `"first"` for a constraint on the first row, like an initial condition, and `"last"` for a
+//!   constraint on the last row, like an output. It cannot read other rows.
+//!
+//! For example, `constrain.eq %a, %zero : !felt.type, !felt.type {air.row = "first"}` starts
+//! column `a` at zero, while the same constraint without the attribute makes it zero on every
+//! row.
+//!
+//! Every felt member, including the members of subcomponents, must be a column. The module must
+//! be flattened and its arrays lowered to scalars beforehand, for example with the flattening and
+//! array-to-scalar passes.
+//!
+//! The description serializes to a JSON document with the following schema, where the keys always
+//! appear in this order and felts are strings with a decimal number:
+//!
+//! ```text
+//! {
+//!   "version": 2,
+//!   "name": "@Fib",
+//!   "prime": "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+//!   "columns": [{ "name": "main.a", "public": false }, ...],
+//!   "inputs": [{ "name": "main.arg0", "public": true }, ...],
+//!   "transition": [<constraint>, ...],
+//!   "boundary": [<constraint>, ...]
+//! }
+//! ```
+//!
+//! A constraint is a polynomial that must be zero on the rows of its domain, written as a sum of
+//! terms:
+//!
+//! ```text
+//! {
+//!   "location": "loc(\"fib.llzk\":12:7)",
+//!   "domain": { "skip_first": 0, "skip_last": 1 },
+//!   "terms": [{ "coeff": "1", "factors": [<factor>, ...] }, ...]
+//! }
+//! ```
+//!
+//! where the domain is `"first"` or `"last"` for boundary constraints, and a factor is
+//! `{ "column": 0, "offset": 1, "exp": 2 }` for the value of a column in the row at the given
+//! offset from the current one, or `{ "input": 0, "exp": 1 }` for an input. The constant term has
+//! no factors.
+
+use super::collect_structs;
+use crate::{
+    dialect::r#struct::{StructDefOpLike as _, StructDefOpRef},
+    error::Error,
+    interpreter::Field,
+    json::Json,
+    polynomial::{Polynomial, PolynomialSystem, VariableKind},
+};
+use melior::ir::{Attribute, Module, attribute::StringAttribute, operation::OperationLike as _};
+use num_bigint::BigUint;
+use std::collections::HashMap;
+
+const TARGET: &str = "AIR";
+
+/// Version of the JSON schema of [`Air::to_json`].
+pub const AIR_VERSION: u64 = 2;
+
+/// Name of the attribute of `constrain.eq` that restricts the constraint to the `"first"` or the
+/// `"last"` row of the trace.
+pub const ROW_ATTRIBUTE: &str = "air.row";
+
+/// A column or an input of an AIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AirVariable {
+    /// Path of the member or input, like `main.sub.x`.
+    pub name: String,
+    /// Whether the column or input is public.
+    pub public: bool,
+}
+
+/// A value a constraint refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    /// The value of a column in the row at the given offset from the current one.
+    Column {
+        /// Index of the column.
+        index: usize,
+        /// Row offset, zero for the current row.
+        offset: i64,
+    },
+    /// An input, by index.
+    Input(usize),
+}
+
+/// A term of a constraint: a coefficient times a product of powers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AirTerm {
+    /// Non-zero coefficient of the term.
+    pub coeff: BigUint,
+    /// Values of the product with their exponents. Empty for the constant term.
+    pub factors: Vec<(Reference, u32)>,
+}
+
+/// The rows of the trace a constraint applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    /// The first row.
+    FirstRow,
+    /// The last row.
+    LastRow,
+    /// Every row except the first `skip_first` and the last `skip_last` ones, so that the rows
+    /// the constraint reads at an offset exist.
+    Rows {
+        /// Largest backward offset of the reads of the constraint.
+        skip_first: u64,
+        /// Largest forward offset of the reads of the constraint.
+        skip_last: u64,
+    },
+}
+
+/// A polynomial that must be zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AirConstraint {
+    /// Terms of the polynomial.
+    pub terms: Vec<AirTerm>,
+    /// Rows the polynomial must be zero on.
+    pub domain: Domain,
+    /// Location of the `constrain.eq` the constraint comes from.
+    pub location: String,
+}
+
+/// An AIR description of a column struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Air {
+    /// Fully qualified name of the struct.
+    pub name: String,
+    /// Prime of the field the constraints are defined over.
+    pub prime: BigUint,
+    /// Columns of the trace, in the order of the members.
+    pub columns: Vec<AirVariable>,
+    /// Inputs of the `@constrain` function, in order.
+    pub inputs: Vec<AirVariable>,
+    /// Constraints that apply to a range of rows.
+    pub transition: Vec<AirConstraint>,
+    /// Constraints that apply to the first or the last row.
+    pub boundary: Vec<AirConstraint>,
+}
+
+impl Air {
+    /// Translates the struct into an AIR description.
+    ///
+    /// Felt types that don't name a field are interpreted in the default field. Fails if the
+    /// struct doesn't have columns, if one of its felt members is not a column, if its
+    /// constraints cannot be evaluated into polynomials, or if a boundary constraint has an invalid
+    /// row or reads another row.
+    pub fn from_struct(def: StructDefOpRef, default_field: &Field) -> Result<Self, Error> {
+        let fail = |msg: String| Error::TranslationFailed {
+            target: TARGET,
+            name: "struct.def".to_owned(),
+            location: def.location().to_string(),
+            msg,
+        };
+        if !def.has_columns() {
+            return Err(fail(format!(
+                "{} does not have column members",
+                def.fully_qualified_name()
+            )));
+        }
+        let system = PolynomialSystem::from_struct(def, default_field)?;
+
+        // Reads at an offset refer to the column of the read of the same member in the current
+        // row, which comes first.
+        let mut references = vec![];
+        let (mut columns, mut inputs) = (vec![], vec![]);
+        for var in system.variables() {
+            let reference = match var.kind {
+                VariableKind::Input => {
+                    inputs.push(AirVariable {
+                        name: var.name.clone(),
+                        public: var.public,
+                    });
+                    Reference::Input(inputs.len() - 1)
+                }
+                VariableKind::Member if !var.column => {
+                    return Err(fail(format!("member {} is not a column", var.name)));
+                }
+                VariableKind::Member if var.offset == 0 => {
+                    columns.push(AirVariable {
+                        name: var.name.clone(),
+                        public: var.public,
+                    });
+                    Reference::Column {
+                        index: columns.len() - 1,
+                        offset: 0,
+                    }
+                }
+                VariableKind::Member => {
+                    let index = columns
+                        .iter()
+                        .position(|column| column.name == var.name)
+                        .expect("columns are allocated before their reads at an offset");
+                    Reference::Column {
+                        index,
+                        offset: var.offset,
+                    }
+                }
+            };
+            references.push(reference);
+        }
+
+        let (mut transition, mut boundary) = (vec![], vec![]);
+        for polynomial in system.polynomials() {
+            let terms = terms(polynomial, &references);
+            let location = polynomial.location().to_string();
+            let row = polynomial.attribute(ROW_ATTRIBUTE);
+            let domain = domain(&terms, row).map_err(|msg| Error::TranslationFailed {
+                target: TARGET,
+                name: "constrain.eq".to_owned(),
+                location: location.clone(),
+                msg: msg.to_owned(),
+            })?;
+            let constraint = AirConstraint {
+                terms,
+                domain,
+                location,
+            };
+            match domain {
+                Domain::Rows { .. } => transition.push(constraint),
+                Domain::FirstRow | Domain::LastRow => boundary.push(constraint),
+            }
+        }
+
+        Ok(Self {
+            name: def.fully_qualified_name().to_string(),
+            prime: system.field().prime().clone(),
+            columns,
+            inputs,
+            transition,
+            boundary,
+        })
+    }
+
+    /// Serializes the description to JSON, following the schema in the [module](self)
+    /// documentation.
+    pub fn to_json(&self) -> String {
+        let variables = |variables: &[AirVariable]| {
+            Json::array(variables.iter().map(|var| {
+                Json::object([
+                    ("name", Json::from(var.name.as_str())),
+                    ("public", Json::from(var.public)),
+                ])
+            }))
+        };
+        let constraints =
+            |constraints: &[AirConstraint]| Json::array(constraints.iter().map(constraint_to_json));
+        Json::object([
+            ("version", Json::from(AIR_VERSION)),
+            ("name", Json::from(self.name.as_str())),
+            ("prime", Json::from(self.prime.to_string())),
+            ("columns", variables(&self.columns)),
+            ("inputs", variables(&self.inputs)),
+            ("transition", constraints(&self.transition)),
+            ("boundary", constraints(&self.boundary)),
+        ])
+        .to_string()
+    }
+}
+
+/// Translates the main struct of the module into an AIR description.
+///
+/// Felt types that don't name a field are interpreted in the default field.
+pub fn translate_module(module: &Module, default_field: &Field) -> Result<Air, Error> {
+    let mut structs = HashMap::new();
+    collect_structs(module.body(), &mut structs)?;
+    let main = structs
+        .into_values()
+        .find(|def| def.is_main_component())
+        .ok_or(Error::GeneralError("module does not have a main struct"))?;
+    Air::from_struct(main, default_field)
+}
+
+fn terms(polynomial: &Polynomial, references: &[Reference]) -> Vec<AirTerm> {
+    polynomial
+        .terms()
+        .iter()
+        .map(|(monomial, coeff)| AirTerm {
+            coeff: coeff.clone(),
+            factors: monomial
+                .iter()
+                .map(|(var, exp)| (references[*var], *exp))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Returns the rows a constraint with the given terms and [`ROW_ATTRIBUTE`] applies to,
+/// following the rules in the [module](self) documentation.
+fn domain(terms: &[AirTerm], row: Option<Attribute>) -> Result<Domain, &'static str> {
+    let (mut min_offset, mut max_offset) = (0i64, 0i64);
+    for (reference, _) in terms.iter().flat_map(|term| &term.factors) {
+        if let Reference::Column { offset, .. } = *reference {
+            min_offset = min_offset.min(offset);
+            max_offset = max_offset.max(offset);
+        }
+    }
+    let Some(row) = row else {
+        return Ok(Domain::Rows {
+            skip_first: min_offset.unsigned_abs(),
+            skip_last: max_offset.unsigned_abs(),
+        });
+    };
+    let domain = match StringAttribute::try_from(row).map(|row| row.value()) {
+        Ok("first") => Domain::FirstRow,
+        Ok("last") => Domain::LastRow,
+        _ => return Err("the air.row attribute must be \"first\" or \"last\""),
+    };
+    if (min_offset, max_offset) != (0, 0) {
+        return Err("boundary constraints cannot read other rows");
+    }
+    Ok(domain)
+}
+
+fn domain_to_json(domain: Domain) -> Json {
+    match domain {
+        Domain::FirstRow => Json::from("first"),
+        Domain::LastRow => Json::from("last"),
+        Domain::Rows {
+            skip_first,
+            skip_last,
+        } => Json::object([
+            ("skip_first", Json::from(skip_first)),
+            ("skip_last", Json::from(skip_last)),
+        ]),
+    }
+}
+
+fn constraint_to_json(constraint: &AirConstraint) -> Json {
+    let terms = constraint.terms.iter().map(|term| {
+        let factors = term.factors.iter().map(|(reference, exp)| match reference {
+            Reference::Column { index, offset } => Json::object([
+                ("column", Json::from(*index)),
+                ("offset", Json::from(*offset)),
+                ("exp", Json::from(u64::from(*exp))),
+            ]),
+            Reference::Input(index) => Json::object([
+                ("input", Json::from(*index)),
+                ("exp", Json::from(u64::from(*exp))),
+            ]),
+        });
+        Json::object([
+            ("coeff", Json::from(term.coeff.to_string())),
+            ("factors", Json::array(factors)),
+        ])
+    });
+    Json::object([
+        ("location", Json::from(constraint.location.as_str())),
+        ("domain", domain_to_json(constraint.domain)),
+        ("terms", Json::array(terms)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+
+    fn column(index: usize, offset: i64) -> Reference {
+        Reference::Column { index, offset }
+    }
+
+    fn term(coeff: BigUint, factors: &[Reference]) -> AirTerm {
+        AirTerm {
+            coeff,
+            factors: factors.iter().map(|reference| (*reference, 1)).collect(),
+        }
+    }
+
+    #[test]
+    fn translate_fibonacci() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/fibonacci.mlir")).unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let air = translate_module(&module, &field).unwrap();
+        assert_eq!(air.name, "@Fib");
+        assert_eq!(&air.prime, field.prime());
+        let names = air
+            .columns
+            .iter()
+            .map(|column| (column.name.as_str(), column.public))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [("main.a", false), ("main.b", true), ("main.i", false)]
+        );
+        let names = air
+            .inputs
+            .iter()
+            .map(|input| (input.name.as_str(), input.public))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("main.arg0", true), ("main.arg1", true)]);
+
+        let one = BigUint::from(1u8);
+        let minus_one = field.neg(&one);
+        let constraints = |constraints: &[AirConstraint]| {
+            constraints
+                .iter()
+                .map(|constraint| (constraint.domain, constraint.terms.clone()))
+                .collect::<Vec<_>>()
+        };
+        // The initial conditions hold in the first row and the result in the last one, as their
+        // row attributes say.
+        assert_eq!(
+            constraints(&air.boundary),
+            [
+                (
+                    Domain::FirstRow,
+                    vec![
+                        term(one.clone(), &[column(0, 0)]),
+                        term(minus_one.clone(), &[Reference::Input(0)]),
+                    ]
+                ),
+                (Domain::FirstRow, vec![term(one.clone(), &[column(2, 0)])]),
+                (
+                    Domain::LastRow,
+                    vec![
+                        term(one.clone(), &[column(1, 0)]),
+                        term(minus_one.clone(), &[Reference::Input(1)]),
+                    ]
+                ),
+            ]
+        );
+        // The transitions read the next row, so they don't apply to the last one.
+        let rows = Domain::Rows {
+            skip_first: 0,
+            skip_last: 1,
+        };
+        assert_eq!(
+            constraints(&air.transition),
+            [
+                (
+                    rows,
+                    vec![
+                        term(minus_one.clone(), &[column(1, 0)]),
+                        term(one.clone(), &[column(0, 1)]),
+                    ]
+                ),
+                (
+                    rows,
+                    vec![
+                        term(minus_one.clone(), &[column(0, 0)]),
+                        term(minus_one.clone(), &[column(1, 0)]),
+                        term(one.clone(), &[column(1, 1)]),
+                    ]
+                ),
+                (
+                    rows,
+                    vec![
+                        term(minus_one.clone(), &[]),
+                        term(minus_one, &[column(2, 0)]),
+                        term(one, &[column(2, 1)]),
+                    ]
+                ),
+            ]
+        );
+        assert!(
+            air.boundary[0].location.ends_with(":24:7)"),
+            "{}",
+            air.boundary[0].location
+        );
+
+        let json = air.to_json();
+        let Ok(Json::Object(entries)) = Json::parse(&json) else {
+            panic!("malformed JSON:\n{json}");
+        };
+        let keys = entries
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                "version",
+                "name",
+                "prime",
+                "columns",
+                "inputs",
+                "transition",
+                "boundary"
+            ]
+        );
+        assert!(json.contains("\"version\": 2,"), "{json}");
+        assert!(json.contains("\"domain\": \"first\","), "{json}");
+        assert!(json.contains("\"domain\": \"last\","), "{json}");
+        assert!(json.contains("\"skip_last\": 1"), "{json}");
+        assert!(json.contains("\"offset\": 1,"), "{json}");
+        assert!(json.contains("\"input\": 0,"), "{json}");
+    }
+
+    #[test]
+    fn boundary_constraints_cannot_read_other_rows() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @a : !felt.type {column}
+    function.def @compute(%x: !felt.type {llzk.pub}) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@a] = %x : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type {llzk.pub}) {
+      %a = struct.readm %self[@a] : <@Main>, !felt.type {tableOffset = 1 : index}
+      constrain.eq %a, %x : !felt.type, !felt.type {air.row = "first"}
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let err = translate_module(&module, &field).unwrap_err();
+        let Error::TranslationFailed { name, msg, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(name, "constrain.eq");
+        assert_eq!(msg, "boundary constraints cannot read other rows");
+    }
+
+    fn column_module(row: &str) -> String {
+        format!(
+            r#"module attributes {{llzk.lang, llzk.main = !struct.type<@Main>}} {{
+  struct.def @Main {{
+    struct.member @a : !felt.type {{column}}
+    function.def @compute(%x: !felt.type {{llzk.pub}}) -> !struct.type<@Main> {{
+      %self = struct.new : <@Main>
+      struct.writem %self[@a] = %x : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }}
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type {{llzk.pub}}) {{
+      %a = struct.readm %self[@a] : <@Main>, !felt.type
+      %one = felt.const 1
+      constrain.eq %a, %x : !felt.type, !felt.type
+      constrain.eq %a, %one : !felt.type, !felt.type {row}
+      function.return
+    }}
+  }}
+}}"#
+        )
+    }
+
+    #[test]
+    fn constraints_apply_to_every_row_by_default() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, &column_module("")).unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let air = translate_module(&module, &field).unwrap();
+        // Neither the input nor the constant make the constraints boundary constraints.
+        let rows = Domain::Rows {
+            skip_first: 0,
+            skip_last: 0,
+        };
+        let domains = air
+            .transition
+            .iter()
+            .map(|constraint| constraint.domain)
+            .collect::<Vec<_>>();
+        assert_eq!(domains, [rows, rows]);
+        assert!(air.boundary.is_empty());
+
+        let module = Module::parse(&ctx, &column_module(r#"{air.row = "last"}"#)).unwrap();
+        let air = translate_module(&module, &field).unwrap();
+        assert_eq!(air.transition.len(), 1);
+        assert_eq!(air.boundary.len(), 1);
+        assert_eq!(air.boundary[0].domain, Domain::LastRow);
+    }
+
+    #[test]
+    fn invalid_rows_fail() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, &column_module(r#"{air.row = "middle"}"#)).unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let err = translate_module(&module, &field).unwrap_err();
+        let Error::TranslationFailed { name, msg, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(name, "constrain.eq");
+        assert_eq!(msg, "the air.row attribute must be \"first\" or \"last\"");
+    }
+
+    #[test]
+    fn structs_without_columns_fail() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @a : !felt.type
+    function.def @compute(%x: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@a] = %x : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type) {
+      %a = struct.readm %self[@a] : <@Main>, !felt.type
+      constrain.eq %a, %x : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let field = Field::builtin("bn128").unwrap();
+        let err = translate_module(&module, &field).unwrap_err();
+        let Error::TranslationFailed { msg, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(msg, "@Main does not have column members");
+    }
+}
@@ -0,0 +1,37 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Fib>} {
+  struct.def @Fib {
+    struct.member @a : !felt.type {column}
+    struct.member @b : !felt.type {column, llzk.pub}
+    struct.member @i : !felt.type {column}
+    function.def @compute(%first: !felt.type {llzk.pub}, %last: !felt.type {llzk.pub}) -> !struct.type<@Fib> {
+      %self = struct.new : <@Fib>
+      %zero = felt.const 0
+      struct.writem %self[@a] = %first : <@Fib>, !felt.type
+      struct.writem %self[@b] = %first : <@Fib>, !felt.type
+      struct.writem %self[@i] = %zero : <@Fib>, !felt.type
+      function.return %self : !struct.type<@Fib>
+    }
+    function.def @constrain(%self: !struct.type<@Fib>, %first: !felt.type {llzk.pub}, %last: !felt.type {llzk.pub}) {
+      %a = struct.readm %self[@a] : <@Fib>, !felt.type
+      %b = struct.readm %self[@b] : <@Fib>, !felt.type
+      %i = struct.readm %self[@i] : <@Fib>, !felt.type
+      %next_a = struct.readm %self[@a] : <@Fib>, !felt.type {tableOffset = 1 : index}
+      %next_b = struct.readm %self[@b] : <@Fib>, !felt.type {tableOffset = 1 : index}
+      %next_i = struct.readm %self[@i] : <@Fib>, !felt.type {tableOffset = 1 : index}
+      %zero = felt.const 0
+      %one = felt.const 1
+      // The first row starts the sequence and counts the steps from zero.
+      constrain.eq %a, %first : !felt.type, !felt.type {air.row = "first"}
+      constrain.eq %i, %zero : !felt.type, !felt.type {air.row = "first"}
+      // Each row computes the next one.
+      constrain.eq %next_a, %b : !felt.type, !felt.type
+      %sum = felt.add %a, %b : !felt.type, !felt.type
+      constrain.eq %next_b, %sum : !felt.type, !felt.type
+      %step = felt.add %i, %one : !felt.type, !felt.type
+      constrain.eq %next_i, %step : !felt.type, !felt.type
+      // The public column holds the result in the last row.
+      constrain.eq %b, %last : !felt.type, !felt.type {air.row = "last"}
+      function.return
+    }
+  }
+}
@@ -0,0 +1,319 @@
+//! Rendering of LLZK IR as Graphviz DOT graphs.
+//!
+//! Two views are available:
+//!
+//!  - [`struct_graph`] renders the struct instantiation graph of a module: one node per
+//!    `struct.def`, labelled with its template params, and one edge per member whose type is a
+//!    struct, or an array of structs, from the struct that holds the member to the struct it
+//!    instantiates. Edges are labelled with the member name, the array dimensions and the params
+//!    of the instantiation.
+//!  - [`dataflow_graph`] renders the dataflow of a function: the operations and arguments whose
+//!    values feed a `constrain.eq`, following use-def chains backwards, with one edge per operand.
+//!    Operations that don't contribute to a constraint are left out.
+//!
+//! The output is deterministic: nodes and edges appear in the order of the IR.
+
+use crate::{
+    dialect::{
+        array::ArrayType,
+        constrain::is_eq_op,
+        function::FuncDefOpLike,
+        poly::{TemplateOpLike as _, is_template_op, ops::TemplateOpRef},
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef, StructType},
+    },
+    error::Error,
+    operation::OperationIterExt as _,
+};
+use melior::ir::{
+    Attribute, BlockLike as _, Module, OperationRef, RegionLike as _, ValueLike as _,
+    attribute::{FlatSymbolRefAttribute, IntegerAttribute, SymbolRefAttribute},
+    operation::OperationLike,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    os::raw::c_void,
+};
+
+/// Renders the struct instantiation graph of the module, including the structs defined in nested
+/// modules and templates.
+pub fn struct_graph(module: &Module) -> String {
+    let mut graph = String::new();
+    let _ = writeln!(graph, "digraph structs {{");
+    let _ = writeln!(graph, "  node [shape=box];");
+    let defs = module
+        .as_operation()
+        .descendants::<StructDefOpRef>()
+        .collect::<Vec<_>>();
+    for def in &defs {
+        let name = def.fully_qualified_name().to_string();
+        let label = match template_params(def) {
+            Some(params) => format!("{name}<[{}]>", params.join(", ")),
+            None => name.clone(),
+        };
+        let _ = writeln!(graph, "  {} [label={}];", quote(&name), quote(&label));
+    }
+    for def in &defs {
+        let from = def.fully_qualified_name().to_string();
+        for member in def.member_defs() {
+            let mut ty = member.member_type();
+            let mut label = format!("@{}", member.member_name());
+            if let Ok(array) = ArrayType::try_from(ty) {
+                let dims = array.dims().into_iter().map(param).collect::<Vec<_>>();
+                let _ = write!(label, "[{}]", dims.join(", "));
+                ty = array.element_type();
+            }
+            let Ok(ty) = StructType::try_from(ty) else {
+                continue;
+            };
+            let params = ty.params_vec();
+            if !params.is_empty() {
+                let params = params.into_iter().map(param).collect::<Vec<_>>();
+                let _ = write!(label, " <[{}]>", params.join(", "));
+            }
+            let _ = writeln!(
+                graph,
+                "  {} -> {} [label={}];",
+                quote(&from),
+                quote(&ty.name().to_string()),
+                quote(&label)
+            );
+        }
+    }
+    graph.push_str("}\n");
+    graph
+}
+
+/// Renders the dataflow graph of the values that feed the `constrain.eq` operations of the
+/// function, including the ones nested in its regions.
+pub fn dataflow_graph<'c: 'a, 'a>(func: &impl FuncDefOpLike<'c, 'a>) -> Result<String, Error> {
+    let mut dataflow = Dataflow::default();
+    let body = func.body()?;
+    if let Some(block) = body.first_block() {
+        for index in 0..block.argument_count() {
+            let label = func
+                .arg_name(index)?
+                .map_or_else(|| format!("arg{index}"), |name| format!("%{name}"));
+            dataflow.add_argument(block.argument(index)?.to_raw().ptr, label);
+        }
+    }
+    for op in func.descendants::<OperationRef>() {
+        let node = dataflow.add_operation(&op);
+        for operand in op.operands() {
+            let source = match dataflow.values.get(&operand.to_raw().ptr) {
+                Some(&source) => source,
+                // Arguments of the blocks nested in the function, defined by the op that owns the
+                // region.
+                None if operand.is_block_argument() => {
+                    let label = format!("arg{}", dataflow.arguments);
+                    dataflow.add_argument(operand.to_raw().ptr, label)
+                }
+                None => continue,
+            };
+            dataflow.edges.push((source, node));
+        }
+        for result in op.results() {
+            dataflow.values.insert(result.to_raw().ptr, node);
+        }
+    }
+    Ok(dataflow.render(&func.fully_qualified_name().to_string()))
+}
+
+/// Returns the names of the params of the template that defines the struct, if any.
+fn template_params(def: &StructDefOpRef) -> Option<Vec<String>> {
+    let parent = def.parent_operation().filter(is_template_op)?;
+    let template = TemplateOpRef::try_from(parent).ok()?;
+    Some(
+        template
+            .const_param_names()
+            .into_iter()
+            .map(|name| format!("@{}", name.value()))
+            .collect(),
+    )
+}
+
+/// Formats a struct param or an array dimension, omitting the type of integers.
+fn param(attr: Attribute) -> String {
+    if let Ok(int) = IntegerAttribute::try_from(attr) {
+        int.value().to_string()
+    } else {
+        attr.to_string()
+    }
+}
+
+/// Quotes the string as a DOT identifier.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A node of a dataflow graph.
+struct Node {
+    id: String,
+    label: String,
+    attrs: &'static str,
+}
+
+/// A dataflow graph under construction.
+#[derive(Default)]
+struct Dataflow {
+    nodes: Vec<Node>,
+    /// Edges from the node that defines a value to the node that uses it.
+    edges: Vec<(usize, usize)>,
+    /// The node that defines each value.
+    values: HashMap<*const c_void, usize>,
+    /// Number of argument nodes.
+    arguments: usize,
+    /// Number of operation nodes.
+    operations: usize,
+    /// Nodes of the `constrain.eq` operations.
+    constraints: Vec<usize>,
+}
+
+impl Dataflow {
+    fn add_argument(&mut self, value: *const c_void, label: String) -> usize {
+        self.nodes.push(Node {
+            id: format!("a{}", self.arguments),
+            label,
+            attrs: ", shape=ellipse",
+        });
+        self.arguments += 1;
+        self.values.insert(value, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn add_operation(&mut self, op: &OperationRef) -> usize {
+        let constraint = is_eq_op(op);
+        self.nodes.push(Node {
+            id: format!("n{}", self.operations),
+            label: op_label(op),
+            attrs: if constraint { ", style=bold" } else { "" },
+        });
+        self.operations += 1;
+        if constraint {
+            self.constraints.push(self.nodes.len() - 1);
+        }
+        self.nodes.len() - 1
+    }
+
+    /// Renders the nodes and edges that lead to a constraint.
+    fn render(&self, name: &str) -> String {
+        let mut sources = vec![vec![]; self.nodes.len()];
+        for &(source, user) in &self.edges {
+            sources[user].push(source);
+        }
+        let mut live = HashSet::new();
+        let mut stack = self.constraints.clone();
+        while let Some(node) = stack.pop() {
+            if live.insert(node) {
+                stack.extend(&sources[node]);
+            }
+        }
+
+        let mut graph = String::new();
+        let _ = writeln!(graph, "digraph {} {{", quote(name));
+        let _ = writeln!(graph, "  node [shape=box];");
+        for (index, node) in self.nodes.iter().enumerate() {
+            if live.contains(&index) {
+                let _ = writeln!(
+                    graph,
+                    "  {} [label={}{}];",
+                    node.id,
+                    quote(&node.label),
+                    node.attrs
+                );
+            }
+        }
+        for &(source, user) in &self.edges {
+            if live.contains(&user) {
+                let _ = writeln!(
+                    graph,
+                    "  {} -> {};",
+                    self.nodes[source].id, self.nodes[user].id
+                );
+            }
+        }
+        graph.push_str("}\n");
+        graph
+    }
+}
+
+/// Returns the name of the operation, followed by the member, callee or constant it refers to.
+fn op_label(op: &OperationRef) -> String {
+    let name = op.name();
+    let name = name.as_string_ref().as_str().unwrap_or("<unknown>");
+    let detail = ["member_name", "callee", "value"]
+        .into_iter()
+        .find_map(|attr| op.attribute(attr).ok())
+        .map(|attr| {
+            FlatSymbolRefAttribute::try_from(attr)
+                .map(|attr| format!("@{}", attr.value()))
+                .or_else(|_| SymbolRefAttribute::try_from(attr).map(|attr| attr.to_string()))
+                .unwrap_or_else(|_| attr.to_string())
+        });
+    match detail {
+        Some(detail) => format!("{name} {detail}"),
+        None => name.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::LlzkContext, dialect::module::ModuleExt as _};
+
+    #[test]
+    fn render_struct_graph() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/structs.mlir")).unwrap();
+        let graph = struct_graph(&module);
+        assert!(graph.starts_with("digraph structs {\n"), "{graph}");
+        assert!(
+            graph.contains("  \"@Main\" [label=\"@Main\"];\n"),
+            "{graph}"
+        );
+        assert!(graph.contains("<[@N]>\"];\n"), "{graph}");
+        assert!(graph.contains("[label=\"@one <[4]>\"];\n"), "{graph}");
+        assert!(graph.contains("[label=\"@many[2] <[8]>\"];\n"), "{graph}");
+        assert!(graph.contains("[label=\"@leaf\"];\n"), "{graph}");
+        // Felt members don't instantiate structs.
+        assert!(!graph.contains("@x\""), "{graph}");
+        assert_eq!(graph.matches(" -> ").count(), 3, "{graph}");
+    }
+
+    #[test]
+    fn render_dataflow_graph() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/dataflow.mlir")).unwrap();
+        let main = module
+            .structs()
+            .find(|def| def.sym_name() == "Main")
+            .unwrap();
+        let graph = dataflow_graph(&main.constrain_func().unwrap()).unwrap();
+        assert_eq!(
+            graph,
+            "digraph \"@Main::@constrain\" {\n  \
+               node [shape=box];\n  \
+               a0 [label=\"arg0\", shape=ellipse];\n  \
+               a1 [label=\"arg1\", shape=ellipse];\n  \
+               a2 [label=\"arg2\", shape=ellipse];\n  \
+               n0 [label=\"struct.readm @c\"];\n  \
+               n1 [label=\"felt.add\"];\n  \
+               n2 [label=\"felt.mul\"];\n  \
+               n3 [label=\"constrain.eq\", style=bold];\n  \
+               a0 -> n0;\n  \
+               a1 -> n1;\n  \
+               a2 -> n1;\n  \
+               n1 -> n2;\n  \
+               n1 -> n2;\n  \
+               n0 -> n3;\n  \
+               n2 -> n3;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn quote_identifiers() {
+        assert_eq!(quote("@A"), "\"@A\"");
+        assert_eq!(quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    }
+}
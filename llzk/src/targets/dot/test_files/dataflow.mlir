@@ -0,0 +1,20 @@
+module attributes {llzk.lang} {
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    function.def @compute(%a: !felt.type, %b: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %sum = felt.add %a, %b : !felt.type, !felt.type
+      %square = felt.mul %sum, %sum : !felt.type, !felt.type
+      struct.writem %self[@c] = %square : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type, %b: !felt.type) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      %sum = felt.add %a, %b : !felt.type, !felt.type
+      %square = felt.mul %sum, %sum : !felt.type, !felt.type
+      constrain.eq %c, %square : !felt.type, !felt.type
+      %unused = felt.mul %a, %b : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
@@ -0,0 +1,39 @@
+module attributes {llzk.lang} {
+  module @lib attributes {llzk.lang} {
+    struct.def @Leaf {
+      struct.member @x : !felt.type
+      function.def @compute() -> !struct.type<@lib::@Leaf> {
+        %self = struct.new : <@lib::@Leaf>
+        function.return %self : !struct.type<@lib::@Leaf>
+      }
+      function.def @constrain(%self: !struct.type<@lib::@Leaf>) {
+        function.return
+      }
+    }
+  }
+  poly.template @Wrap {
+    poly.param @N
+    struct.def @Wrap {
+      struct.member @leaf : !struct.type<@lib::@Leaf>
+      function.def @compute() -> !struct.type<@Wrap::@Wrap<[@N]>> attributes {function.allow_witness} {
+        %self = struct.new : <@Wrap::@Wrap<[@N]>>
+        function.return %self : !struct.type<@Wrap::@Wrap<[@N]>>
+      }
+      function.def @constrain(%self: !struct.type<@Wrap::@Wrap<[@N]>>) attributes {function.allow_constraint} {
+        function.return
+      }
+    }
+  }
+  struct.def @Main {
+    struct.member @x : !felt.type
+    struct.member @one : !struct.type<@Wrap::@Wrap<[4]>>
+    struct.member @many : !array.type<2 x !struct.type<@Wrap::@Wrap<[8]>>>
+    function.def @compute() -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>) {
+      function.return
+    }
+  }
+}
@@ -0,0 +1,590 @@
+//! Translation to the binary R1CS format used by circom and snarkjs.
+//!
+//! The translation works on the `@constrain` functions of the main struct and of the
+//! subcomponents it contains. The module must be flattened and its arrays lowered to scalars
+//! beforehand, for example with the flattening and array-to-scalar passes.
+//!
+//! Wires are assigned in the order the format expects:
+//!
+//!  1. The constant one.
+//!  2. Public members of the main struct (public outputs).
+//!  3. Public arguments of the main `@constrain` function (public inputs).
+//!  4. The remaining arguments of the main `@constrain` function (private inputs).
+//!  5. The remaining members of the main struct and the members of its subcomponents, depth first.
+//!
+//! Each `constrain.eq` becomes one constraint `A * B - C = 0`. The translation fails if the
+//! difference of its operands is not of the form `A * B + C` for linear combinations `A`, `B` and
+//! `C`.
+
+use super::symbolic::{
+    self, Component, Components, Member, constrain_func, symbol_attr, walk_constrain,
+};
+use crate::{
+    dialect::{
+        felt::FeltConstAttribute,
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _},
+        r#struct::{MemberDefOpLike as _, MemberDefOpRef, StructDefOpLike as _, StructDefOpRef},
+    },
+    error::Error,
+    interpreter::Field,
+};
+use melior::ir::{Module, OperationRef, ValueLike, operation::OperationLike};
+use num_bigint::BigUint;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    os::raw::c_void,
+};
+
+const TARGET: &str = "R1CS";
+
+/// A linear combination of wires, mapping wire indices to their non-zero coefficients.
+pub type LinearCombination = BTreeMap<usize, BigUint>;
+
+/// A constraint of the form `A * B - C = 0`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Constraint {
+    /// Left factor of the product.
+    pub a: LinearCombination,
+    /// Right factor of the product.
+    pub b: LinearCombination,
+    /// Linear combination the product must be equal to.
+    pub c: LinearCombination,
+}
+
+/// A rank-1 constraint system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct R1cs {
+    prime: BigUint,
+    wire_names: Vec<String>,
+    public_outputs: usize,
+    public_inputs: usize,
+    private_inputs: usize,
+    constraints: Vec<Constraint>,
+}
+
+impl R1cs {
+    /// Returns the prime of the field the constraints are defined over.
+    pub fn prime(&self) -> &BigUint {
+        &self.prime
+    }
+
+    /// Returns the names of the wires, indexed by wire. Wire 0 is the constant one.
+    pub fn wire_names(&self) -> &[String] {
+        &self.wire_names
+    }
+
+    /// Returns the number of public outputs.
+    pub fn public_outputs(&self) -> usize {
+        self.public_outputs
+    }
+
+    /// Returns the number of public inputs.
+    pub fn public_inputs(&self) -> usize {
+        self.public_inputs
+    }
+
+    /// Returns the number of private inputs.
+    pub fn private_inputs(&self) -> usize {
+        self.private_inputs
+    }
+
+    /// Returns the constraints.
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Writes the constraint system in the binary `.r1cs` format.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        // Field elements are written in little endian with a size that is a multiple of 8 bytes.
+        let field_size = self.prime.bits().div_ceil(64) as usize * 8;
+        let field_element = |out: &mut Vec<u8>, value: &BigUint| {
+            let mut bytes = value.to_bytes_le();
+            bytes.resize(field_size, 0);
+            out.extend(bytes);
+        };
+
+        let mut header = vec![];
+        header.extend(to_u32(field_size)?.to_le_bytes());
+        field_element(&mut header, &self.prime);
+        header.extend(to_u32(self.wire_names.len())?.to_le_bytes());
+        header.extend(to_u32(self.public_outputs)?.to_le_bytes());
+        header.extend(to_u32(self.public_inputs)?.to_le_bytes());
+        header.extend(to_u32(self.private_inputs)?.to_le_bytes());
+        header.extend((self.wire_names.len() as u64).to_le_bytes());
+        header.extend(to_u32(self.constraints.len())?.to_le_bytes());
+
+        let mut constraints = vec![];
+        for constraint in &self.constraints {
+            for lc in [&constraint.a, &constraint.b, &constraint.c] {
+                constraints.extend(to_u32(lc.len())?.to_le_bytes());
+                for (wire, coeff) in lc {
+                    constraints.extend(to_u32(*wire)?.to_le_bytes());
+                    field_element(&mut constraints, coeff);
+                }
+            }
+        }
+
+        // Each wire is its own label.
+        let labels: Vec<u8> = (0..self.wire_names.len() as u64)
+            .flat_map(u64::to_le_bytes)
+            .collect();
+
+        writer.write_all(b"r1cs")?;
+        writer.write_all(&1u32.to_le_bytes())?;
+        writer.write_all(&3u32.to_le_bytes())?;
+        for (section, content) in [(1u32, header), (2, constraints), (3, labels)] {
+            writer.write_all(&section.to_le_bytes())?;
+            writer.write_all(&(content.len() as u64).to_le_bytes())?;
+            writer.write_all(&content)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the constraint system in the binary `.r1cs` format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_to(&mut bytes)
+            .expect("writing into a vector does not fail");
+        bytes
+    }
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "value does not fit in the R1CS format",
+        )
+    })
+}
+
+/// Translates the main struct of the module into a rank-1 constraint system.
+///
+/// Felt types that don't name a field are interpreted in the default field.
+pub fn translate_module(module: &Module, default_field: &Field) -> Result<R1cs, Error> {
+    let components = Components::new(TARGET, &module.as_operation(), default_field)?;
+    let main = components
+        .structs()
+        .find(|def| def.is_main_component())
+        .ok_or(Error::GeneralError("module does not have a main struct"))?;
+
+    let mut translator = Translator {
+        components,
+        wire_names: vec!["one".to_owned()],
+        constraints: vec![],
+    };
+    let (main_id, inputs, counts) = translator.allocate_main(main)?;
+    translator.translate_constrain(main_id, inputs)?;
+
+    let (public_outputs, public_inputs, private_inputs) = counts;
+    Ok(R1cs {
+        prime: translator.components.field().prime().clone(),
+        wire_names: translator.wire_names,
+        public_outputs,
+        public_inputs,
+        private_inputs,
+        constraints: translator.constraints,
+    })
+}
+
+fn fail<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, msg: impl Into<String>) -> Error {
+    symbolic::fail(TARGET, op, msg)
+}
+
+/// Symbolic value of an SSA value.
+#[derive(Clone)]
+enum Expr {
+    Linear(LinearCombination),
+    /// `a * b + c`
+    Quadratic(LinearCombination, LinearCombination, LinearCombination),
+    Component(usize),
+}
+
+fn constant(value: BigUint) -> LinearCombination {
+    let mut lc = LinearCombination::new();
+    if value != BigUint::ZERO {
+        lc.insert(0, value);
+    }
+    lc
+}
+
+/// Returns the value of the linear combination if it only depends on the constant wire.
+fn as_constant(lc: &LinearCombination) -> Option<BigUint> {
+    match lc.iter().next_back() {
+        None => Some(BigUint::ZERO),
+        Some((0, value)) => Some(value.clone()),
+        Some(_) => None,
+    }
+}
+
+fn add(field: &Field, lhs: &LinearCombination, rhs: &LinearCombination) -> LinearCombination {
+    let mut sum = lhs.clone();
+    for (wire, coeff) in rhs {
+        let value = field.add(sum.get(wire).unwrap_or(&BigUint::ZERO), coeff);
+        if value == BigUint::ZERO {
+            sum.remove(wire);
+        } else {
+            sum.insert(*wire, value);
+        }
+    }
+    sum
+}
+
+fn scale(field: &Field, lc: &LinearCombination, factor: &BigUint) -> LinearCombination {
+    lc.iter()
+        .map(|(wire, coeff)| (*wire, field.mul(coeff, factor)))
+        .filter(|(_, coeff)| *coeff != BigUint::ZERO)
+        .collect()
+}
+
+fn negate(field: &Field, lc: &LinearCombination) -> LinearCombination {
+    scale(field, lc, &field.neg(&BigUint::from(1u8)))
+}
+
+struct Translator<'c, 'm> {
+    /// Components whose felt members are wires.
+    components: Components<'c, 'm, usize>,
+    wire_names: Vec<String>,
+    constraints: Vec<Constraint>,
+}
+
+impl<'c, 'm> Translator<'c, 'm> {
+    fn new_wire(&mut self, name: String) -> usize {
+        self.wire_names.push(name);
+        self.wire_names.len() - 1
+    }
+
+    /// Allocates the wires of the main struct. Returns the id of its component, the symbolic
+    /// values of its inputs and the number of public outputs, public inputs and private inputs.
+    fn allocate_main(
+        &mut self,
+        main: StructDefOpRef<'c, 'm>,
+    ) -> Result<(usize, Vec<Expr>, (usize, usize, usize)), Error> {
+        let prefix = "main";
+        let mut members = HashMap::new();
+        let member_defs = main.member_defs();
+        for member in member_defs.iter().filter(|member| member.has_public_attr()) {
+            self.components.field_of(member, member.member_type())?;
+            let wire = self.new_wire(format!("{prefix}.{}", member.member_name()));
+            members.insert(member.member_name().to_owned(), Member::Felt(wire));
+        }
+        let public_outputs = members.len();
+
+        let func = constrain_func(main)?;
+        let arg_count = func.arg_count()?;
+        let mut inputs = vec![None; arg_count];
+        let mut counts = [0usize; 2];
+        for (public, count) in [true, false].into_iter().zip(&mut counts) {
+            for index in 1..arg_count {
+                if func.arg_is_pub(u32::try_from(index).expect("argument index fits in u32"))
+                    != public
+                {
+                    continue;
+                }
+                let arg = func.argument(index)?;
+                self.components.field_of(&func, arg.r#type())?;
+                let name = func
+                    .arg_name(index)?
+                    .unwrap_or_else(|| format!("arg{}", index - 1));
+                let wire = self.new_wire(format!("{prefix}.{name}"));
+                inputs[index] = Some(Expr::Linear(LinearCombination::from([(
+                    wire,
+                    BigUint::from(1u8),
+                )])));
+                *count += 1;
+            }
+        }
+
+        let wire_names = &mut self.wire_names;
+        let mut new_wire = |name: String, _: &MemberDefOpRef<'c, 'm>| {
+            wire_names.push(name);
+            wire_names.len() - 1
+        };
+        for member in member_defs
+            .iter()
+            .filter(|member| !member.has_public_attr())
+        {
+            let value = self
+                .components
+                .allocate_member(prefix, member, &mut new_wire)?;
+            members.insert(member.member_name().to_owned(), value);
+        }
+
+        let id = self.components.push(Component { def: main, members });
+        let inputs = inputs.into_iter().skip(1).flatten().collect();
+        Ok((id, inputs, (public_outputs, counts[0], counts[1])))
+    }
+
+    /// Translates the constraints of the `@constrain` function of a component.
+    fn translate_constrain(&mut self, component: usize, inputs: Vec<Expr>) -> Result<(), Error> {
+        let mut args = vec![Expr::Component(component)];
+        args.extend(inputs);
+        walk_constrain(
+            TARGET,
+            self.components[component].def,
+            args,
+            |values, op| self.translate_op(values, op),
+        )
+    }
+
+    fn translate_op(
+        &mut self,
+        values: &mut HashMap<*const c_void, Expr>,
+        op: OperationRef<'c, 'm>,
+    ) -> Result<(), Error> {
+        let operand = |index: usize| -> Result<Expr, Error> {
+            values
+                .get(&op.operand(index)?.to_raw().ptr)
+                .cloned()
+                .ok_or_else(|| fail(&op, "use of a value that was not translated"))
+        };
+        let linear = |expr: Expr| match expr {
+            Expr::Linear(lc) => Ok(lc),
+            _ => Err(fail(&op, "expected a linear combination")),
+        };
+
+        let name = op.name();
+        let result = match name.as_string_ref().as_str()? {
+            "felt.const" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let value = FeltConstAttribute::try_from(op.attribute("value")?)?.to_bigint();
+                Expr::Linear(constant(field.from_signed(&value)))
+            }
+            "felt.add" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                self.add(&field, &op, operand(0)?, operand(1)?)?
+            }
+            "felt.sub" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let rhs = self.negate(&field, &op, operand(1)?)?;
+                self.add(&field, &op, operand(0)?, rhs)?
+            }
+            "felt.neg" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                self.negate(&field, &op, operand(0)?)?
+            }
+            "felt.mul" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                match (operand(0)?, operand(1)?) {
+                    (Expr::Linear(lhs), Expr::Linear(rhs)) => {
+                        match (as_constant(&lhs), as_constant(&rhs)) {
+                            (Some(factor), _) => Expr::Linear(scale(&field, &rhs, &factor)),
+                            (_, Some(factor)) => Expr::Linear(scale(&field, &lhs, &factor)),
+                            _ => Expr::Quadratic(lhs, rhs, LinearCombination::new()),
+                        }
+                    }
+                    (Expr::Quadratic(a, b, c), Expr::Linear(factor))
+                    | (Expr::Linear(factor), Expr::Quadratic(a, b, c)) => {
+                        let factor = as_constant(&factor)
+                            .ok_or_else(|| fail(&op, "expression is not rank-1"))?;
+                        Expr::Quadratic(scale(&field, &a, &factor), b, scale(&field, &c, &factor))
+                    }
+                    _ => return Err(fail(&op, "expression is not rank-1")),
+                }
+            }
+            "felt.div" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let divisor = as_constant(&linear(operand(1)?)?)
+                    .ok_or_else(|| fail(&op, "only division by constants is supported"))?;
+                let inverse = field
+                    .inv(&divisor)
+                    .ok_or_else(|| fail(&op, "division by zero"))?;
+                match operand(0)? {
+                    Expr::Linear(lc) => Expr::Linear(scale(&field, &lc, &inverse)),
+                    Expr::Quadratic(a, b, c) => {
+                        Expr::Quadratic(scale(&field, &a, &inverse), b, scale(&field, &c, &inverse))
+                    }
+                    Expr::Component(_) => return Err(fail(&op, "expected a felt value")),
+                }
+            }
+            "struct.readm" => {
+                let Expr::Component(component) = operand(0)? else {
+                    return Err(fail(&op, "expected a struct value"));
+                };
+                let member = symbol_attr(TARGET, &op, "member_name")?;
+                match self.components[component].members.get(&member) {
+                    Some(Member::Felt(wire)) => {
+                        Expr::Linear(LinearCombination::from([(*wire, BigUint::from(1u8))]))
+                    }
+                    Some(Member::Component(id)) => Expr::Component(*id),
+                    None => return Err(fail(&op, format!("struct has no member @{member}"))),
+                }
+            }
+            "constrain.eq" => {
+                let field = self.components.field_of(&op, op.operand(0)?.r#type())?;
+                let rhs = self.negate(&field, &op, operand(1)?)?;
+                let constraint = match self.add(&field, &op, operand(0)?, rhs) {
+                    Ok(Expr::Linear(lc)) => Constraint {
+                        c: negate(&field, &lc),
+                        ..Constraint::default()
+                    },
+                    Ok(Expr::Quadratic(a, b, c)) => Constraint {
+                        a,
+                        b,
+                        c: negate(&field, &c),
+                    },
+                    _ => return Err(fail(&op, "constraint is not rank-1")),
+                };
+                self.constraints.push(constraint);
+                return Ok(());
+            }
+            "function.call" => {
+                let call = CallOpRef::try_from(op)?;
+                if !call.callee_is_struct_constrain() {
+                    return Err(fail(
+                        &op,
+                        "only calls to @constrain functions of subcomponents are supported",
+                    ));
+                }
+                let Some(Expr::Component(component)) =
+                    values.get(&call.arg_operand_at(0).to_raw().ptr).cloned()
+                else {
+                    return Err(fail(&op, "expected a subcomponent as first argument"));
+                };
+                let inputs = (1..call.arg_operand_count())
+                    .map(|index| {
+                        values
+                            .get(&call.arg_operand_at(index).to_raw().ptr)
+                            .cloned()
+                            .ok_or_else(|| fail(&op, "use of a value that was not translated"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                return self.translate_constrain(component, inputs);
+            }
+            _ => {
+                return Err(fail(
+                    &op,
+                    "operation is not supported by the R1CS translation",
+                ));
+            }
+        };
+        values.insert(op.result(0)?.to_raw().ptr, result);
+        Ok(())
+    }
+
+    fn add(
+        &self,
+        field: &Field,
+        op: &OperationRef<'c, 'm>,
+        lhs: Expr,
+        rhs: Expr,
+    ) -> Result<Expr, Error> {
+        match (lhs, rhs) {
+            (Expr::Linear(lhs), Expr::Linear(rhs)) => Ok(Expr::Linear(add(field, &lhs, &rhs))),
+            (Expr::Quadratic(a, b, c), Expr::Linear(lc))
+            | (Expr::Linear(lc), Expr::Quadratic(a, b, c)) => {
+                Ok(Expr::Quadratic(a, b, add(field, &c, &lc)))
+            }
+            (Expr::Quadratic(..), Expr::Quadratic(..)) => Err(fail(op, "expression is not rank-1")),
+            _ => Err(fail(op, "expected a felt value")),
+        }
+    }
+
+    fn negate(&self, field: &Field, op: &OperationRef<'c, 'm>, expr: Expr) -> Result<Expr, Error> {
+        match expr {
+            Expr::Linear(lc) => Ok(Expr::Linear(negate(field, &lc))),
+            Expr::Quadratic(a, b, c) => {
+                Ok(Expr::Quadratic(negate(field, &a), b, negate(field, &c)))
+            }
+            Expr::Component(_) => Err(fail(op, "expected a felt value")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+
+    fn lc(terms: &[(usize, u64)]) -> LinearCombination {
+        terms
+            .iter()
+            .map(|(wire, coeff)| (*wire, BigUint::from(*coeff)))
+            .collect()
+    }
+
+    fn bn128() -> Field {
+        Field::builtin("bn128").unwrap()
+    }
+
+    #[test]
+    fn translate_division() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let r1cs = translate_module(&module, &bn128()).unwrap();
+        assert_eq!(r1cs.prime(), bn128().prime());
+        assert_eq!(
+            r1cs.wire_names(),
+            ["one", "main.c", "main.arg0", "main.arg1"]
+        );
+        assert_eq!(
+            (
+                r1cs.public_outputs(),
+                r1cs.public_inputs(),
+                r1cs.private_inputs()
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(
+            r1cs.constraints(),
+            [Constraint {
+                a: lc(&[(1, 1)]),
+                b: lc(&[(3, 1)]),
+                c: lc(&[(2, 1)]),
+            }]
+        );
+    }
+
+    #[test]
+    fn translate_linear_constraint() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/linear.mlir")).unwrap();
+        let r1cs = translate_module(&module, &bn128()).unwrap();
+        // out = 2 * in + 3
+        let minus_one = bn128().prime() - 1u8;
+        assert_eq!(
+            r1cs.constraints(),
+            [Constraint {
+                c: LinearCombination::from([
+                    (0, BigUint::from(3u8)),
+                    (1, minus_one),
+                    (2, BigUint::from(2u8)),
+                ]),
+                ..Constraint::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn reject_non_rank_1_constraint() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/cubic.mlir")).unwrap();
+        let err = translate_module(&module, &bn128()).unwrap_err();
+        assert!(
+            matches!(&err, Error::TranslationFailed { name, msg, .. } if name == "felt.mul" && msg == "expression is not rank-1"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn binary_format() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let bytes = translate_module(&module, &bn128()).unwrap().to_bytes();
+        assert_eq!(&bytes[0..4], b"r1cs");
+        assert_eq!(bytes[4..8], 1u32.to_le_bytes());
+        assert_eq!(bytes[8..12], 3u32.to_le_bytes());
+        // Header section with 32 byte field elements.
+        assert_eq!(bytes[12..16], 1u32.to_le_bytes());
+        assert_eq!(bytes[16..24], 64u64.to_le_bytes());
+        assert_eq!(bytes[24..28], 32u32.to_le_bytes());
+        assert_eq!(bytes[28..60], bn128().prime().to_bytes_le()[..]);
+        assert_eq!(bytes[60..64], 4u32.to_le_bytes());
+        // 3 linear combinations with one term each.
+        let constraints_len = 3 * (4 + 4 + 32);
+        assert_eq!(bytes[88..92], 2u32.to_le_bytes());
+        assert_eq!(bytes[92..100], (constraints_len as u64).to_le_bytes());
+        assert_eq!(bytes.len(), 100 + constraints_len + 12 + 4 * 8);
+    }
+}
@@ -0,0 +1,23 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @out : !felt.type {llzk.pub}
+    function.def @compute(%in: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %two = felt.const 2
+      %three = felt.const 3
+      %0 = felt.mul %two, %in : !felt.type, !felt.type
+      %1 = felt.add %0, %three : !felt.type, !felt.type
+      struct.writem %self[@out] = %1 : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %in: !felt.type) {
+      %out = struct.readm %self[@out] : <@Main>, !felt.type
+      %two = felt.const 2
+      %three = felt.const 3
+      %0 = felt.mul %two, %in : !felt.type, !felt.type
+      %1 = felt.add %0, %three : !felt.type, !felt.type
+      constrain.eq %out, %1 : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
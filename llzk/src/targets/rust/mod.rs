@@ -0,0 +1,987 @@
+//! Translation of `@compute` functions into Rust source code for native witness generation.
+//!
+//! Every struct of the module becomes a Rust struct with one field per member and an associated
+//! `compute` function that mirrors its `@compute` function. Free functions called from them become
+//! Rust functions. Felts are values of a `ff::PrimeField` type chosen by the caller, which the
+//! generated source calls `Felt`. Booleans are `bool`, indices and other integers `i64`, and
+//! arrays are flattened into `Vec`s.
+//!
+//! The generated functions return `Result<_, &'static str>`. They fail on division by zero, out of
+//! bounds array accesses and felts that don't fit in an index, which the
+//! [interpreter](crate::interpreter) rejects as well. Shifts follow the interpreter: `felt.shl`
+//! multiplies by a power of two modulo the prime and `felt.shr` gives zero for amounts of at least
+//! the bit width of the prime. Constraint operations are ignored. The crate that includes the
+//! source must depend on `ff`.
+//!
+//! The module must be flattened beforehand, so that template parameters are instantiated and array
+//! dimensions are concrete. Arrays are values in the generated code: writing to an array read from
+//! a member does not change the member.
+//!
+//! ```ignore
+//! let source = targets::rust::translate_module(&module, "halo2curves::bn256::Fr")?;
+//! std::fs::write(out_dir.join("witness.rs"), source)?;
+//! // In the prover crate:
+//! include!(concat!(env!("OUT_DIR"), "/witness.rs"));
+//! let main = Main::compute(a, b)?;
+//! ```
+
+use super::collect_structs;
+use crate::{
+    dialect::{
+        array::ArrayType,
+        bool::CmpPredicate,
+        felt::{FeltConstAttribute, FeltType},
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _, FuncDefOpRef},
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef, StructType},
+    },
+    error::Error,
+    interpreter::cmp_predicate,
+    operation::isa,
+    symbol_ref::SymbolRefAttribute,
+};
+use melior::ir::{
+    BlockLike as _, BlockRef, Module, OperationRef, RegionLike as _, Type, TypeLike as _, Value,
+    ValueLike as _,
+    attribute::{FlatSymbolRefAttribute, IntegerAttribute, StringAttribute},
+    operation::OperationLike,
+    r#type::IntegerType,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    os::raw::c_void,
+};
+
+const TARGET: &str = "Rust";
+
+/// Helpers used by the generated code, generic over the field.
+const RUNTIME: &str = include_str!("runtime.rs");
+
+/// Translates the `@compute` functions of the structs in the module into Rust source code.
+///
+/// `field` is the path of the `ff::PrimeField` type used for felts, for example
+/// `halo2curves::bn256::Fr`. All felts use that field, regardless of the field named by their
+/// type.
+pub fn translate_module(module: &Module, field: &str) -> Result<String, Error> {
+    let mut structs = HashMap::new();
+    collect_structs(module.body(), &mut structs)?;
+    let mut functions = HashMap::new();
+    collect_functions(module.body(), &[], &mut functions)?;
+
+    let mut generator = Generator {
+        functions,
+        queued: HashSet::new(),
+        pending: vec![],
+        out: String::new(),
+    };
+    let _ = writeln!(
+        generator.out,
+        "// Witness generators translated from LLZK IR. Do not edit.\n"
+    );
+    let _ = writeln!(
+        generator.out,
+        "/// Field of the felt values.\npub type Felt = {field};"
+    );
+
+    let mut names = structs.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        generator.translate_struct(structs[&name])?;
+    }
+    while let Some(name) = generator.pending.pop() {
+        let func = generator.functions[&name];
+        generator.translate_function(&name, func)?;
+    }
+    generator.out.push('\n');
+    generator.out.push_str(RUNTIME);
+    Ok(generator.out)
+}
+
+fn fail<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, msg: impl Into<String>) -> Error {
+    Error::TranslationFailed {
+        target: TARGET,
+        name: op
+            .name()
+            .as_string_ref()
+            .as_str()
+            .unwrap_or("<unknown>")
+            .to_owned(),
+        location: op.location().to_string(),
+        msg: msg.into(),
+    }
+}
+
+/// Collects the free functions in the block and in the modules nested in it, by Rust name.
+fn collect_functions<'c, 'm>(
+    block: BlockRef<'c, 'm>,
+    path: &[String],
+    functions: &mut HashMap<String, FuncDefOpRef<'c, 'm>>,
+) -> Result<(), Error> {
+    let mut next = block.first_operation();
+    while let Some(op) = next {
+        if let Ok(func) = FuncDefOpRef::try_from(op) {
+            let mut path = path.to_vec();
+            path.push(func.sym_name()?.value().to_owned());
+            functions.insert(rust_path(&path), func);
+        } else if isa(&op, "builtin.module") {
+            if let Some(name) = op
+                .attribute("sym_name")
+                .ok()
+                .and_then(|attr| StringAttribute::try_from(attr).ok())
+            {
+                if let Some(block) = op.region(0)?.first_block() {
+                    let mut path = path.to_vec();
+                    path.push(name.value().to_owned());
+                    collect_functions(block, &path, functions)?;
+                }
+            }
+        }
+        next = op.next_in_block();
+    }
+    Ok(())
+}
+
+/// Returns the components of a symbol reference.
+fn components(symbol: SymbolRefAttribute) -> Result<Vec<String>, Error> {
+    let mut components = vec![symbol.root().as_str()?.to_owned()];
+    components.extend(symbol.nested().iter().map(|r| r.value().to_owned()));
+    Ok(components)
+}
+
+/// Returns a Rust identifier for a symbol path.
+fn rust_path(path: &[impl AsRef<str>]) -> String {
+    let name = path
+        .iter()
+        .map(|part| {
+            part.as_ref()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("_");
+    rust_ident(name)
+}
+
+/// Makes the name usable as a Rust identifier.
+fn rust_ident(name: String) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while", "Felt",
+    ];
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+/// Returns the Rust name of the struct type.
+fn struct_name(ty: StructType) -> Result<String, Error> {
+    Ok(rust_path(&components(ty.name())?))
+}
+
+/// Returns the name of the member referenced by the `member_name` attribute of the op.
+fn member_name<'c: 'a, 'a>(op: &OperationRef<'c, 'a>) -> Result<String, Error> {
+    let attr = op.attribute("member_name")?;
+    let name = FlatSymbolRefAttribute::try_from(attr)
+        .map(|attr| attr.value())
+        .or_else(|_| StringAttribute::try_from(attr).map(|attr| attr.value()))
+        .map_err(|_| fail(op, "malformed 'member_name' attribute"))?;
+    Ok(rust_ident(name.to_owned()))
+}
+
+fn is_i1(ty: Type) -> bool {
+    IntegerType::try_from(ty).is_ok_and(|ty| ty.width() == 1)
+}
+
+/// Returns the dimensions of an array type, which must be concrete.
+fn array_dims<'c: 'a, 'a>(
+    op: &impl OperationLike<'c, 'a>,
+    ty: ArrayType<'c>,
+) -> Result<Vec<usize>, Error> {
+    (0..ty.num_dims())
+        .map(|index| {
+            IntegerAttribute::try_from(ty.dim(index))
+                .ok()
+                .and_then(|dim| usize::try_from(dim.value()).ok())
+                .ok_or_else(|| fail(op, "array dimensions must be concrete"))
+        })
+        .collect()
+}
+
+/// Returns the Rust type of an LLZK type.
+fn rust_type<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, ty: Type<'c>) -> Result<String, Error> {
+    if FeltType::try_from(ty).is_ok() {
+        Ok("Felt".to_owned())
+    } else if is_i1(ty) {
+        Ok("bool".to_owned())
+    } else if ty.is_index() || ty.is_integer() {
+        Ok("i64".to_owned())
+    } else if let Ok(array) = ArrayType::try_from(ty) {
+        Ok(format!("Vec<{}>", rust_type(op, array.element_type())?))
+    } else if let Ok(ty) = StructType::try_from(ty) {
+        struct_name(ty)
+    } else {
+        Err(fail(op, format!("type {ty} is not supported")))
+    }
+}
+
+/// Returns true if the values of the type are `Copy` in the generated code.
+fn is_copy(ty: Type) -> bool {
+    ArrayType::try_from(ty).is_err() && StructType::try_from(ty).is_err()
+}
+
+/// Formats a list of expressions as a single expression, a tuple if there are several.
+fn tuple(items: &[String]) -> String {
+    match items {
+        [item] => item.clone(),
+        items => format!("({})", items.join(", ")),
+    }
+}
+
+struct Generator<'c, 'm> {
+    functions: HashMap<String, FuncDefOpRef<'c, 'm>>,
+    /// Free functions that were queued for translation.
+    queued: HashSet<String>,
+    pending: Vec<String>,
+    out: String,
+}
+
+impl<'c: 'm, 'm> Generator<'c, 'm> {
+    fn translate_struct(&mut self, def: StructDefOpRef<'c, 'm>) -> Result<(), Error> {
+        let name = rust_path(&components(def.fully_qualified_name())?);
+        let fqn = def.fully_qualified_name().to_string();
+        let mut members = String::new();
+        for member in def.member_defs() {
+            let _ = writeln!(
+                members,
+                "    pub {}: {},",
+                rust_ident(member.member_name().to_owned()),
+                rust_type(&member, member.member_type())?
+            );
+        }
+        let _ = write!(
+            self.out,
+            "\n/// Witness of `{fqn}`.\n\
+             #[derive(Clone, Debug, Default, PartialEq, Eq)]\n\
+             pub struct {name} {{\n{members}}}\n"
+        );
+
+        let Some(func) = def.compute_func() else {
+            return Ok(());
+        };
+        let mut body = Body::new(self, &func);
+        let params = body.params(&func)?;
+        body.translate_block(
+            func.body()?.first_block().ok_or(Error::BlockExpected(0))?,
+            &[],
+        )?;
+        let code = body.code;
+        let _ = write!(
+            self.out,
+            "\nimpl {name} {{\n    \
+             /// Computes the witness of `{fqn}`.\n    \
+             #[allow(unused_mut, unused_variables, unreachable_code, clippy::all)]\n    \
+             pub fn compute({params}) -> Result<Self, &'static str> {{\n{code}    }}\n}}\n"
+        );
+        Ok(())
+    }
+
+    fn translate_function(&mut self, name: &str, func: FuncDefOpRef<'c, 'm>) -> Result<(), Error> {
+        if func.is_declaration() {
+            return Err(fail(&func, "cannot translate a function without a body"));
+        }
+        let ty = func.function_type()?;
+        let results = (0..ty.result_count())
+            .map(|index| rust_type(&func, ty.result(index)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut body = Body::new(self, &func);
+        let params = body.params(&func)?;
+        body.translate_block(
+            func.body()?.first_block().ok_or(Error::BlockExpected(0))?,
+            &[],
+        )?;
+        let code = body.code;
+        let _ = write!(
+            self.out,
+            "\n/// Translation of `{}`.\n\
+             #[allow(unused_mut, unused_variables, unreachable_code, clippy::all)]\n\
+             pub fn {name}({params}) -> Result<{}, &'static str> {{\n{code}}}\n",
+            func.fully_qualified_name(),
+            tuple(&results)
+        );
+        Ok(())
+    }
+
+    /// Returns the Rust name of the function called by the op, queueing free functions for
+    /// translation.
+    fn callee(&mut self, call: CallOpRef<'c, '_>) -> Result<String, Error> {
+        let path = components(call.callee()?)?;
+        if call.callee_is_struct_compute() {
+            return Ok(format!("{}::compute", rust_path(&path[..path.len() - 1])));
+        }
+        if call.callee_is_struct_constrain() || call.callee_is_struct_product() {
+            return Err(fail(
+                &call,
+                "only @compute functions of structs can be called",
+            ));
+        }
+        let name = rust_path(&path);
+        if !self.functions.contains_key(&name) {
+            return Err(Error::SymbolNotFound(call.callee()?.to_string()));
+        }
+        if self.queued.insert(name.clone()) {
+            self.pending.push(name.clone());
+        }
+        Ok(name)
+    }
+}
+
+/// A Rust variable that holds the value of an SSA value.
+struct Var {
+    name: String,
+    copy: bool,
+}
+
+/// Body of a generated function.
+struct Body<'g, 'c, 'm> {
+    generator: &'g mut Generator<'c, 'm>,
+    vars: HashMap<*const c_void, Var>,
+    code: String,
+    indent: usize,
+}
+
+impl<'g, 'c: 'm, 'm> Body<'g, 'c, 'm> {
+    fn new(generator: &'g mut Generator<'c, 'm>, func: &FuncDefOpRef<'c, 'm>) -> Self {
+        // The `compute` functions are nested in an `impl` block.
+        let indent = if func.is_struct_compute() { 2 } else { 1 };
+        Self {
+            generator,
+            vars: HashMap::new(),
+            code: String::new(),
+            indent,
+        }
+    }
+
+    /// Declares the arguments of the function and returns its parameter list.
+    fn params(&mut self, func: &FuncDefOpRef<'c, 'm>) -> Result<String, Error> {
+        let block = func.body()?.first_block().ok_or(Error::BlockExpected(0))?;
+        let mut params = vec![];
+        for index in 0..block.argument_count() {
+            let arg: Value = block.argument(index)?.into();
+            let name = self.bind(arg);
+            params.push(format!("{name}: {}", rust_type(func, arg.r#type())?));
+        }
+        Ok(params.join(", "))
+    }
+
+    fn line(&mut self, line: impl AsRef<str>) {
+        for _ in 0..self.indent {
+            self.code.push_str("    ");
+        }
+        self.code.push_str(line.as_ref());
+        self.code.push('\n');
+    }
+
+    /// Creates a new variable for the value and returns its name.
+    fn bind(&mut self, value: Value<'c, '_>) -> String {
+        let name = format!("v{}", self.vars.len());
+        self.vars.insert(
+            value.to_raw().ptr,
+            Var {
+                name: name.clone(),
+                copy: is_copy(value.r#type()),
+            },
+        );
+        name
+    }
+
+    /// Declares a variable for the value, initialized with the expression.
+    fn define(
+        &mut self,
+        op: &OperationRef<'c, 'm>,
+        value: Value<'c, '_>,
+        expr: impl AsRef<str>,
+    ) -> Result<(), Error> {
+        let ty = rust_type(op, value.r#type())?;
+        let name = self.bind(value);
+        self.line(format!("let mut {name}: {ty} = {};", expr.as_ref()));
+        Ok(())
+    }
+
+    /// Declares an uninitialized variable for each result of the op and returns their names.
+    fn declare_results(&mut self, op: &OperationRef<'c, 'm>) -> Result<Vec<String>, Error> {
+        op.results()
+            .map(|result| {
+                let ty = rust_type(op, result.r#type())?;
+                let name = self.bind(result.into());
+                self.line(format!("let mut {name}: {ty};"));
+                Ok(name)
+            })
+            .collect()
+    }
+
+    fn var(&self, op: &OperationRef<'c, 'm>, value: Value<'c, '_>) -> Result<&Var, Error> {
+        self.vars
+            .get(&value.to_raw().ptr)
+            .ok_or_else(|| fail(op, "use of a value that was not translated"))
+    }
+
+    /// Returns the name of the variable that holds the value, for mutating it in place.
+    fn place(&self, op: &OperationRef<'c, 'm>, value: Value<'c, '_>) -> Result<String, Error> {
+        Ok(self.var(op, value)?.name.clone())
+    }
+
+    /// Returns an expression that evaluates to the value.
+    fn value(&self, op: &OperationRef<'c, 'm>, value: Value<'c, '_>) -> Result<String, Error> {
+        let var = self.var(op, value)?;
+        Ok(if var.copy {
+            var.name.clone()
+        } else {
+            format!("{}.clone()", var.name)
+        })
+    }
+
+    fn operand(&self, op: &OperationRef<'c, 'm>, index: usize) -> Result<String, Error> {
+        self.value(op, op.operand(index)?)
+    }
+
+    fn operands(
+        &self,
+        op: &OperationRef<'c, 'm>,
+        range: std::ops::Range<usize>,
+    ) -> Result<Vec<String>, Error> {
+        range.map(|index| self.operand(op, index)).collect()
+    }
+
+    /// Returns the operand as an `i64` expression, converting booleans.
+    fn int(&self, op: &OperationRef<'c, 'm>, index: usize) -> Result<String, Error> {
+        let value = self.operand(op, index)?;
+        Ok(if is_i1(op.operand(index)?.r#type()) {
+            format!("i64::from({value})")
+        } else {
+            value
+        })
+    }
+
+    /// Declares the arguments of the block, initialized with the expressions.
+    fn bind_args(
+        &mut self,
+        op: &OperationRef<'c, 'm>,
+        block: BlockRef<'c, 'm>,
+        exprs: &[String],
+    ) -> Result<(), Error> {
+        if exprs.len() != block.argument_count() {
+            return Err(fail(
+                op,
+                "number of values does not match the block arguments",
+            ));
+        }
+        for (index, expr) in exprs.iter().enumerate() {
+            self.define(op, block.argument(index)?.into(), expr)?;
+        }
+        Ok(())
+    }
+
+    /// Translates the operations of the block. The values of `scf.yield` are assigned to
+    /// `targets`.
+    fn translate_block(
+        &mut self,
+        block: BlockRef<'c, 'm>,
+        targets: &[String],
+    ) -> Result<(), Error> {
+        let mut next = block.first_operation();
+        while let Some(op) = next {
+            self.translate_op(op, targets)?;
+            next = op.next_in_block();
+        }
+        Ok(())
+    }
+
+    /// Translates the entry block of a region of the op.
+    fn translate_region(
+        &mut self,
+        op: &OperationRef<'c, 'm>,
+        index: usize,
+        args: &[String],
+        targets: &[String],
+    ) -> Result<(), Error> {
+        let Some(block) = op.region(index)?.first_block() else {
+            return Ok(());
+        };
+        self.indent += 1;
+        self.bind_args(op, block, args)?;
+        self.translate_block(block, targets)?;
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn felt_binary(&mut self, op: &OperationRef<'c, 'm>, name: &str) -> Result<String, Error> {
+        let (lhs, rhs) = (self.operand(op, 0)?, self.operand(op, 1)?);
+        Ok(match name {
+            "felt.add" => format!("{lhs} + {rhs}"),
+            "felt.sub" => format!("{lhs} - {rhs}"),
+            "felt.mul" => format!("{lhs} * {rhs}"),
+            "felt.div" => format!("{lhs} * llzk_inv(&{rhs}, \"division by zero\")?"),
+            "felt.pow" => format!("llzk_pow(&{lhs}, &{rhs})"),
+            "felt.uintdiv" => format!("llzk_uintdiv(&{lhs}, &{rhs})?"),
+            "felt.umod" => format!("llzk_umod(&{lhs}, &{rhs})?"),
+            "felt.sintdiv" => format!("llzk_signed_divmod(&{lhs}, &{rhs})?.0"),
+            "felt.smod" => format!("llzk_signed_divmod(&{lhs}, &{rhs})?.1"),
+            "felt.bit_and" => format!("llzk_bitwise(&{lhs}, &{rhs}, |a, b| a & b)"),
+            "felt.bit_or" => format!("llzk_bitwise(&{lhs}, &{rhs}, |a, b| a | b)"),
+            "felt.bit_xor" => format!("llzk_bitwise(&{lhs}, &{rhs}, |a, b| a ^ b)"),
+            "felt.shl" => format!("llzk_shl(&{lhs}, &{rhs})"),
+            _ => format!("llzk_shr(&{lhs}, &{rhs})"),
+        })
+    }
+
+    fn int_binary(&mut self, op: &OperationRef<'c, 'm>, name: &str) -> Result<String, Error> {
+        if is_i1(op.result(0)?.r#type()) {
+            let (lhs, rhs) = (self.operand(op, 0)?, self.operand(op, 1)?);
+            return match name {
+                "arith.andi" | "arith.muli" => Ok(format!("{lhs} && {rhs}")),
+                "arith.ori" => Ok(format!("{lhs} || {rhs}")),
+                "arith.xori" | "arith.addi" | "arith.subi" => Ok(format!("{lhs} ^ {rhs}")),
+                _ => Err(fail(op, "operation is not supported on i1")),
+            };
+        }
+        let (lhs, rhs) = (self.int(op, 0)?, self.int(op, 1)?);
+        let unsigned = |method: &str| {
+            format!("({lhs} as u64).{method}({rhs} as u64).ok_or(\"division by zero\")? as i64")
+        };
+        Ok(match name {
+            "arith.addi" => format!("{lhs}.wrapping_add({rhs})"),
+            "arith.subi" => format!("{lhs}.wrapping_sub({rhs})"),
+            "arith.muli" => format!("{lhs}.wrapping_mul({rhs})"),
+            "arith.andi" => format!("{lhs} & {rhs}"),
+            "arith.ori" => format!("{lhs} | {rhs}"),
+            "arith.xori" => format!("{lhs} ^ {rhs}"),
+            "arith.divsi" => format!("{lhs}.checked_div({rhs}).ok_or(\"division by zero\")?"),
+            "arith.remsi" => format!("{lhs}.checked_rem({rhs}).ok_or(\"division by zero\")?"),
+            "arith.divui" => unsigned("checked_div"),
+            _ => unsigned("checked_rem"),
+        })
+    }
+
+    /// Returns an expression for the flat offset of the element or subarray at the indices given
+    /// by the operands in `range`, together with the number of elements it spans.
+    fn offset(
+        &self,
+        op: &OperationRef<'c, 'm>,
+        array: Type<'c>,
+        range: std::ops::Range<usize>,
+    ) -> Result<(String, usize), Error> {
+        let dims = array_dims(op, ArrayType::try_from(array)?)?;
+        let count = range.len();
+        if count > dims.len() {
+            return Err(fail(op, "too many indices"));
+        }
+        let indices = (range.start..range.end)
+            .map(|index| self.int(op, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let span = dims[count..].iter().product::<usize>();
+        let prefix = dims[..count]
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok((
+            format!(
+                "llzk_index(&[{}], &[{prefix}])? * {span}",
+                indices.join(", ")
+            ),
+            span,
+        ))
+    }
+
+    /// Translates the operation. The values of `scf.yield` are assigned to `targets`.
+    fn translate_op(&mut self, op: OperationRef<'c, 'm>, targets: &[String]) -> Result<(), Error> {
+        let name = op.name();
+        let name = name.as_string_ref().as_str()?;
+        let result = || -> Result<Value<'c, 'm>, Error> { Ok(op.result(0)?.into()) };
+        match name {
+            "function.return" => {
+                let values = self.operands(&op, 0..op.operand_count())?;
+                self.line(format!("return Ok({});", tuple(&values)));
+            }
+            "scf.yield" => {
+                let values = self.operands(&op, 0..op.operand_count())?;
+                if targets.len() != values.len() {
+                    return Err(fail(&op, "number of values does not match the results"));
+                }
+                for (target, value) in targets.iter().zip(values) {
+                    self.line(format!("{target} = {value};"));
+                }
+            }
+            "felt.const" => {
+                let value = FeltConstAttribute::try_from(op.attribute("value")?)?;
+                let parts = value.to_parts();
+                let magnitude = match parts.as_slice() {
+                    [] => "Felt::from(0u64)".to_owned(),
+                    [part] => format!("Felt::from({part}u64)"),
+                    parts => format!(
+                        "llzk_from_limbs::<Felt>(&[{}])",
+                        parts
+                            .iter()
+                            .map(|part| format!("{part}u64"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+                let expr = if value.is_negative() {
+                    format!("-{magnitude}")
+                } else {
+                    magnitude
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "felt.add" | "felt.sub" | "felt.mul" | "felt.div" | "felt.pow" | "felt.uintdiv"
+            | "felt.sintdiv" | "felt.umod" | "felt.smod" | "felt.bit_and" | "felt.bit_or"
+            | "felt.bit_xor" | "felt.shl" | "felt.shr" => {
+                let expr = self.felt_binary(&op, name)?;
+                self.define(&op, result()?, expr)?;
+            }
+            "felt.neg" | "felt.inv" | "felt.bit_not" => {
+                let value = self.operand(&op, 0)?;
+                let expr = match name {
+                    "felt.neg" => format!("-{value}"),
+                    "felt.inv" => format!("llzk_inv(&{value}, \"inverse of zero\")?"),
+                    _ => format!("llzk_bit_not(&{value})"),
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "bool.cmp" => {
+                let (lhs, rhs) = (self.operand(&op, 0)?, self.operand(&op, 1)?);
+                let predicate = cmp_predicate(op.attribute("predicate")?)
+                    .ok_or_else(|| fail(&op, "malformed comparison predicate"))?;
+                let expr = match predicate {
+                    CmpPredicate::Eq => format!("{lhs} == {rhs}"),
+                    CmpPredicate::Ne => format!("{lhs} != {rhs}"),
+                    CmpPredicate::Lt => format!("llzk_cmp(&{lhs}, &{rhs}).is_lt()"),
+                    CmpPredicate::Le => format!("llzk_cmp(&{lhs}, &{rhs}).is_le()"),
+                    CmpPredicate::Gt => format!("llzk_cmp(&{lhs}, &{rhs}).is_gt()"),
+                    CmpPredicate::Ge => format!("llzk_cmp(&{lhs}, &{rhs}).is_ge()"),
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "bool.and" | "bool.or" | "bool.xor" => {
+                let (lhs, rhs) = (self.operand(&op, 0)?, self.operand(&op, 1)?);
+                let expr = match name {
+                    "bool.and" => format!("{lhs} && {rhs}"),
+                    "bool.or" => format!("{lhs} || {rhs}"),
+                    _ => format!("{lhs} ^ {rhs}"),
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "bool.not" => {
+                let expr = format!("!{}", self.operand(&op, 0)?);
+                self.define(&op, result()?, expr)?;
+            }
+            "bool.assert" | "constrain.eq" | "constrain.in" => {}
+            "cast.tofelt" => {
+                let expr = format!("llzk_from_i64::<Felt>({})", self.int(&op, 0)?);
+                self.define(&op, result()?, expr)?;
+            }
+            "cast.toindex" => {
+                let expr = format!("llzk_to_index(&{})?", self.operand(&op, 0)?);
+                self.define(&op, result()?, expr)?;
+            }
+            "llzk.nondet" => self.define(&op, result()?, "Default::default()")?,
+            "arith.constant" => {
+                let value = IntegerAttribute::try_from(op.attribute("value")?)
+                    .map_err(|_| fail(&op, "only integer constants are supported"))?
+                    .value();
+                let expr = if is_i1(result()?.r#type()) {
+                    (value & 1 != 0).to_string()
+                } else {
+                    format!("{value}i64")
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "arith.addi" | "arith.subi" | "arith.muli" | "arith.divsi" | "arith.divui"
+            | "arith.remsi" | "arith.remui" | "arith.andi" | "arith.ori" | "arith.xori" => {
+                let expr = self.int_binary(&op, name)?;
+                self.define(&op, result()?, expr)?;
+            }
+            "arith.cmpi" => {
+                let (lhs, rhs) = (self.int(&op, 0)?, self.int(&op, 1)?);
+                let predicate = IntegerAttribute::try_from(op.attribute("predicate")?)
+                    .map_err(|_| fail(&op, "malformed comparison predicate"))?
+                    .value();
+                let (signed, operator) = match predicate {
+                    0 => (true, "=="),
+                    1 => (true, "!="),
+                    2 => (true, "<"),
+                    3 => (true, "<="),
+                    4 => (true, ">"),
+                    5 => (true, ">="),
+                    6 => (false, "<"),
+                    7 => (false, "<="),
+                    8 => (false, ">"),
+                    9 => (false, ">="),
+                    _ => return Err(fail(&op, "unknown comparison predicate")),
+                };
+                let expr = if signed {
+                    format!("{lhs} {operator} {rhs}")
+                } else {
+                    format!("({lhs} as u64) {operator} ({rhs} as u64)")
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "arith.select" => {
+                let expr = format!(
+                    "if {} {{ {} }} else {{ {} }}",
+                    self.operand(&op, 0)?,
+                    self.operand(&op, 1)?,
+                    self.operand(&op, 2)?
+                );
+                self.define(&op, result()?, expr)?;
+            }
+            "arith.index_cast" | "arith.index_castui" | "arith.extui" | "arith.extsi"
+            | "arith.trunci" => {
+                let value = self.int(&op, 0)?;
+                let expr = if is_i1(result()?.r#type()) {
+                    format!("{value} & 1 != 0")
+                } else {
+                    value
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "array.new" => {
+                let ty = ArrayType::try_from(result()?.r#type())?;
+                let len = array_dims(&op, ty)?.iter().product::<usize>();
+                let elements = self.operands(&op, 0..op.operand_count())?;
+                let expr = if elements.is_empty() {
+                    format!("vec![Default::default(); {len}]")
+                } else if elements.len() == len {
+                    format!("vec![{}]", elements.join(", "))
+                } else {
+                    return Err(fail(
+                        &op,
+                        "arrays with dimensions given by map operands are not supported",
+                    ));
+                };
+                self.define(&op, result()?, expr)?;
+            }
+            "array.read" => {
+                let array = op.operand(0)?;
+                let (offset, _) = self.offset(&op, array.r#type(), 1..op.operand_count())?;
+                let expr = format!("{}[{offset}].clone()", self.place(&op, array)?);
+                self.define(&op, result()?, expr)?;
+            }
+            "array.extract" => {
+                let array = op.operand(0)?;
+                let (offset, span) = self.offset(&op, array.r#type(), 1..op.operand_count())?;
+                let expr = format!(
+                    "{{ let offset = {offset}; {}[offset..offset + {span}].to_vec() }}",
+                    self.place(&op, array)?
+                );
+                self.define(&op, result()?, expr)?;
+            }
+            "array.write" | "array.insert" => {
+                let last = op.operand_count() - 1;
+                let array = op.operand(0)?;
+                let (offset, span) = self.offset(&op, array.r#type(), 1..last)?;
+                let place = self.place(&op, array)?;
+                let value = self.operand(&op, last)?;
+                if name == "array.write" {
+                    self.line(format!("{place}[{offset}] = {value};"));
+                } else {
+                    self.line(format!(
+                        "{{ let offset = {offset}; {place}[offset..offset + {span}].clone_from_slice(&{value}); }}"
+                    ));
+                }
+            }
+            "array.len" => {
+                let dims = array_dims(&op, ArrayType::try_from(op.operand(0)?.r#type())?)?;
+                let expr = format!(
+                    "llzk_dim(&[{}], {})?",
+                    dims.iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    self.int(&op, 1)?
+                );
+                self.define(&op, result()?, expr)?;
+            }
+            "struct.new" => self.define(&op, result()?, "Default::default()")?,
+            "struct.readm" => {
+                let expr = format!(
+                    "{}.{}.clone()",
+                    self.place(&op, op.operand(0)?)?,
+                    member_name(&op)?
+                );
+                self.define(&op, result()?, expr)?;
+            }
+            "struct.writem" => {
+                let line = format!(
+                    "{}.{} = {};",
+                    self.place(&op, op.operand(0)?)?,
+                    member_name(&op)?,
+                    self.operand(&op, 1)?
+                );
+                self.line(line);
+            }
+            "function.call" => {
+                let call = CallOpRef::try_from(op)?;
+                let callee = self.generator.callee(call)?;
+                let args = (0..call.arg_operand_count())
+                    .map(|index| self.value(&op, call.arg_operand_at(index)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let expr = format!("{callee}({})?", args.join(", "));
+                match op.result_count() {
+                    0 => self.line(format!("{expr};")),
+                    1 => self.define(&op, result()?, expr)?,
+                    _ => {
+                        let results = self.declare_results(&op)?;
+                        self.line(format!("{} = {expr};", tuple(&results)));
+                    }
+                }
+            }
+            "scf.if" => {
+                let results = self.declare_results(&op)?;
+                self.line(format!("if {} {{", self.operand(&op, 0)?));
+                self.translate_region(&op, 0, &[], &results)?;
+                self.line("} else {");
+                self.translate_region(&op, 1, &[], &results)?;
+                self.line("}");
+            }
+            "scf.for" => {
+                let (lower, upper, step) =
+                    (self.int(&op, 0)?, self.int(&op, 1)?, self.int(&op, 2)?);
+                let iter_args = self.operands(&op, 3..op.operand_count())?;
+                let results = self.declare_results(&op)?;
+                for (result, init) in results.iter().zip(&iter_args) {
+                    self.line(format!("{result} = {init};"));
+                }
+                // Nested loops must not shadow the variables of the enclosing ones.
+                let id = self.vars.len();
+                let (iv, end, step_var) =
+                    (format!("iv{id}"), format!("end{id}"), format!("step{id}"));
+                self.line(format!(
+                    "let (mut {iv}, {end}, {step_var}): (i64, i64, i64) = ({lower}, {upper}, {step});"
+                ));
+                self.line(format!(
+                    "if {step_var} <= 0 {{ return Err(\"loop step must be positive\"); }}"
+                ));
+                self.line(format!("while {iv} < {end} {{"));
+                let mut args = vec![iv.clone()];
+                args.extend(results.iter().map(|result| format!("{result}.clone()")));
+                self.translate_region(&op, 0, &args, &results)?;
+                self.line(format!(
+                    "    {iv} = match {iv}.checked_add({step_var}) {{ Some(next) => next, None => break }};"
+                ));
+                self.line("}");
+            }
+            "scf.while" => {
+                let init = self.operands(&op, 0..op.operand_count())?;
+                let results = self.declare_results(&op)?;
+                let state = (0..init.len())
+                    .map(|index| format!("s{}_{index}", self.vars.len()))
+                    .collect::<Vec<_>>();
+                for (state, init) in state.iter().zip(&init) {
+                    self.line(format!("let mut {state} = {init};"));
+                }
+                self.line("loop {");
+                let before = op.region(0)?.first_block().ok_or(Error::BlockExpected(0))?;
+                let args = state
+                    .iter()
+                    .map(|state| format!("{state}.clone()"))
+                    .collect::<Vec<_>>();
+                self.indent += 1;
+                self.bind_args(&op, before, &args)?;
+                let mut next = before.first_operation();
+                while let Some(nested) = next {
+                    if isa(&nested, "scf.condition") {
+                        let condition = self.operand(&nested, 0)?;
+                        let forwarded = self.operands(&nested, 1..nested.operand_count())?;
+                        self.line(format!("if !{condition} {{"));
+                        for (result, value) in results.iter().zip(&forwarded) {
+                            self.line(format!("    {result} = {value};"));
+                        }
+                        self.line("    break;");
+                        self.line("}");
+                        self.indent -= 1;
+                        self.translate_region(&op, 1, &forwarded, &state)?;
+                        self.indent += 1;
+                        break;
+                    }
+                    self.translate_op(nested, &[])?;
+                    next = nested.next_in_block();
+                }
+                self.indent -= 1;
+                self.line("}");
+            }
+            _ => return Err(fail(&op, "operation is not supported by the Rust target")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::LlzkContext;
+
+    #[test]
+    fn translate_division() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let source = translate_module(&module, "crate::Fr").unwrap();
+        assert!(source.contains("pub type Felt = crate::Fr;"));
+        assert!(source.contains("pub struct Main {\n    pub c: Felt,\n}"));
+        assert!(
+            source.contains("pub fn compute(v0: Felt, v1: Felt) -> Result<Self, &'static str>")
+        );
+        assert!(source.contains("let mut v2: Main = Default::default();"));
+        assert!(source.contains("let mut v3: Felt = v0 * llzk_inv(&v1, \"division by zero\")?;"));
+        assert!(source.contains("v2.c = v3;"));
+        assert!(source.contains("return Ok(v2.clone());"));
+        assert!(source.contains("fn llzk_inv<F: ff::PrimeField>"));
+    }
+
+    #[test]
+    fn translate_subcomponents_and_loops() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/bits.mlir")).unwrap();
+        let source = translate_module(&module, "Fr").unwrap();
+        assert!(source.contains("pub struct Bits {\n    pub bits: Vec<Felt>,\n}"));
+        assert!(source.contains("pub struct Main {\n    pub out: Felt,\n    pub sub: Bits,\n}"));
+        assert!(source.contains("let mut v2: Bits = Bits::compute(v0)?;"));
+        assert!(source.contains("while iv"));
+        assert!(source.contains("llzk_shr(&"));
+        assert!(source.contains("llzk_bitwise(&"));
+        assert!(source.contains("vec![Default::default(); 4]"));
+    }
+
+    #[test]
+    fn unsupported_type_fails() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    function.def @compute(%s: !string.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %s: !string.type) {
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let err = translate_module(&module, "Fr").unwrap_err();
+        assert!(
+            matches!(&err, Error::TranslationFailed { target: "Rust", .. }),
+            "unexpected error: {err}"
+        );
+    }
+}
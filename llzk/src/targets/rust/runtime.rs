@@ -0,0 +1,233 @@
+// Helpers used by the generated witness generators. The integer operations work on the canonical
+// representatives of the felts, as little-endian u64 limbs without trailing zero limbs.
+
+/// Returns the canonical representative of the felt.
+#[allow(dead_code, clippy::all)]
+fn llzk_limbs<F: ff::PrimeField>(value: &F) -> Vec<u64> {
+    let repr = value.to_repr();
+    let mut bytes = repr.as_ref().to_vec();
+    // The byte order of the representation is not fixed by `ff`.
+    if F::ONE.to_repr().as_ref().first() != Some(&1) {
+        bytes.reverse();
+    }
+    let mut limbs = bytes
+        .chunks(8)
+        .map(|chunk| {
+            let mut limb = [0u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(limb)
+        })
+        .collect::<Vec<_>>();
+    llzk_normalize(&mut limbs);
+    limbs
+}
+
+#[allow(dead_code, clippy::all)]
+fn llzk_normalize(limbs: &mut Vec<u64>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+/// Returns the felt of the integer, reduced modulo the prime.
+#[allow(dead_code, clippy::all)]
+fn llzk_from_limbs<F: ff::PrimeField>(limbs: &[u64]) -> F {
+    let base = F::from(1u64 << 32).square();
+    limbs
+        .iter()
+        .rev()
+        .fold(F::ZERO, |acc, limb| acc * base + F::from(*limb))
+}
+
+#[allow(dead_code, clippy::all)]
+fn llzk_cmp_limbs(lhs: &[u64], rhs: &[u64]) -> core::cmp::Ordering {
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// Compares the representatives of the felts.
+#[allow(dead_code, clippy::all)]
+fn llzk_cmp<F: ff::PrimeField>(lhs: &F, rhs: &F) -> core::cmp::Ordering {
+    llzk_cmp_limbs(&llzk_limbs(lhs), &llzk_limbs(rhs))
+}
+
+/// Applies the operation to the limbs of the representatives.
+#[allow(dead_code, clippy::all)]
+fn llzk_bitwise<F: ff::PrimeField>(lhs: &F, rhs: &F, op: impl Fn(u64, u64) -> u64) -> F {
+    let (lhs, rhs) = (llzk_limbs(lhs), llzk_limbs(rhs));
+    let limbs = (0..lhs.len().max(rhs.len()))
+        .map(|i| {
+            op(
+                lhs.get(i).copied().unwrap_or(0),
+                rhs.get(i).copied().unwrap_or(0),
+            )
+        })
+        .collect::<Vec<_>>();
+    llzk_from_limbs(&limbs)
+}
+
+/// Negates the bits of the representative within the bit width of the prime.
+#[allow(dead_code, clippy::all)]
+fn llzk_bit_not<F: ff::PrimeField>(value: &F) -> F {
+    let bits = F::NUM_BITS as usize;
+    let mut limbs = llzk_limbs(value);
+    limbs.resize(bits.div_ceil(64), 0);
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let width = (bits - i * 64).min(64);
+        let mask = if width == 64 { u64::MAX } else { (1u64 << width) - 1 };
+        *limb = !*limb & mask;
+    }
+    llzk_from_limbs(&limbs)
+}
+
+/// Shifts the representative left, modulo the prime. This is the product of the value and
+/// `2^amount`.
+#[allow(dead_code, clippy::all)]
+fn llzk_shl<F: ff::PrimeField>(value: &F, amount: &F) -> F {
+    *value * F::from(2u64).pow(llzk_limbs(amount))
+}
+
+/// Shifts the representative right. Amounts of at least the bit width of the prime give zero.
+#[allow(dead_code, clippy::all)]
+fn llzk_shr<F: ff::PrimeField>(value: &F, amount: &F) -> F {
+    let amount = match llzk_limbs(amount)[..] {
+        [] => 0,
+        [amount] if amount < u64::from(F::NUM_BITS) => amount as usize,
+        _ => return F::ZERO,
+    };
+    let limbs = llzk_limbs(value);
+    let (skip, bits) = (amount / 64, amount % 64);
+    let shifted = (skip..limbs.len())
+        .map(|i| {
+            let high = match limbs.get(i + 1) {
+                Some(next) if bits > 0 => next << (64 - bits),
+                _ => 0,
+            };
+            (limbs[i] >> bits) | high
+        })
+        .collect::<Vec<_>>();
+    llzk_from_limbs(&shifted)
+}
+
+/// Divides the integers, returning the quotient and the remainder.
+#[allow(dead_code, clippy::all)]
+fn llzk_divmod(lhs: &[u64], rhs: &[u64]) -> Result<(Vec<u64>, Vec<u64>), &'static str> {
+    if rhs.is_empty() {
+        return Err("division by zero");
+    }
+    let mut quotient = vec![0u64; lhs.len()];
+    let mut remainder: Vec<u64> = vec![];
+    for bit in (0..lhs.len() * 64).rev() {
+        // remainder = remainder * 2 + bit
+        let mut carry = (lhs[bit / 64] >> (bit % 64)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+        if llzk_cmp_limbs(&remainder, rhs).is_ge() {
+            let mut borrow = false;
+            for (i, limb) in remainder.iter_mut().enumerate() {
+                let (diff, b1) = limb.overflowing_sub(rhs.get(i).copied().unwrap_or(0));
+                let (diff, b2) = diff.overflowing_sub(u64::from(borrow));
+                *limb = diff;
+                borrow = b1 || b2;
+            }
+            llzk_normalize(&mut remainder);
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    llzk_normalize(&mut quotient);
+    Ok((quotient, remainder))
+}
+
+/// Integer division of the representatives.
+#[allow(dead_code, clippy::all)]
+fn llzk_uintdiv<F: ff::PrimeField>(lhs: &F, rhs: &F) -> Result<F, &'static str> {
+    let (quotient, _) = llzk_divmod(&llzk_limbs(lhs), &llzk_limbs(rhs))?;
+    Ok(llzk_from_limbs(&quotient))
+}
+
+/// Integer remainder of the representatives.
+#[allow(dead_code, clippy::all)]
+fn llzk_umod<F: ff::PrimeField>(lhs: &F, rhs: &F) -> Result<F, &'static str> {
+    let (_, remainder) = llzk_divmod(&llzk_limbs(lhs), &llzk_limbs(rhs))?;
+    Ok(llzk_from_limbs(&remainder))
+}
+
+/// Returns true if the felt represents a negative integer, that is, if it is above `(p - 1) / 2`.
+#[allow(dead_code, clippy::all)]
+fn llzk_is_negative<F: ff::PrimeField>(value: &F) -> bool {
+    llzk_cmp(value, &-*value).is_gt()
+}
+
+/// Signed integer division, rounding towards zero, and remainder with the sign of `lhs`.
+#[allow(dead_code, clippy::all)]
+fn llzk_signed_divmod<F: ff::PrimeField>(lhs: &F, rhs: &F) -> Result<(F, F), &'static str> {
+    let (lhs_negative, rhs_negative) = (llzk_is_negative(lhs), llzk_is_negative(rhs));
+    let abs = |value: &F, negative: bool| if negative { -*value } else { *value };
+    let (quotient, remainder) = llzk_divmod(
+        &llzk_limbs(&abs(lhs, lhs_negative)),
+        &llzk_limbs(&abs(rhs, rhs_negative)),
+    )?;
+    let (quotient, remainder) = (llzk_from_limbs::<F>(&quotient), llzk_from_limbs::<F>(&remainder));
+    Ok((
+        abs(&quotient, lhs_negative != rhs_negative),
+        abs(&remainder, lhs_negative),
+    ))
+}
+
+#[allow(dead_code, clippy::all)]
+fn llzk_from_i64<F: ff::PrimeField>(value: i64) -> F {
+    let magnitude = F::from(value.unsigned_abs());
+    if value < 0 { -magnitude } else { magnitude }
+}
+
+/// Converts the representative of the felt into an index.
+#[allow(dead_code, clippy::all)]
+fn llzk_to_index<F: ff::PrimeField>(value: &F) -> Result<i64, &'static str> {
+    match llzk_limbs(value)[..] {
+        [] => Ok(0),
+        [value] => i64::try_from(value).map_err(|_| "felt does not fit in an index"),
+        _ => Err("felt does not fit in an index"),
+    }
+}
+
+/// Raises the felt to the power of the representative of `exp`.
+#[allow(dead_code, clippy::all)]
+fn llzk_pow<F: ff::PrimeField>(base: &F, exp: &F) -> F {
+    base.pow(llzk_limbs(exp))
+}
+
+#[allow(dead_code, clippy::all)]
+fn llzk_inv<F: ff::PrimeField>(value: &F, msg: &'static str) -> Result<F, &'static str> {
+    Option::from(value.invert()).ok_or(msg)
+}
+
+/// Returns the offset of the element at the indices in a row-major array.
+#[allow(dead_code, clippy::all)]
+fn llzk_index(indices: &[i64], dims: &[usize]) -> Result<usize, &'static str> {
+    let mut offset = 0;
+    for (index, dim) in indices.iter().zip(dims) {
+        let index = usize::try_from(*index)
+            .ok()
+            .filter(|index| index < dim)
+            .ok_or("array index out of bounds")?;
+        offset = offset * dim + index;
+    }
+    Ok(offset)
+}
+
+/// Returns the size of the dimension of an array.
+#[allow(dead_code, clippy::all)]
+fn llzk_dim(dims: &[usize], index: i64) -> Result<i64, &'static str> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| dims.get(index))
+        .map(|dim| *dim as i64)
+        .ok_or("array dimension out of bounds")
+}
@@ -0,0 +1,17 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    function.def @compute(%a: !felt.type {llzk.pub}, %b: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %c = felt.div %a, %b : !felt.type, !felt.type
+      struct.writem %self[@c] = %c : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type {llzk.pub}, %b: !felt.type) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      %t = felt.mul %c, %b : !felt.type, !felt.type
+      constrain.eq %t, %a : !felt.type, !felt.type
+      function.return
+    }
+  }
+}
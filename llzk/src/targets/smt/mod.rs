@@ -0,0 +1,1170 @@
+//! Translation of struct constraints into SMT-LIB2 scripts.
+//!
+//! The translation encodes the `@constrain` function of a struct, including the `@constrain`
+//! functions of the subcomponents it calls, as assertions over one variable per felt member and
+//! per input. The `@compute` function can be encoded as well, in which case its writes to members
+//! become equalities and `llzk.nondet` values become free variables.
+//!
+//! The script is self-contained: it sets the logic, defines the sort used for felts and ends with
+//! `(check-sat)`, so it can be piped directly into a solver. Felts are encoded with the chosen
+//! [`FeltSort`]. `bool.forall` and `bool.exists` become quantifiers whose bound variable is
+//! restricted to the elements of their domain, which must be an array created by `array.new` from
+//! its elements.
+//!
+//! The module must be flattened and its other arrays lowered to scalars beforehand, for example
+//! with the flattening and array-to-scalar passes.
+//!
+//! Variables are named after the path that leads to them, for example `main.c` for member `@c` of
+//! the translated struct, `main.sub.x` for member `@x` of its subcomponent `@sub`, and `main.arg0`
+//! for its first input.
+//!
+//! [`Solver`] runs a script through a local solver binary.
+
+mod solver;
+
+pub use solver::{SatResult, Solver};
+
+use super::symbolic::{self, Components, Member, constrain_func, symbol_attr};
+use crate::{
+    analysis::determinism::DeterminismObligation,
+    dialect::{
+        array::ArrayType,
+        bool::CmpPredicate,
+        felt::FeltConstAttribute,
+        function::{CallOpLike as _, CallOpRef, FuncDefOpLike as _, FuncDefOpRef},
+        r#struct::{StructDefOpLike, StructDefOpRef, StructType},
+    },
+    error::Error,
+    interpreter::{Field, cmp_predicate},
+    operation::isa,
+};
+use melior::ir::{
+    BlockLike as _, BlockRef, OperationRef, RegionLike as _, Type, ValueLike,
+    attribute::IntegerAttribute, operation::OperationLike, r#type::IntegerType,
+};
+use std::{collections::HashMap, fmt::Write as _, os::raw::c_void};
+
+const TARGET: &str = "SMT-LIB2";
+
+/// Sort used to encode felt values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeltSort {
+    /// The finite field sort `(_ FiniteField p)`, as supported by cvc5.
+    #[default]
+    FiniteField,
+    /// Integers in the range `[0, p)`, with arithmetic reduced modulo `p`.
+    ///
+    /// Works with any solver that supports nonlinear integer arithmetic and is required by the
+    /// ordering predicates of `bool.cmp`.
+    Integer,
+}
+
+/// Translates structs into SMT-LIB2 scripts.
+#[derive(Debug, Clone)]
+pub struct SmtTranslator {
+    sort: FeltSort,
+    default_field: Field,
+    compute: bool,
+}
+
+impl Default for SmtTranslator {
+    fn default() -> Self {
+        Self::new(FeltSort::default())
+    }
+}
+
+impl SmtTranslator {
+    /// Creates a translator that encodes felts with the given sort and uses `bn128` as the
+    /// default field.
+    pub fn new(sort: FeltSort) -> Self {
+        Self {
+            sort,
+            default_field: Field::builtin("bn128").expect("bn128 is a builtin field"),
+            compute: false,
+        }
+    }
+
+    /// Sets the field used for felt types that don't name a field.
+    pub fn with_default_field(mut self, field: Field) -> Self {
+        self.default_field = field;
+        self
+    }
+
+    /// Sets whether the `@compute` function is encoded along with the `@constrain` function.
+    pub fn with_compute(mut self, compute: bool) -> Self {
+        self.compute = compute;
+        self
+    }
+
+    /// Translates the struct into an SMT-LIB2 script.
+    pub fn translate<'c: 'a, 'a>(
+        &self,
+        struct_def: &impl StructDefOpLike<'c, 'a>,
+    ) -> Result<String, Error> {
+        // SAFETY: the operation is a valid struct definition that outlives the translation.
+        let def = unsafe { StructDefOpRef::from_raw(struct_def.to_raw()) };
+        let mut encoder = Encoder::new(self, def)?;
+        let (main, inputs) = encoder.allocate_main(def, "main", None)?;
+        encoder.translate_constrain(main, inputs.clone())?;
+        if self.compute {
+            let func = compute_func(def)?;
+            encoder.translate_function(func, inputs, Some(main))?;
+        }
+        Ok(encoder.finish(def))
+    }
+
+    /// Translates the `@constrain` function of the struct twice, with the same public inputs, and
+    /// asserts that the given felt member differs between both copies.
+    ///
+    /// The script is unsatisfiable if and only if the member is uniquely determined by the public
+    /// inputs. Private inputs are part of the witness, so each copy has its own. The variables of
+    /// the copies are prefixed with `a.` and `b.` respectively.
+    pub(crate) fn translate_uniqueness<'c, 'm>(
+        &self,
+        def: StructDefOpRef<'c, 'm>,
+        member: &str,
+    ) -> Result<String, Error> {
+        let mut encoder = Encoder::new(self, def)?;
+        let (first, inputs) = encoder.allocate_main(def, "a.main", None)?;
+        encoder.translate_constrain(first, inputs.clone())?;
+        let (second, inputs) = encoder.allocate_main(def, "b.main", Some(&inputs))?;
+        encoder.translate_constrain(second, inputs)?;
+        let vars = [first, second].map(|id| encoder.components[id].members.get(member).cloned());
+        let [Some(Member::Felt(lhs)), Some(Member::Felt(rhs))] = vars else {
+            return Err(Error::SymbolNotFound(format!(
+                "{}::@{member} felt member",
+                def.fully_qualified_name()
+            )));
+        };
+        encoder
+            .commands
+            .push(format!("(assert (distinct {lhs} {rhs}))"));
+        Ok(encoder.finish(def))
+    }
+
+    /// Translates the product program of the struct, made by
+    /// [`build_product_program`](crate::analysis::determinism::build_product_program), and asserts
+    /// that the obligation does not hold.
+    ///
+    /// The script is unsatisfiable if and only if the private members of the obligation coincide
+    /// in both copies, which proves that the struct is deterministic.
+    pub fn translate_determinism<'c: 'a, 'a>(
+        &self,
+        struct_def: &impl StructDefOpLike<'c, 'a>,
+        obligation: &DeterminismObligation,
+    ) -> Result<String, Error> {
+        // SAFETY: the operation is a valid struct definition that outlives the translation.
+        let def = unsafe { StructDefOpRef::from_raw(struct_def.to_raw()) };
+        let func = def.product_func().ok_or_else(|| {
+            Error::SymbolNotFound(format!("{}::@product", def.fully_qualified_name()))
+        })?;
+        let mut encoder = Encoder::new(self, def)?;
+        encoder.product = true;
+        let inputs = encoder.allocate_inputs(func, 0, "main", None)?;
+        let Some(Term::Component(witness)) = encoder.translate_function(func, inputs, None)? else {
+            return Err(fail(&func, "@product did not return a struct value"));
+        };
+        let &[other] = encoder.nondet_components.as_slice() else {
+            return Err(fail(
+                &func,
+                "@product must create exactly one arbitrary instance of the struct",
+            ));
+        };
+        let mut distinct = vec![];
+        for path in &obligation.members {
+            let vars = [witness, other].map(|id| encoder.components.felt_at(id, path));
+            let [Some(lhs), Some(rhs)] = vars else {
+                return Err(Error::SymbolNotFound(format!(
+                    "{}::@{} felt member",
+                    def.fully_qualified_name(),
+                    path.join(".@")
+                )));
+            };
+            distinct.push(format!("(distinct {lhs} {rhs})"));
+        }
+        let term = match distinct.len() {
+            0 => "false".to_owned(),
+            1 => distinct.remove(0),
+            _ => format!("(or {})", distinct.join(" ")),
+        };
+        encoder.commands.push(format!("(assert {term})"));
+        Ok(encoder.finish(def))
+    }
+}
+
+fn compute_func<'c, 'm>(def: StructDefOpRef<'c, 'm>) -> Result<FuncDefOpRef<'c, 'm>, Error> {
+    def.compute_func()
+        .ok_or_else(|| Error::SymbolNotFound(format!("{}::@compute", def.fully_qualified_name())))
+}
+
+fn fail<'c: 'a, 'a>(op: &impl OperationLike<'c, 'a>, msg: impl Into<String>) -> Error {
+    symbolic::fail(TARGET, op, msg)
+}
+
+fn is_i1(ty: Type) -> bool {
+    IntegerType::try_from(ty).is_ok_and(|ty| ty.width() == 1)
+}
+
+/// Symbolic value of an SSA value.
+#[derive(Clone)]
+enum Term {
+    Felt(String),
+    Bool(String),
+    Component(usize),
+    /// Elements of an array created by `array.new`. Only used as the domain of quantifiers.
+    Array(Vec<Term>),
+}
+
+struct Encoder<'c, 'm, 't> {
+    options: &'t SmtTranslator,
+    /// Components whose felt members are declared constants.
+    components: Components<'c, 'm, String>,
+    commands: Vec<String>,
+    next_name: usize,
+    /// Whether the script contains quantifiers.
+    quantified: bool,
+    /// Number of quantifiers whose body is being translated. Terms are inlined instead of named
+    /// while it is not zero, since they may refer to the bound variables.
+    bound: usize,
+    /// Whether the body of a `@product` function is being translated, which allows calls to
+    /// `@compute` and `llzk.nondet` alongside the constraints. It is cleared while translating the
+    /// `@constrain` functions the body calls.
+    product: bool,
+    /// Components created by the `llzk.nondet` operations of struct types of a `@product` function.
+    nondet_components: Vec<usize>,
+}
+
+impl<'c, 'm, 't> Encoder<'c, 'm, 't> {
+    fn new(options: &'t SmtTranslator, def: StructDefOpRef<'c, 'm>) -> Result<Self, Error> {
+        Ok(Self {
+            options,
+            components: Components::new(TARGET, &def, &options.default_field)?,
+            commands: vec![],
+            next_name: 0,
+            quantified: false,
+            bound: 0,
+            product: false,
+            nondet_components: vec![],
+        })
+    }
+
+    /// Assembles the script from the commands emitted so far.
+    fn finish(self, def: StructDefOpRef<'c, 'm>) -> String {
+        let field = self.components.field().clone();
+        let logic = match (self.options.sort, self.quantified) {
+            (FeltSort::FiniteField, false) => "QF_FF",
+            (FeltSort::Integer, false) => "QF_NIA",
+            (_, true) => "ALL",
+        };
+        let mut script = String::new();
+        let function = if self.product {
+            "@product"
+        } else if self.options.compute {
+            "@compute and @constrain"
+        } else {
+            "@constrain"
+        };
+        let _ = writeln!(script, "; {function} of {}", def.fully_qualified_name());
+        let _ = writeln!(script, "(set-logic {logic})");
+        match self.options.sort {
+            FeltSort::FiniteField => {
+                let _ = writeln!(
+                    script,
+                    "(define-sort F () (_ FiniteField {}))",
+                    field.prime()
+                );
+            }
+            FeltSort::Integer => {
+                let _ = writeln!(script, "(define-sort F () Int)");
+                let _ = writeln!(script, "(define-fun P () Int {})", field.prime());
+            }
+        }
+        for command in &self.commands {
+            let _ = writeln!(script, "{command}");
+        }
+        script.push_str("(check-sat)\n");
+        script
+    }
+
+    /// Returns a name that is not used by any variable.
+    fn fresh_name(&mut self, kind: &str) -> String {
+        self.next_name += 1;
+        format!("{kind}.{}", self.next_name - 1)
+    }
+
+    /// Declares a felt variable and restricts it to the field.
+    fn declare_felt(&mut self, name: String) -> String {
+        self.commands.push(format!("(declare-const {name} F)"));
+        if self.options.sort == FeltSort::Integer {
+            self.commands
+                .push(format!("(assert (and (<= 0 {name}) (< {name} P)))"));
+        }
+        name
+    }
+
+    /// Names the term with a definition, unless it is inside a quantifier.
+    fn define(&mut self, sort: &str, term: String) -> String {
+        if self.bound > 0 {
+            return term;
+        }
+        let name = self.fresh_name("t");
+        self.commands
+            .push(format!("(define-fun {name} () {sort} {term})"));
+        name
+    }
+
+    fn assert(&mut self, op: &OperationRef<'c, 'm>, term: String) -> Result<(), Error> {
+        if self.bound > 0 {
+            return Err(fail(op, "assertions are not supported inside quantifiers"));
+        }
+        self.commands.push(format!("(assert {term})"));
+        Ok(())
+    }
+
+    /// Allocates the variables of the translated struct. Returns the id of its component and the
+    /// symbolic values of its inputs. Public inputs are taken from `shared` if it is given.
+    fn allocate_main(
+        &mut self,
+        def: StructDefOpRef<'c, 'm>,
+        prefix: &str,
+        shared: Option<&[Term]>,
+    ) -> Result<(usize, Vec<Term>), Error> {
+        let id = self.allocate_component(prefix, def)?;
+        let inputs = self.allocate_inputs(constrain_func(def)?, 1, prefix, shared)?;
+        Ok((id, inputs))
+    }
+
+    /// Declares a variable for each argument of the function, starting at the given index, except
+    /// for the public arguments whose values are taken from `shared`.
+    fn allocate_inputs(
+        &mut self,
+        func: FuncDefOpRef<'c, 'm>,
+        first: usize,
+        prefix: &str,
+        shared: Option<&[Term]>,
+    ) -> Result<Vec<Term>, Error> {
+        let mut inputs = vec![];
+        for index in first..func.arg_count()? {
+            let is_pub = u32::try_from(index).is_ok_and(|index| func.arg_is_pub(index));
+            if let Some(term) = shared
+                .filter(|_| is_pub)
+                .and_then(|shared| shared.get(index - first))
+            {
+                inputs.push(term.clone());
+                continue;
+            }
+            let arg = func.argument(index)?;
+            let name = func
+                .arg_name(index)?
+                .unwrap_or_else(|| format!("arg{}", index - first));
+            let name = format!("{prefix}.{name}");
+            inputs.push(if is_i1(arg.r#type()) {
+                self.commands.push(format!("(declare-const {name} Bool)"));
+                Term::Bool(name)
+            } else {
+                self.components.field_of(&func, arg.r#type())?;
+                Term::Felt(self.declare_felt(name))
+            });
+        }
+        Ok(inputs)
+    }
+
+    /// Allocates a component for the struct and declares the variables of its felt members.
+    fn allocate_component(
+        &mut self,
+        prefix: &str,
+        def: StructDefOpRef<'c, 'm>,
+    ) -> Result<usize, Error> {
+        let mut felts = vec![];
+        let id = self
+            .components
+            .allocate_component(prefix, def, &mut |name: String, _| {
+                felts.push(name.clone());
+                name
+            })?;
+        for name in felts {
+            self.declare_felt(name);
+        }
+        Ok(id)
+    }
+
+    /// Translates the `@constrain` function of a component.
+    fn translate_constrain(&mut self, component: usize, inputs: Vec<Term>) -> Result<(), Error> {
+        let func = constrain_func(self.components[component].def)?;
+        let mut args = vec![Term::Component(component)];
+        args.extend(inputs);
+        let product = std::mem::replace(&mut self.product, false);
+        let result = self.translate_function(func, args, None);
+        self.product = product;
+        result?;
+        Ok(())
+    }
+
+    /// Translates the body of a function and returns the symbolic value it returns, if any.
+    ///
+    /// `instance` is the component that `struct.new` creates in a `@compute` function.
+    fn translate_function(
+        &mut self,
+        func: FuncDefOpRef<'c, 'm>,
+        args: Vec<Term>,
+        instance: Option<usize>,
+    ) -> Result<Option<Term>, Error> {
+        let block = func.body()?.first_block().ok_or(Error::BlockExpected(0))?;
+        if block.argument_count() != args.len() {
+            return Err(fail(&func, "number of arguments does not match"));
+        }
+        let mut values: HashMap<*const c_void, Term> = HashMap::new();
+        for (index, arg) in args.into_iter().enumerate() {
+            values.insert(block.argument(index)?.to_raw().ptr, arg);
+        }
+
+        match self.translate_block(&mut values, block, instance)? {
+            Some(op) if isa(&op, "function.return") => {
+                if op.operand_count() == 0 {
+                    return Ok(None);
+                }
+                values
+                    .get(&op.operand(0)?.to_raw().ptr)
+                    .cloned()
+                    .map(Some)
+                    .ok_or_else(|| fail(&op, "use of a value that was not translated"))
+            }
+            _ => Err(fail(&func, "function body did not end with a return")),
+        }
+    }
+
+    /// Translates the operations of the block up to its terminator, which is returned.
+    fn translate_block(
+        &mut self,
+        values: &mut HashMap<*const c_void, Term>,
+        block: BlockRef<'c, 'm>,
+        instance: Option<usize>,
+    ) -> Result<Option<OperationRef<'c, 'm>>, Error> {
+        let mut next = block.first_operation();
+        while let Some(op) = next {
+            if isa(&op, "function.return") || isa(&op, "bool.yield") {
+                return Ok(Some(op));
+            }
+            self.translate_op(values, op, instance)?;
+            next = op.next_in_block();
+        }
+        Ok(None)
+    }
+
+    fn translate_op(
+        &mut self,
+        values: &mut HashMap<*const c_void, Term>,
+        op: OperationRef<'c, 'm>,
+        instance: Option<usize>,
+    ) -> Result<(), Error> {
+        let operand = |index: usize| -> Result<Term, Error> {
+            values
+                .get(&op.operand(index)?.to_raw().ptr)
+                .cloned()
+                .ok_or_else(|| fail(&op, "use of a value that was not translated"))
+        };
+        let felt = |index: usize| match operand(index)? {
+            Term::Felt(term) => Ok(term),
+            _ => Err(fail(&op, "expected a felt value")),
+        };
+        let boolean = |index: usize| match operand(index)? {
+            Term::Bool(term) => Ok(term),
+            _ => Err(fail(&op, "expected a boolean value")),
+        };
+        let sort = self.options.sort;
+        // `instance` is only set while translating a `@compute` function.
+        let computing = instance.is_some();
+
+        let name = op.name();
+        let name = name.as_string_ref().as_str()?;
+        let result = match name {
+            "felt.const" => {
+                let field = self.components.field_of(&op, op.result(0)?.r#type())?;
+                let value = FeltConstAttribute::try_from(op.attribute("value")?)?.to_bigint();
+                Term::Felt(constant(sort, &field.from_signed(&value).to_string()))
+            }
+            "felt.add" | "felt.sub" | "felt.mul" => {
+                self.components.field_of(&op, op.result(0)?.r#type())?;
+                let (lhs, rhs) = (felt(0)?, felt(1)?);
+                let term = match (sort, name) {
+                    (FeltSort::FiniteField, "felt.add") => format!("(ff.add {lhs} {rhs})"),
+                    (FeltSort::FiniteField, "felt.sub") => {
+                        format!("(ff.add {lhs} (ff.neg {rhs}))")
+                    }
+                    (FeltSort::FiniteField, _) => format!("(ff.mul {lhs} {rhs})"),
+                    (FeltSort::Integer, "felt.add") => format!("(mod (+ {lhs} {rhs}) P)"),
+                    (FeltSort::Integer, "felt.sub") => format!("(mod (- {lhs} {rhs}) P)"),
+                    (FeltSort::Integer, _) => format!("(mod (* {lhs} {rhs}) P)"),
+                };
+                Term::Felt(self.define("F", term))
+            }
+            "felt.neg" => {
+                self.components.field_of(&op, op.result(0)?.r#type())?;
+                let value = felt(0)?;
+                let term = match sort {
+                    FeltSort::FiniteField => format!("(ff.neg {value})"),
+                    FeltSort::Integer => format!("(mod (- {value}) P)"),
+                };
+                Term::Felt(self.define("F", term))
+            }
+            "felt.div" | "felt.inv" => {
+                // The quotient is a fresh variable constrained by `rhs * q = lhs`. Evaluation fails
+                // on division by zero, so the divisor is asserted to be non-zero.
+                self.components.field_of(&op, op.result(0)?.r#type())?;
+                let (lhs, rhs) = if name == "felt.div" {
+                    (felt(0)?, felt(1)?)
+                } else {
+                    (constant(sort, "1"), felt(0)?)
+                };
+                if self.bound > 0 {
+                    return Err(fail(&op, "division is not supported inside quantifiers"));
+                }
+                let quotient = self.fresh_name("q");
+                let quotient = self.declare_felt(quotient);
+                let zero = constant(sort, "0");
+                self.assert(&op, format!("(not (= {rhs} {zero}))"))?;
+                self.assert(&op, format!("(= {} {lhs})", mul(sort, &rhs, &quotient)))?;
+                Term::Felt(quotient)
+            }
+            "bool.cmp" => {
+                self.components.field_of(&op, op.operand(0)?.r#type())?;
+                let (lhs, rhs) = (felt(0)?, felt(1)?);
+                let predicate = cmp_predicate(op.attribute("predicate")?)
+                    .ok_or_else(|| fail(&op, "malformed comparison predicate"))?;
+                let symbol = match predicate {
+                    CmpPredicate::Eq => "=",
+                    CmpPredicate::Ne => "distinct",
+                    _ if sort == FeltSort::FiniteField => {
+                        return Err(fail(
+                            &op,
+                            "ordering comparisons require the integer felt sort",
+                        ));
+                    }
+                    CmpPredicate::Lt => "<",
+                    CmpPredicate::Le => "<=",
+                    CmpPredicate::Gt => ">",
+                    CmpPredicate::Ge => ">=",
+                };
+                Term::Bool(self.define("Bool", format!("({symbol} {lhs} {rhs})")))
+            }
+            "bool.and" | "bool.or" | "bool.xor" => {
+                let (lhs, rhs) = (boolean(0)?, boolean(1)?);
+                let symbol = &name["bool.".len()..];
+                Term::Bool(self.define("Bool", format!("({symbol} {lhs} {rhs})")))
+            }
+            "bool.not" => {
+                let value = boolean(0)?;
+                Term::Bool(self.define("Bool", format!("(not {value})")))
+            }
+            "arith.constant" if is_i1(op.result(0)?.r#type()) => {
+                let value = IntegerAttribute::try_from(op.attribute("value")?)
+                    .map_err(|_| fail(&op, "malformed boolean constant"))?
+                    .value();
+                Term::Bool(if value & 1 != 0 { "true" } else { "false" }.to_owned())
+            }
+            "array.new" => {
+                let ty = ArrayType::try_from(op.result(0)?.r#type())?;
+                let len = ty
+                    .dims()
+                    .into_iter()
+                    .map(|dim| {
+                        IntegerAttribute::try_from(dim)
+                            .ok()
+                            .and_then(|dim| usize::try_from(dim.value()).ok())
+                    })
+                    .product::<Option<usize>>();
+                if len.is_none_or(|len| len == 0 || len != op.operand_count()) {
+                    return Err(fail(
+                        &op,
+                        "only arrays created from their elements are supported",
+                    ));
+                }
+                Term::Array(
+                    (0..op.operand_count())
+                        .map(operand)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "bool.forall" | "bool.exists" => {
+                let Term::Array(domain) = operand(0)? else {
+                    return Err(fail(
+                        &op,
+                        "quantifier domain must be an array created from its elements",
+                    ));
+                };
+                let block = op
+                    .region(0)?
+                    .first_block()
+                    .ok_or_else(|| fail(&op, "quantifier does not have a body"))?;
+                let arg = block.argument(0)?;
+                let var = self.fresh_name("x");
+                let var_sort = if is_i1(arg.r#type()) {
+                    "Bool"
+                } else {
+                    self.components.field_of(&op, arg.r#type())?;
+                    "F"
+                };
+                // The elements are in range, so membership also bounds integer variables.
+                let membership = domain
+                    .iter()
+                    .map(|element| match element {
+                        Term::Felt(term) | Term::Bool(term) => Ok(format!("(= {var} {term})")),
+                        _ => Err(fail(
+                            &op,
+                            "quantifier domain must contain felts or booleans",
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let membership = match membership.as_slice() {
+                    [single] => single.clone(),
+                    _ => format!("(or {})", membership.join(" ")),
+                };
+                values.insert(
+                    arg.to_raw().ptr,
+                    if var_sort == "Bool" {
+                        Term::Bool(var.clone())
+                    } else {
+                        Term::Felt(var.clone())
+                    },
+                );
+
+                self.bound += 1;
+                let terminator = self.translate_block(values, block, instance);
+                self.bound -= 1;
+                let terminator = terminator?
+                    .filter(|op| isa(op, "bool.yield"))
+                    .ok_or_else(|| fail(&op, "quantifier body did not end with a yield"))?;
+                let body = match values.get(&terminator.operand(0)?.to_raw().ptr) {
+                    Some(Term::Bool(body)) => body.clone(),
+                    _ => return Err(fail(&terminator, "expected a boolean value")),
+                };
+
+                self.quantified = true;
+                let term = if name == "bool.forall" {
+                    format!("(forall (({var} {var_sort})) (=> {membership} {body}))")
+                } else {
+                    format!("(exists (({var} {var_sort})) (and {membership} {body}))")
+                };
+                Term::Bool(self.define("Bool", term))
+            }
+            "bool.assert" => {
+                if !computing {
+                    let cond = boolean(0)?;
+                    self.assert(&op, cond)?;
+                }
+                return Ok(());
+            }
+            "constrain.eq" => {
+                if !computing {
+                    self.components.field_of(&op, op.operand(0)?.r#type())?;
+                    let (lhs, rhs) = (felt(0)?, felt(1)?);
+                    self.assert(&op, format!("(= {lhs} {rhs})"))?;
+                }
+                return Ok(());
+            }
+            "llzk.nondet" if computing || self.product => {
+                if self.bound > 0 {
+                    return Err(fail(
+                        &op,
+                        "nondeterminism is not supported inside quantifiers",
+                    ));
+                }
+                let ty = op.result(0)?.r#type();
+                if let Ok(ty) = StructType::try_from(ty) {
+                    // An arbitrary instance, whose members are free variables.
+                    let def = self.components.struct_def(&op, ty)?;
+                    let prefix = self.fresh_name("n");
+                    let component = self.allocate_component(&prefix, def)?;
+                    if !computing {
+                        self.nondet_components.push(component);
+                    }
+                    Term::Component(component)
+                } else {
+                    self.components.field_of(&op, ty)?;
+                    let var = self.fresh_name("n");
+                    Term::Felt(self.declare_felt(var))
+                }
+            }
+            "struct.new" => match instance {
+                Some(instance) => Term::Component(instance),
+                None => return Err(fail(&op, "struct.new outside of a @compute function")),
+            },
+            "struct.readm" => {
+                let Term::Component(component) = operand(0)? else {
+                    return Err(fail(&op, "expected a struct value"));
+                };
+                let member = symbol_attr(TARGET, &op, "member_name")?;
+                match self.components[component].members.get(&member) {
+                    Some(Member::Felt(var)) => Term::Felt(var.clone()),
+                    Some(Member::Component(id)) => Term::Component(*id),
+                    None => return Err(fail(&op, format!("struct has no member @{member}"))),
+                }
+            }
+            "struct.writem" if computing => {
+                let Term::Component(component) = operand(0)? else {
+                    return Err(fail(&op, "expected a struct value"));
+                };
+                let member = symbol_attr(TARGET, &op, "member_name")?;
+                let target = self.components[component]
+                    .members
+                    .get(&member)
+                    .cloned()
+                    .ok_or_else(|| fail(&op, format!("struct has no member @{member}")))?;
+                self.assign(&op, target, operand(1)?)?;
+                return Ok(());
+            }
+            "function.call" => {
+                if self.bound > 0 {
+                    return Err(fail(&op, "calls are not supported inside quantifiers"));
+                }
+                let call = CallOpRef::try_from(op)?;
+                let inputs = (0..call.arg_operand_count())
+                    .map(|index| {
+                        values
+                            .get(&call.arg_operand_at(index).to_raw().ptr)
+                            .cloned()
+                            .ok_or_else(|| fail(&op, "use of a value that was not translated"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if call.callee_is_struct_constrain() && !computing {
+                    let Some(Term::Component(component)) = inputs.first().cloned() else {
+                        return Err(fail(&op, "expected a subcomponent as first argument"));
+                    };
+                    return self.translate_constrain(component, inputs[1..].to_vec());
+                }
+                if !(call.callee_is_struct_compute() && (computing || self.product)) {
+                    return Err(fail(
+                        &op,
+                        "only calls to @compute and @constrain functions of subcomponents are \
+                         supported",
+                    ));
+                }
+                // The subcomponent is computed into a fresh instance, which is then copied into
+                // the member it is written to.
+                let ty = StructType::try_from(op.result(0)?.r#type())?;
+                let def = self.components.struct_def(&op, ty)?;
+                let prefix = self.fresh_name("call");
+                let component = self.allocate_component(&prefix, def)?;
+                self.translate_function(compute_func(def)?, inputs, Some(component))?
+                    .ok_or_else(|| fail(&op, "@compute did not return a value"))?
+            }
+            _ => {
+                return Err(fail(
+                    &op,
+                    "operation is not supported by the SMT-LIB2 translation",
+                ));
+            }
+        };
+        values.insert(op.result(0)?.to_raw().ptr, result);
+        Ok(())
+    }
+
+    /// Asserts that the member holds the value.
+    fn assign(
+        &mut self,
+        op: &OperationRef<'c, 'm>,
+        target: Member<String>,
+        value: Term,
+    ) -> Result<(), Error> {
+        match (target, value) {
+            (Member::Felt(var), Term::Felt(value)) => self.assert(op, format!("(= {var} {value})")),
+            (Member::Component(target), Term::Component(value)) => {
+                let members = self.components[target].members.clone();
+                for (name, member) in members {
+                    let value = match self.components[value].members.get(&name) {
+                        Some(Member::Felt(var)) => Term::Felt(var.clone()),
+                        Some(Member::Component(id)) => Term::Component(*id),
+                        None => return Err(fail(op, format!("struct has no member @{name}"))),
+                    };
+                    self.assign(op, member, value)?;
+                }
+                Ok(())
+            }
+            _ => Err(fail(op, "value does not match the type of the member")),
+        }
+    }
+}
+
+/// Returns the felt constant with the given value, which must be reduced.
+fn constant(sort: FeltSort, value: &str) -> String {
+    match sort {
+        FeltSort::FiniteField => format!("(as ff{value} F)"),
+        FeltSort::Integer => value.to_owned(),
+    }
+}
+
+fn mul(sort: FeltSort, lhs: &str, rhs: &str) -> String {
+    match sort {
+        FeltSort::FiniteField => format!("(ff.mul {lhs} {rhs})"),
+        FeltSort::Integer => format!("(mod (* {lhs} {rhs}) P)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::determinism::build_product_program, builder::OpBuilder, context::LlzkContext,
+        dialect, operation::BlockIterExt as _, targets::collect_structs,
+    };
+    use melior::ir::{Module, Value};
+
+    fn main_struct<'c>(module: &Module<'c>) -> StructDefOpRef<'c, '_> {
+        let mut structs = HashMap::new();
+        collect_structs(module.body(), &mut structs).unwrap();
+        structs["@Main"]
+    }
+
+    #[test]
+    fn translate_constrain_in_finite_field() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate(&main_struct(&module))
+            .unwrap();
+        let prime = Field::builtin("bn128").unwrap().prime().to_string();
+        assert_eq!(
+            script,
+            format!(
+                "; @constrain of @Main\n\
+                 (set-logic QF_FF)\n\
+                 (define-sort F () (_ FiniteField {prime}))\n\
+                 (declare-const main.c F)\n\
+                 (declare-const main.arg0 F)\n\
+                 (declare-const main.arg1 F)\n\
+                 (define-fun t.0 () F (ff.mul main.c main.arg1))\n\
+                 (assert (= t.0 main.arg0))\n\
+                 (check-sat)\n"
+            )
+        );
+    }
+
+    #[test]
+    fn translate_constrain_in_integers() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let script = SmtTranslator::new(FeltSort::Integer)
+            .with_default_field(Field::builtin("babybear").unwrap())
+            .translate(&main_struct(&module))
+            .unwrap();
+        assert!(script.contains("(set-logic QF_NIA)\n"), "{script}");
+        assert!(
+            script.contains("(define-fun P () Int 2013265921)\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(assert (and (<= 0 main.c) (< main.c P)))\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(define-fun t.0 () F (mod (* main.c main.arg1) P))\n"),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn translate_compute() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .with_compute(true)
+            .translate(&main_struct(&module))
+            .unwrap();
+        assert!(script.starts_with("; @compute and @constrain of @Main\n"));
+        assert!(script.contains("(declare-const q.1 F)\n"), "{script}");
+        assert!(
+            script.contains("(assert (not (= main.arg1 (as ff0 F))))\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(assert (= (ff.mul main.arg1 q.1) main.arg0))\n"),
+            "{script}"
+        );
+        assert!(script.contains("(assert (= main.c q.1))\n"), "{script}");
+        assert!(script.ends_with("(check-sat)\n"));
+    }
+
+    #[test]
+    fn translate_uniqueness_shares_public_inputs() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/division.mlir")).unwrap();
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate_uniqueness(main_struct(&module), "c")
+            .unwrap();
+        assert!(script.contains("(declare-const a.main.c F)\n"), "{script}");
+        assert!(script.contains("(declare-const b.main.c F)\n"), "{script}");
+        // The public input is shared and the private one is declared for each copy.
+        assert!(
+            script.contains("(declare-const a.main.arg0 F)\n"),
+            "{script}"
+        );
+        assert!(!script.contains("b.main.arg0"), "{script}");
+        assert!(
+            script.contains("(declare-const b.main.arg1 F)\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(define-fun t.1 () F (ff.mul b.main.c b.main.arg1))\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(assert (= t.1 a.main.arg0))\n"),
+            "{script}"
+        );
+        assert!(
+            script.ends_with("(assert (distinct a.main.c b.main.c))\n(check-sat)\n"),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn translate_determinism() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            include_str!("../../analysis/test_files/determinism.mlir"),
+        )
+        .unwrap();
+        let def = main_struct(&module);
+        let obligation = build_product_program(def).unwrap();
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate_determinism(&def, &obligation)
+            .unwrap();
+        assert!(script.starts_with("; @product of @Main\n"), "{script}");
+        // The witness of @compute and the arbitrary instance.
+        assert!(script.contains("(declare-const call.0.sq F)\n"), "{script}");
+        assert!(script.contains("(declare-const n.5.sq F)\n"), "{script}");
+        // The private input of the second copy is arbitrary, the public one is shared.
+        assert!(script.contains("(declare-const n.6 F)\n"), "{script}");
+        assert!(
+            script.contains("(define-fun t.7 () F (ff.mul main.arg0 main.arg0))\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(define-fun t.8 () F (ff.add n.5.sq n.6))\n"),
+            "{script}"
+        );
+        assert!(
+            script.contains("(assert (= call.0.out n.5.out))\n"),
+            "{script}"
+        );
+        assert!(
+            script.ends_with("(assert (distinct call.0.sq n.5.sq))\n(check-sat)\n"),
+            "{script}"
+        );
+
+        // The members of private subcomponents are part of the obligation.
+        let module = Module::parse(
+            &ctx,
+            include_str!("../../analysis/test_files/subcomponent.mlir"),
+        )
+        .unwrap();
+        let def = main_struct(&module);
+        let obligation = build_product_program(def).unwrap();
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate_determinism(&def, &obligation)
+            .unwrap();
+        assert!(
+            script.contains("(assert (distinct call.0.square.sq n."),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn translate_determinism_rejects_nondet_in_constraints() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(
+            &ctx,
+            r#"module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    struct.member @c : !felt.type
+    function.def @compute(%a: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@c] = %a : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      %n = llzk.nondet : !felt.type
+      constrain.eq %c, %n : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let def = main_struct(&module);
+        let obligation = build_product_program(def).unwrap();
+        let err = SmtTranslator::new(FeltSort::FiniteField)
+            .translate_determinism(&def, &obligation)
+            .unwrap_err();
+        let Error::TranslationFailed { name, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(name, "llzk.nondet");
+    }
+
+    /// Runs the script through a solver that supports the sort, or returns `None` if the solver is
+    /// not installed and `LLZK_REQUIRE_SOLVERS` is not set.
+    fn solve(sort: FeltSort, script: &str) -> Option<SatResult> {
+        let solver = match sort {
+            FeltSort::FiniteField => Solver::cvc5(),
+            FeltSort::Integer => Solver::z3(),
+        };
+        solver.check_if_installed(script)
+    }
+
+    #[test]
+    fn solve_determinism() {
+        let ctx = LlzkContext::new();
+        let cases = [
+            (
+                include_str!("../../analysis/test_files/determinism.mlir"),
+                SatResult::Unsat,
+            ),
+            // Nothing constrains the private member @tmp.
+            (
+                include_str!("../../analysis/test_files/unconstrained.mlir"),
+                SatResult::Sat,
+            ),
+            // The subcomponent is determined by the public input.
+            (
+                include_str!("../../analysis/test_files/subcomponent.mlir"),
+                SatResult::Unsat,
+            ),
+        ];
+        for (source, expected) in cases {
+            let module = Module::parse(&ctx, source).unwrap();
+            let def = main_struct(&module);
+            let obligation = build_product_program(def).unwrap();
+            for sort in [FeltSort::FiniteField, FeltSort::Integer] {
+                let script = SmtTranslator::new(sort)
+                    .translate_determinism(&def, &obligation)
+                    .unwrap();
+                if let Some(result) = solve(sort, &script) {
+                    assert_eq!(result, expected, "{script}");
+                }
+            }
+        }
+    }
+
+    /// Asserts a quantifier over the domain of `quantifier.mlir` whose body compares the bound
+    /// variable with the input.
+    fn add_quantifier(def: StructDefOpRef, forall: bool) {
+        let context = unsafe { def.context().to_ref() };
+        let block = constrain_func(def)
+            .unwrap()
+            .body()
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let input: Value = block.argument(1).unwrap().into();
+        let domain = block
+            .ops()
+            .find(|op| isa(op, "array.new"))
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into();
+        let loc = def.location();
+        let builder = OpBuilder::at_block_end(context, block);
+        let quantifier = if forall {
+            dialect::bool::forall(&builder, loc, domain)
+        } else {
+            dialect::bool::exists(&builder, loc, domain)
+        }
+        .unwrap();
+        let body = quantifier.region(0).unwrap().first_block().unwrap();
+        let body_builder = OpBuilder::at_block_end(context, body);
+        let var = body.argument(0).unwrap().into();
+        let cmp = if forall {
+            dialect::bool::ne(&body_builder, loc, var, input)
+        } else {
+            dialect::bool::eq(&body_builder, loc, var, input)
+        }
+        .unwrap();
+        dialect::bool::r#yield(&body_builder, loc, cmp.result(0).unwrap().into());
+        dialect::bool::assert(&builder, loc, quantifier.result(0).unwrap().into(), None).unwrap();
+    }
+
+    #[test]
+    fn translate_forall() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/quantifier.mlir")).unwrap();
+        let def = main_struct(&module);
+        add_quantifier(def, true);
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate(&def)
+            .unwrap();
+        assert!(script.contains("(set-logic ALL)\n"), "{script}");
+        assert!(
+            script.contains(
+                "(define-fun t.1 () Bool (forall ((x.0 F)) (=> (or (= x.0 (as ff1 F)) \
+                 (= x.0 (as ff2 F))) (distinct x.0 main.arg0))))\n"
+            ),
+            "{script}"
+        );
+        assert!(script.contains("(assert t.1)\n"), "{script}");
+
+        let script = SmtTranslator::new(FeltSort::Integer)
+            .translate(&def)
+            .unwrap();
+        assert!(
+            script.contains(
+                "(define-fun t.1 () Bool (forall ((x.0 F)) (=> (or (= x.0 1) (= x.0 2)) \
+                 (distinct x.0 main.arg0))))\n"
+            ),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn translate_exists() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/quantifier.mlir")).unwrap();
+        let def = main_struct(&module);
+        add_quantifier(def, false);
+        let script = SmtTranslator::new(FeltSort::FiniteField)
+            .translate(&def)
+            .unwrap();
+        assert!(
+            script.contains(
+                "(define-fun t.1 () Bool (exists ((x.0 F)) (and (or (= x.0 (as ff1 F)) \
+                 (= x.0 (as ff2 F))) (= x.0 main.arg0))))\n"
+            ),
+            "{script}"
+        );
+
+        let script = SmtTranslator::new(FeltSort::Integer)
+            .translate(&def)
+            .unwrap();
+        assert!(
+            script.contains(
+                "(define-fun t.1 () Bool (exists ((x.0 F)) (and (or (= x.0 1) (= x.0 2)) \
+                 (= x.0 main.arg0))))\n"
+            ),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn reject_ordering_in_finite_field() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("test_files/range.mlir")).unwrap();
+        let err = SmtTranslator::new(FeltSort::FiniteField)
+            .translate(&main_struct(&module))
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::TranslationFailed { name, .. } if name == "bool.cmp"),
+            "unexpected error: {err}"
+        );
+        let script = SmtTranslator::new(FeltSort::Integer)
+            .translate(&main_struct(&module))
+            .unwrap();
+        assert!(
+            script.contains("(define-fun t.0 () Bool (< main.arg0 16))\n"),
+            "{script}"
+        );
+        assert!(script.contains("(assert t.0)\n"), "{script}");
+    }
+}
@@ -0,0 +1,155 @@
+//! Running SMT-LIB2 scripts through a local solver binary.
+
+use crate::error::Error;
+use std::{
+    io::Write as _,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// Answer of a solver to `(check-sat)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatResult {
+    /// The assertions are satisfiable.
+    Sat,
+    /// The assertions are unsatisfiable.
+    Unsat,
+    /// The solver could not decide, for example because it ran out of time.
+    Unknown,
+}
+
+/// A solver binary that reads an SMT-LIB2 script on its standard input.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl Solver {
+    /// Creates a solver that runs the given program without arguments.
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    /// Returns a solver that runs `cvc5` from the `PATH`. It supports both
+    /// [felt sorts](super::FeltSort).
+    pub fn cvc5() -> Self {
+        Self::new("cvc5").with_arg("--lang=smt2")
+    }
+
+    /// Returns a solver that runs `z3` from the `PATH`. It only supports the
+    /// [integer felt sort](super::FeltSort::Integer).
+    pub fn z3() -> Self {
+        Self::new("z3").with_arg("-in")
+    }
+
+    /// Adds a command line argument.
+    pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Runs the script and returns the answer to its first `(check-sat)` command.
+    pub fn check(&self, script: &str) -> Result<SatResult, Error> {
+        let program = self.program.display();
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| Error::SolverFailed(format!("could not run {program}: {err}")))?;
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(script.as_bytes())
+            .map_err(|err| Error::SolverFailed(format!("could not write to {program}: {err}")))?;
+        let output = child
+            .wait_with_output()
+            .map_err(|err| Error::SolverFailed(format!("{program} did not finish: {err}")))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_answer(&stdout).ok_or_else(|| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Error::SolverFailed(format!(
+                "unexpected output from {program}: {}",
+                if stdout.trim().is_empty() {
+                    stderr.trim()
+                } else {
+                    stdout.trim()
+                }
+            ))
+        })
+    }
+}
+
+/// Name of the environment variable that makes the tests that need a solver fail if it is not
+/// installed, instead of skipping their checks.
+#[cfg(test)]
+const REQUIRE_SOLVERS: &str = "LLZK_REQUIRE_SOLVERS";
+
+#[cfg(test)]
+impl Solver {
+    /// Runs the script like [`Solver::check`], or returns `None` if the program is not installed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the solver fails, or if the program is not installed and [`REQUIRE_SOLVERS`] is
+    /// set.
+    pub(crate) fn check_if_installed(&self, script: &str) -> Option<SatResult> {
+        match self.check(script) {
+            Ok(result) => Some(result),
+            Err(Error::SolverFailed(msg)) if msg.starts_with("could not run") => {
+                assert!(
+                    std::env::var_os(REQUIRE_SOLVERS).is_none(),
+                    "{msg}, and {REQUIRE_SOLVERS} is set"
+                );
+                eprintln!(
+                    "skipping the checks that need {}: {msg}",
+                    self.program.display()
+                );
+                None
+            }
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+/// Reads the answer to `(check-sat)` from the output of a solver.
+fn parse_answer(output: &str) -> Option<SatResult> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .and_then(|line| match line {
+            "sat" => Some(SatResult::Sat),
+            "unsat" => Some(SatResult::Unsat),
+            "unknown" => Some(SatResult::Unknown),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        assert_eq!(parse_answer("sat\n"), Some(SatResult::Sat));
+        assert_eq!(parse_answer("\nunsat\n(model)\n"), Some(SatResult::Unsat));
+        assert_eq!(parse_answer("unknown"), Some(SatResult::Unknown));
+        assert_eq!(parse_answer("(error \"parse error\")\n"), None);
+        assert_eq!(parse_answer(""), None);
+    }
+
+    #[test]
+    fn missing_program() {
+        let err = Solver::new("/nonexistent/solver")
+            .check("(check-sat)\n")
+            .unwrap_err();
+        assert!(matches!(err, Error::SolverFailed(_)), "{err}");
+    }
+}
@@ -0,0 +1,14 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    function.def @compute(%in: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %in: !felt.type) {
+      %one = felt.const 1
+      %two = felt.const 2
+      %domain = array.new %one, %two : <2 x !felt.type>
+      function.return
+    }
+  }
+}
@@ -0,0 +1,14 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    function.def @compute(%in: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %in: !felt.type) {
+      %bound = felt.const 16
+      %lt = bool.cmp lt(%in, %bound) : !felt.type, !felt.type
+      bool.assert %lt
+      function.return
+    }
+  }
+}
@@ -0,0 +1,273 @@
+//! Symbolic instances of structs, shared by the translations that evaluate `@constrain` functions
+//! over variables: the polynomial extraction and the R1CS and SMT-LIB2 targets.
+//!
+//! Each felt member of an instance is assigned a variable, whose representation is up to the
+//! translation, and each struct member gets an instance of its own, recursively.
+
+use super::collect_structs;
+use crate::{
+    dialect::{
+        felt::FeltType,
+        function::{FuncDefOpLike as _, FuncDefOpRef},
+        r#struct::{
+            MemberDefOpLike as _, MemberDefOpRef, StructDefOpLike as _, StructDefOpRef, StructType,
+        },
+    },
+    error::Error,
+    interpreter::{Field, module_fields, root_operation},
+    operation::isa,
+};
+use melior::ir::{
+    BlockLike as _, OperationRef, RegionLike as _, Type, ValueLike as _,
+    attribute::{FlatSymbolRefAttribute, StringAttribute},
+    operation::OperationLike,
+};
+use std::{collections::HashMap, ops::Index, os::raw::c_void};
+
+/// Returns the error of a translation that does not support the operation.
+pub(crate) fn fail<'c: 'a, 'a>(
+    target: &'static str,
+    op: &impl OperationLike<'c, 'a>,
+    msg: impl Into<String>,
+) -> Error {
+    Error::TranslationFailed {
+        target,
+        name: op
+            .name()
+            .as_string_ref()
+            .as_str()
+            .unwrap_or("<unknown>")
+            .to_owned(),
+        location: op.location().to_string(),
+        msg: msg.into(),
+    }
+}
+
+/// Returns the name held by a string or flat symbol reference attribute of the operation.
+pub(crate) fn symbol_attr<'c: 'a, 'a>(
+    target: &'static str,
+    op: &impl OperationLike<'c, 'a>,
+    name: &str,
+) -> Result<String, Error> {
+    op.attribute(name)
+        .ok()
+        .and_then(|attr| {
+            FlatSymbolRefAttribute::try_from(attr)
+                .map(|attr| attr.value().to_owned())
+                .or_else(|_| StringAttribute::try_from(attr).map(|attr| attr.value().to_owned()))
+                .ok()
+        })
+        .ok_or_else(|| fail(target, op, format!("malformed '{name}' attribute")))
+}
+
+/// Returns the `@constrain` function of the struct.
+pub(crate) fn constrain_func<'c, 'm>(
+    def: StructDefOpRef<'c, 'm>,
+) -> Result<FuncDefOpRef<'c, 'm>, Error> {
+    def.constrain_func()
+        .ok_or_else(|| Error::SymbolNotFound(format!("{}::@constrain", def.fully_qualified_name())))
+}
+
+/// Calls `visit` on each operation of the body of the `@constrain` function of the struct, up to
+/// the return, with the arguments of the function bound to the given values.
+pub(crate) fn walk_constrain<'c, 'm, T, F>(
+    target: &'static str,
+    def: StructDefOpRef<'c, 'm>,
+    args: Vec<T>,
+    mut visit: F,
+) -> Result<(), Error>
+where
+    F: FnMut(&mut HashMap<*const c_void, T>, OperationRef<'c, 'm>) -> Result<(), Error>,
+{
+    let func = constrain_func(def)?;
+    let block = func.body()?.first_block().ok_or(Error::BlockExpected(0))?;
+    if block.argument_count() != args.len() {
+        return Err(fail(target, &func, "number of arguments does not match"));
+    }
+
+    let mut values = HashMap::new();
+    for (index, arg) in args.into_iter().enumerate() {
+        values.insert(block.argument(index)?.to_raw().ptr, arg);
+    }
+
+    let mut next = block.first_operation();
+    while let Some(op) = next {
+        if isa(&op, "function.return") {
+            return Ok(());
+        }
+        visit(&mut values, op)?;
+        next = op.next_in_block();
+    }
+    Err(fail(
+        target,
+        &func,
+        "function body did not end with a return",
+    ))
+}
+
+/// A member of a component.
+#[derive(Clone, Copy)]
+pub(crate) enum Member<V> {
+    Felt(V),
+    Component(usize),
+}
+
+/// An instance of a struct whose felt members have been assigned variables.
+pub(crate) struct Component<'c, 'm, V> {
+    pub(crate) def: StructDefOpRef<'c, 'm>,
+    pub(crate) members: HashMap<String, Member<V>>,
+}
+
+/// The structs and fields of a module, and the components allocated so far, indexed by id.
+pub(crate) struct Components<'c, 'm, V> {
+    target: &'static str,
+    structs: HashMap<String, StructDefOpRef<'c, 'm>>,
+    fields: HashMap<String, Field>,
+    default_field: Field,
+    /// Field of the felt types seen so far.
+    field: Option<Field>,
+    components: Vec<Component<'c, 'm, V>>,
+}
+
+impl<'c, 'm, V> Components<'c, 'm, V> {
+    /// Collects the structs and fields of the module that contains the operation.
+    pub(crate) fn new(
+        target: &'static str,
+        op: &impl OperationLike<'c, 'm>,
+        default_field: &Field,
+    ) -> Result<Self, Error> {
+        let root = unsafe { OperationRef::from_raw(root_operation(op.to_raw())) };
+        let mut structs = HashMap::new();
+        if let Some(block) = root.region(0)?.first_block() {
+            collect_structs(block, &mut structs)?;
+        }
+        Ok(Self {
+            target,
+            structs,
+            fields: module_fields(&root)?,
+            default_field: default_field.clone(),
+            field: None,
+            components: vec![],
+        })
+    }
+
+    /// Returns the structs of the module.
+    pub(crate) fn structs(&self) -> impl Iterator<Item = StructDefOpRef<'c, 'm>> + '_ {
+        self.structs.values().copied()
+    }
+
+    /// Returns the field of the felt types seen so far, or the default field if there were none.
+    pub(crate) fn field(&self) -> &Field {
+        self.field.as_ref().unwrap_or(&self.default_field)
+    }
+
+    /// Returns the field of a felt type and checks that it is the same for all types.
+    pub(crate) fn field_of(
+        &mut self,
+        op: &impl OperationLike<'c, 'm>,
+        ty: Type<'c>,
+    ) -> Result<Field, Error> {
+        let target = self.target;
+        let ty = FeltType::try_from(ty)
+            .map_err(|_| fail(target, op, format!("expected felt, got {ty}")))?;
+        let field = match ty.field_name() {
+            None => self.default_field.clone(),
+            Some(name) => self
+                .fields
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| fail(target, op, format!("unknown field '{name}'")))?,
+        };
+        match &self.field {
+            Some(existing) if *existing != field => {
+                Err(fail(target, op, "all felts must belong to the same field"))
+            }
+            Some(_) => Ok(field),
+            None => {
+                self.field = Some(field.clone());
+                Ok(field)
+            }
+        }
+    }
+
+    /// Returns the definition of the struct type.
+    pub(crate) fn struct_def(
+        &self,
+        op: &impl OperationLike<'c, 'm>,
+        ty: StructType<'c>,
+    ) -> Result<StructDefOpRef<'c, 'm>, Error> {
+        let name = ty.name().to_string();
+        self.structs
+            .get(&name)
+            .copied()
+            .ok_or_else(|| fail(self.target, op, format!("struct {name} was not found")))
+    }
+
+    /// Adds a component and returns its id.
+    pub(crate) fn push(&mut self, component: Component<'c, 'm, V>) -> usize {
+        self.components.push(component);
+        self.components.len() - 1
+    }
+
+    /// Allocates a component for the struct, named by the prefix, and returns its id. `felt`
+    /// creates the variable of a felt member from its path, like `main.sub.x`.
+    pub(crate) fn allocate_component(
+        &mut self,
+        prefix: &str,
+        def: StructDefOpRef<'c, 'm>,
+        felt: &mut impl FnMut(String, &MemberDefOpRef<'c, 'm>) -> V,
+    ) -> Result<usize, Error> {
+        let mut members = HashMap::new();
+        for member in def.member_defs() {
+            let value = self.allocate_member(prefix, &member, felt)?;
+            members.insert(member.member_name().to_owned(), value);
+        }
+        Ok(self.push(Component { def, members }))
+    }
+
+    /// Allocates a member of the component named by the prefix, either a variable or a component.
+    pub(crate) fn allocate_member(
+        &mut self,
+        prefix: &str,
+        member: &MemberDefOpRef<'c, 'm>,
+        felt: &mut impl FnMut(String, &MemberDefOpRef<'c, 'm>) -> V,
+    ) -> Result<Member<V>, Error> {
+        let path = format!("{prefix}.{}", member.member_name());
+        match StructType::try_from(member.member_type()) {
+            Ok(ty) => {
+                let def = self.struct_def(member, ty)?;
+                Ok(Member::Component(
+                    self.allocate_component(&path, def, felt)?,
+                ))
+            }
+            Err(_) => {
+                self.field_of(member, member.member_type())?;
+                Ok(Member::Felt(felt(path, member)))
+            }
+        }
+    }
+
+    /// Returns the variable of the felt member at the end of the path, following the
+    /// subcomponents of the component.
+    pub(crate) fn felt_at(&self, mut component: usize, path: &[String]) -> Option<&V> {
+        let (member, subcomponents) = path.split_last()?;
+        for name in subcomponents {
+            let Some(Member::Component(id)) = self.components[component].members.get(name) else {
+                return None;
+            };
+            component = *id;
+        }
+        match self.components[component].members.get(member) {
+            Some(Member::Felt(var)) => Some(var),
+            _ => None,
+        }
+    }
+}
+
+impl<'c, 'm, V> Index<usize> for Components<'c, 'm, V> {
+    type Output = Component<'c, 'm, V>;
+
+    fn index(&self, id: usize) -> &Self::Output {
+        &self.components[id]
+    }
+}
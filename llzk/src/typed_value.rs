@@ -0,0 +1,367 @@
+//! Typed wrappers around values for building expressions with Rust operators.
+//!
+//! [`FeltValue`], [`BoolValue`] and [`IndexValue`] carry a builder and a location together with
+//! the value. Operators and methods on the wrappers emit the corresponding `felt`, `bool` or
+//! `arith` operation at the current insertion point of the builder, using the location of the
+//! left-hand side, and return the wrapped result.
+//!
+//! ```ignore
+//! let a = FeltValue::new(&builder, loc, a)?;
+//! let b = FeltValue::new(&builder, loc, b)?;
+//! let c = FeltValue::new(&builder, loc, c)?;
+//! let out = a * b + c;
+//! out.eq(c).assert(Some("unexpected output"));
+//! ```
+//!
+//! # Panics
+//!
+//! Operators have no way to return errors, so the operators and methods that return a wrapper
+//! panic if the builder fails to create the operation. The builders of the operations they emit
+//! only fail on operands they cannot build an operation from. `new` fails on values of the wrong
+//! type, so the operands of the wrappers are always felts, `i1` values or indices, and the results
+//! of the operations they emit have the type of the wrapper they are wrapped in.
+
+use crate::{
+    builder::OpBuilderLike,
+    dialect::{
+        self, cast,
+        felt::{self, FeltConstAttribute, FeltType},
+    },
+    error::Error,
+};
+use melior::{
+    Context,
+    dialect::arith::{self, CmpiPredicate},
+    ir::{
+        Location, Operation, OperationRef, Type, TypeLike as _, Value, ValueLike as _,
+        attribute::IntegerAttribute, operation::OperationLike as _, r#type::IntegerType,
+    },
+};
+use std::{fmt, ops};
+
+/// Returns the result of an operation that was just built.
+///
+/// # Panics
+///
+/// Panics with the error of the builder if the operation could not be built.
+fn result<'c, 'v>(op: Result<OperationRef<'c, 'v>, Error>) -> Value<'c, 'v> {
+    let op = op.unwrap_or_else(|err| panic!("failed to build an operation: {err}"));
+    op.result(0)
+        .expect("the operations emitted by the wrappers have a result")
+        .into()
+}
+
+/// Inserts the operation built by the closure and returns its result.
+fn insert<'c, 'v>(
+    builder: &impl OpBuilderLike<'c>,
+    location: Location<'c>,
+    f: impl FnOnce(&'c Context, Location<'c>) -> Operation<'c>,
+) -> Value<'c, 'v> {
+    result(Ok(builder.insert(location, f)))
+}
+
+/// Fails if the type is not a felt type.
+fn check_felt(r#type: Type) -> Result<(), melior::Error> {
+    FeltType::try_from(r#type).map(drop)
+}
+
+/// Fails if the type is not `i1`.
+fn check_bool(r#type: Type) -> Result<(), melior::Error> {
+    if IntegerType::try_from(r#type).is_ok_and(|r#type| r#type.width() == 1) {
+        Ok(())
+    } else {
+        Err(melior::Error::TypeExpected("i1", r#type.to_string()))
+    }
+}
+
+/// Fails if the type is not `index`.
+fn check_index(r#type: Type) -> Result<(), melior::Error> {
+    if r#type.is_index() {
+        Ok(())
+    } else {
+        Err(melior::Error::TypeExpected("index", r#type.to_string()))
+    }
+}
+
+macro_rules! wrapper {
+    ($(#[$doc:meta])* $name:ident, $check:path) => {
+        $(#[$doc])*
+        pub struct $name<'c, 'v, 'b, B> {
+            builder: &'b B,
+            location: Location<'c>,
+            value: Value<'c, 'v>,
+        }
+
+        impl<'c, 'v, 'b, B: OpBuilderLike<'c>> $name<'c, 'v, 'b, B> {
+            /// Wraps the value. Operations are built with the builder at the given location.
+            ///
+            /// Fails if the value doesn't have the type of the wrapper.
+            pub fn new(
+                builder: &'b B,
+                location: Location<'c>,
+                value: Value<'c, 'v>,
+            ) -> Result<Self, Error> {
+                $check(value.r#type())?;
+                Ok(Self::unchecked(builder, location, value))
+            }
+
+            /// Wraps a value that is known to have the type of the wrapper.
+            fn unchecked(builder: &'b B, location: Location<'c>, value: Value<'c, 'v>) -> Self {
+                Self {
+                    builder,
+                    location,
+                    value,
+                }
+            }
+
+            /// Returns the wrapped value.
+            pub fn value(&self) -> Value<'c, 'v> {
+                self.value
+            }
+
+            /// Returns the location used for the operations built from this value.
+            pub fn location(&self) -> Location<'c> {
+                self.location
+            }
+
+            /// Returns the same value with a different location for the operations built from it.
+            pub fn at(self, location: Location<'c>) -> Self {
+                Self { location, ..self }
+            }
+
+            fn wrap(&self, value: Value<'c, 'v>) -> Self {
+                Self::unchecked(self.builder, self.location, value)
+            }
+        }
+
+        impl<'c, 'v, B> Clone for $name<'c, 'v, '_, B> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<'c, 'v, B> Copy for $name<'c, 'v, '_, B> {}
+
+        impl<'c, 'v, B> fmt::Debug for $name<'c, 'v, '_, B> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.value).finish()
+            }
+        }
+
+        impl<'c, 'v, B> From<$name<'c, 'v, '_, B>> for Value<'c, 'v> {
+            fn from(value: $name<'c, 'v, '_, B>) -> Self {
+                value.value
+            }
+        }
+    };
+}
+
+macro_rules! operator {
+    (binary $name:ident, $trait:ident, $method:ident, $build:path) => {
+        impl<'c, 'v, 'b, B: OpBuilderLike<'c>> ops::$trait for $name<'c, 'v, 'b, B> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.wrap(result($build(
+                    self.builder,
+                    self.location,
+                    self.value,
+                    rhs.value,
+                )))
+            }
+        }
+    };
+    (unary $name:ident, $trait:ident, $method:ident, $build:path) => {
+        impl<'c, 'v, 'b, B: OpBuilderLike<'c>> ops::$trait for $name<'c, 'v, 'b, B> {
+            type Output = Self;
+
+            fn $method(self) -> Self {
+                self.wrap(result($build(self.builder, self.location, self.value)))
+            }
+        }
+    };
+    (arith $name:ident, $trait:ident, $method:ident, $build:path) => {
+        impl<'c, 'v, 'b, B: OpBuilderLike<'c>> ops::$trait for $name<'c, 'v, 'b, B> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.wrap(insert(self.builder, self.location, |_, location| {
+                    $build(self.value, rhs.value, location)
+                }))
+            }
+        }
+    };
+}
+
+wrapper!(
+    /// A felt value. Arithmetic operators emit `felt` operations and comparisons emit `bool.cmp`.
+    FeltValue,
+    check_felt
+);
+
+operator!(binary FeltValue, Add, add, felt::add);
+operator!(binary FeltValue, Sub, sub, felt::sub);
+operator!(binary FeltValue, Mul, mul, felt::mul);
+operator!(binary FeltValue, Div, div, felt::div);
+operator!(binary FeltValue, Shl, shl, felt::shl);
+operator!(binary FeltValue, Shr, shr, felt::shr);
+operator!(binary FeltValue, BitAnd, bitand, felt::bit_and);
+operator!(binary FeltValue, BitOr, bitor, felt::bit_or);
+operator!(binary FeltValue, BitXor, bitxor, felt::bit_xor);
+operator!(unary FeltValue, Neg, neg, felt::neg);
+operator!(unary FeltValue, Not, not, felt::bit_not);
+
+macro_rules! felt_method {
+    ($(#[$doc:meta])* $name:ident, $build:path) => {
+        $(#[$doc])*
+        pub fn $name(self, rhs: Self) -> Self {
+            self.wrap(result($build(self.builder, self.location, self.value, rhs.value)))
+        }
+    };
+}
+
+macro_rules! felt_cmp {
+    ($name:ident, $build:path) => {
+        #[doc = concat!("Emits `bool.cmp ", stringify!($name), "` to compare with another felt.")]
+        pub fn $name(self, rhs: Self) -> BoolValue<'c, 'v, 'b, B> {
+            let value = result($build(self.builder, self.location, self.value, rhs.value));
+            BoolValue::unchecked(self.builder, self.location, value)
+        }
+    };
+}
+
+impl<'c, 'v, 'b, B: OpBuilderLike<'c>> FeltValue<'c, 'v, 'b, B> {
+    /// Emits a `felt.const` operation and wraps its result.
+    pub fn constant(builder: &'b B, location: Location<'c>, value: FeltConstAttribute<'c>) -> Self {
+        Self::unchecked(
+            builder,
+            location,
+            result(felt::constant(builder, location, value)),
+        )
+    }
+
+    felt_method!(
+        /// Emits `felt.pow` to raise the value to the given exponent.
+        pow,
+        felt::pow
+    );
+    felt_method!(
+        /// Emits `felt.uintdiv` for the unsigned integer division by another felt.
+        uintdiv,
+        felt::uintdiv
+    );
+    felt_method!(
+        /// Emits `felt.sintdiv` for the signed integer division by another felt.
+        sintdiv,
+        felt::sintdiv
+    );
+    felt_method!(
+        /// Emits `felt.umod` for the unsigned remainder of the division by another felt.
+        umod,
+        felt::umod
+    );
+    felt_method!(
+        /// Emits `felt.smod` for the signed remainder of the division by another felt.
+        smod,
+        felt::smod
+    );
+
+    /// Emits `felt.inv` for the multiplicative inverse of the value.
+    pub fn inv(self) -> Self {
+        self.wrap(result(felt::inv(self.builder, self.location, self.value)))
+    }
+
+    felt_cmp!(eq, dialect::bool::eq);
+    felt_cmp!(ne, dialect::bool::ne);
+    felt_cmp!(lt, dialect::bool::lt);
+    felt_cmp!(le, dialect::bool::le);
+    felt_cmp!(gt, dialect::bool::gt);
+    felt_cmp!(ge, dialect::bool::ge);
+
+    /// Emits `cast.toindex` to convert the value into an index.
+    pub fn to_index(self) -> IndexValue<'c, 'v, 'b, B> {
+        let value = result(Ok(cast::toindex(
+            self.builder,
+            self.location,
+            self.value,
+            None,
+        )));
+        IndexValue::unchecked(self.builder, self.location, value)
+    }
+}
+
+wrapper!(
+    /// A boolean (`i1`) value. Logical operators emit `bool` operations.
+    BoolValue,
+    check_bool
+);
+
+operator!(binary BoolValue, BitAnd, bitand, dialect::bool::and);
+operator!(binary BoolValue, BitOr, bitor, dialect::bool::or);
+operator!(binary BoolValue, BitXor, bitxor, dialect::bool::xor);
+operator!(unary BoolValue, Not, not, dialect::bool::not);
+
+impl<'c, 'v, 'b, B: OpBuilderLike<'c>> BoolValue<'c, 'v, 'b, B> {
+    /// Emits `bool.assert` on the value, with an optional message.
+    pub fn assert(self, msg: Option<&str>) -> Result<OperationRef<'c, 'v>, Error> {
+        dialect::bool::assert(self.builder, self.location, self.value, msg)
+    }
+}
+
+wrapper!(
+    /// An index value. Arithmetic operators emit signed `arith` operations and comparisons emit
+    /// `arith.cmpi`.
+    IndexValue,
+    check_index
+);
+
+operator!(arith IndexValue, Add, add, arith::addi);
+operator!(arith IndexValue, Sub, sub, arith::subi);
+operator!(arith IndexValue, Mul, mul, arith::muli);
+operator!(arith IndexValue, Div, div, arith::divsi);
+operator!(arith IndexValue, Rem, rem, arith::remsi);
+
+macro_rules! index_cmp {
+    ($name:ident, $predicate:ident) => {
+        #[doc = concat!("Emits `arith.cmpi ", stringify!($predicate), "` to compare with another index.")]
+        pub fn $name(self, rhs: Self) -> BoolValue<'c, 'v, 'b, B> {
+            let value = insert(self.builder, self.location, |ctx, l| {
+                arith::cmpi(ctx, CmpiPredicate::$predicate, self.value, rhs.value, l)
+            });
+            BoolValue::unchecked(self.builder, self.location, value)
+        }
+    };
+}
+
+impl<'c, 'v, 'b, B: OpBuilderLike<'c>> IndexValue<'c, 'v, 'b, B> {
+    /// Emits an `arith.constant` of index type and wraps its result.
+    pub fn constant(builder: &'b B, location: Location<'c>, value: i64) -> Self {
+        let value = insert(builder, location, |ctx, location| {
+            arith::constant(
+                ctx,
+                IntegerAttribute::new(Type::index(ctx), value).into(),
+                location,
+            )
+        });
+        Self::unchecked(builder, location, value)
+    }
+
+    index_cmp!(eq, Eq);
+    index_cmp!(ne, Ne);
+    index_cmp!(lt, Slt);
+    index_cmp!(le, Sle);
+    index_cmp!(gt, Sgt);
+    index_cmp!(ge, Sge);
+
+    /// Emits `cast.tofelt` to convert the value into a felt of the given type, or of the default
+    /// felt type if `None`.
+    pub fn to_felt(self, r#type: Option<FeltType<'c>>) -> FeltValue<'c, 'v, 'b, B> {
+        let value = result(Ok(cast::tofelt(
+            self.builder,
+            self.location,
+            self.value,
+            r#type,
+        )));
+        FeltValue::unchecked(self.builder, self.location, value)
+    }
+}
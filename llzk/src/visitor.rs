@@ -0,0 +1,266 @@
+//! Typed dispatch over the operations of the LLZK dialects.
+//!
+//! [`LlzkOpRef`] classifies any [`OperationRef`] into one variant per LLZK op, which makes it
+//! possible to `match` over operations instead of chaining `is_*_op` predicates. The ops that have
+//! a dedicated reference type, like [`StructDefOpRef`](crate::dialect::r#struct::StructDefOpRef),
+//! are wrapped in it and the others keep their [`OperationRef`].
+//!
+//! [`LlzkVisitor`] has one `visit_*` method per variant that does nothing by default, and
+//! [`LlzkVisitor::walk`] calls them on the operations nested in an operation:
+//!
+//! ```ignore
+//! #[derive(Default)]
+//! struct CountConstraints(usize);
+//!
+//! impl<'c: 'a, 'a> LlzkVisitor<'c, 'a> for CountConstraints {
+//!     fn visit_constrain_eq(&mut self, _op: OperationRef<'c, 'a>) -> WalkResult {
+//!         self.0 += 1;
+//!         WalkResult::Advance
+//!     }
+//! }
+//!
+//! let mut count = CountConstraints::default();
+//! count.walk(&module.as_operation());
+//! ```
+
+use std::fmt;
+
+use melior::ir::operation::{OperationLike, OperationRef, WalkResult};
+
+use crate::{
+    dialect,
+    operation::{TypedOperationRef, push_nested},
+};
+
+macro_rules! llzk_ops {
+    (
+        typed {
+            $($tname:literal => $tvariant:ident($($tty:ident)::+), $tvisit:ident;)*
+        }
+        untyped {
+            $($uname:literal => $uvariant:ident($($uisa:ident)::+), $uvisit:ident;)*
+        }
+    ) => {
+        /// A reference to an operation, classified by LLZK op.
+        ///
+        /// Built from any [`OperationRef`]; the operations that are not part of an LLZK dialect are
+        /// [`LlzkOpRef::Other`].
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum LlzkOpRef<'c, 'a> {
+            $(
+                #[doc = concat!("A `", $tname, "` op.")]
+                $tvariant($($tty)::+<'c, 'a>),
+            )*
+            $(
+                #[doc = concat!("A `", $uname, "` op.")]
+                $uvariant(OperationRef<'c, 'a>),
+            )*
+            /// An operation that is not part of an LLZK dialect.
+            Other(OperationRef<'c, 'a>),
+        }
+
+        impl<'c: 'a, 'a> From<OperationRef<'c, 'a>> for LlzkOpRef<'c, 'a> {
+            fn from(op: OperationRef<'c, 'a>) -> Self {
+                $(
+                    if let Some(op) = <$($tty)::+>::from_option_raw(op.to_raw()) {
+                        return Self::$tvariant(op);
+                    }
+                )*
+                $(
+                    if $($uisa)::+(&op) {
+                        return Self::$uvariant(op);
+                    }
+                )*
+                Self::Other(op)
+            }
+        }
+
+        impl<'c: 'a, 'a> OperationLike<'c, 'a> for LlzkOpRef<'c, 'a> {
+            fn to_raw(&self) -> mlir_sys::MlirOperation {
+                match self {
+                    $(Self::$tvariant(op) => OperationLike::to_raw(op),)*
+                    $(Self::$uvariant(op) => op.to_raw(),)*
+                    Self::Other(op) => op.to_raw(),
+                }
+            }
+        }
+
+        /// Visits the operations of the LLZK dialects by kind.
+        ///
+        /// Every `visit_*` method does nothing and returns [`WalkResult::Advance`] by default, so
+        /// implementations only override the ones they care about. The returned [`WalkResult`]
+        /// controls the [walk](Self::walk): `Skip` doesn't visit the operations nested in the
+        /// visited one and `Interrupt` stops the walk.
+        pub trait LlzkVisitor<'c: 'a, 'a> {
+            $(
+                #[doc = concat!("Visits a `", $tname, "` op.")]
+                fn $tvisit(&mut self, _op: $($tty)::+<'c, 'a>) -> WalkResult {
+                    WalkResult::Advance
+                }
+            )*
+            $(
+                #[doc = concat!("Visits a `", $uname, "` op.")]
+                fn $uvisit(&mut self, _op: OperationRef<'c, 'a>) -> WalkResult {
+                    WalkResult::Advance
+                }
+            )*
+
+            /// Visits an operation that is not part of an LLZK dialect.
+            fn visit_other(&mut self, _op: OperationRef<'c, 'a>) -> WalkResult {
+                WalkResult::Advance
+            }
+
+            /// Calls the `visit_*` method of the kind of the operation.
+            ///
+            /// Override it to handle every operation, calling [`dispatch`](Self::dispatch) to
+            /// keep calling the dedicated methods.
+            fn visit(&mut self, op: LlzkOpRef<'c, 'a>) -> WalkResult {
+                self.dispatch(op)
+            }
+
+            /// Calls the `visit_*` method of the kind of the operation.
+            fn dispatch(&mut self, op: LlzkOpRef<'c, 'a>) -> WalkResult {
+                match op {
+                    $(LlzkOpRef::$tvariant(op) => self.$tvisit(op),)*
+                    $(LlzkOpRef::$uvariant(op) => self.$uvisit(op),)*
+                    LlzkOpRef::Other(op) => self.visit_other(op),
+                }
+            }
+
+            /// Calls [`visit`](Self::visit) on the operation and the operations nested in it, in
+            /// pre-order.
+            ///
+            /// Returns [`WalkResult::Interrupt`] if a visit interrupted the walk and
+            /// [`WalkResult::Advance`] otherwise. Operations must not be erased or moved during
+            /// the walk.
+            fn walk(&mut self, op: &impl OperationLike<'c, 'a>) -> WalkResult
+            where
+                Self: Sized,
+            {
+                let root = unsafe { OperationRef::from_raw(op.to_raw()) };
+                let mut stack = vec![root];
+                while let Some(op) = stack.pop() {
+                    if op != root {
+                        if let Some(next) = op.next_in_block() {
+                            stack.push(next);
+                        }
+                    }
+                    match self.visit(op.into()) {
+                        WalkResult::Advance => push_nested(&mut stack, op),
+                        WalkResult::Skip => {}
+                        WalkResult::Interrupt => return WalkResult::Interrupt,
+                    }
+                }
+                WalkResult::Advance
+            }
+        }
+    };
+}
+
+llzk_ops! {
+    typed {
+        "struct.def" => StructDef(dialect::r#struct::StructDefOpRef), visit_struct_def;
+        "struct.member" => MemberDef(dialect::r#struct::MemberDefOpRef), visit_member_def;
+        "function.def" => FuncDef(dialect::function::FuncDefOpRef), visit_func_def;
+        "function.call" => Call(dialect::function::CallOpRef), visit_call;
+        "include.from" => Include(dialect::include::IncludeOpRef), visit_include;
+        "poly.template" => Template(dialect::poly::ops::TemplateOpRef), visit_template;
+        "poly.param" => TemplateParam(dialect::poly::ops::TemplateParamOpRef), visit_template_param;
+        "poly.expr" => TemplateExpr(dialect::poly::ops::TemplateExprOpRef), visit_template_expr;
+        "poly.yield" => PolyYield(dialect::poly::ops::YieldOpRef), visit_poly_yield;
+        "verif.contract" => Contract(dialect::verif::ContractOpRef), visit_contract;
+        "verif.include" => VerifInclude(dialect::verif::IncludeOpRef), visit_verif_include;
+        "verif.invariant" => Invariant(dialect::verif::InvariantOpRef), visit_invariant;
+        "verif.ensure_compute" => EnsureCompute(dialect::verif::EnsureComputeOpRef), visit_ensure_compute;
+        "verif.ensure_constrain" => EnsureConstrain(dialect::verif::EnsureConstrainOpRef), visit_ensure_constrain;
+        "verif.require_compute" => RequireCompute(dialect::verif::RequireComputeOpRef), visit_require_compute;
+        "verif.require_constrain" => RequireConstrain(dialect::verif::RequireConstrainOpRef), visit_require_constrain;
+    }
+    untyped {
+        "array.new" => ArrayNew(dialect::array::is_new_op), visit_array_new;
+        "array.read" => ArrayRead(dialect::array::is_read_op), visit_array_read;
+        "array.write" => ArrayWrite(dialect::array::is_write_op), visit_array_write;
+        "array.extract" => ArrayExtract(dialect::array::is_extract_op), visit_array_extract;
+        "array.insert" => ArrayInsert(dialect::array::is_insert_op), visit_array_insert;
+        "array.len" => ArrayLen(dialect::array::is_len_op), visit_array_len;
+        "bool.cmp" => BoolCmp(dialect::bool::is_cmp_op), visit_bool_cmp;
+        "bool.and" => BoolAnd(dialect::bool::is_and_op), visit_bool_and;
+        "bool.or" => BoolOr(dialect::bool::is_or_op), visit_bool_or;
+        "bool.xor" => BoolXor(dialect::bool::is_xor_op), visit_bool_xor;
+        "bool.not" => BoolNot(dialect::bool::is_not_op), visit_bool_not;
+        "bool.assert" => BoolAssert(dialect::bool::is_assert_op), visit_bool_assert;
+        "bool.forall" => BoolForAll(dialect::bool::is_forall_op), visit_bool_forall;
+        "bool.exists" => BoolExists(dialect::bool::is_exists_op), visit_bool_exists;
+        "bool.yield" => BoolYield(dialect::bool::is_yield_op), visit_bool_yield;
+        "cast.tofelt" => CastToFelt(dialect::cast::is_tofelt_op), visit_cast_tofelt;
+        "cast.toindex" => CastToIndex(dialect::cast::is_toindex_op), visit_cast_toindex;
+        "constrain.eq" => ConstrainEq(dialect::constrain::is_eq_op), visit_constrain_eq;
+        "constrain.in" => ConstrainIn(dialect::constrain::is_in_op), visit_constrain_in;
+        "felt.const" => FeltConst(dialect::felt::is_const_op), visit_felt_const;
+        "felt.add" => FeltAdd(dialect::felt::is_add_op), visit_felt_add;
+        "felt.sub" => FeltSub(dialect::felt::is_sub_op), visit_felt_sub;
+        "felt.mul" => FeltMul(dialect::felt::is_mul_op), visit_felt_mul;
+        "felt.div" => FeltDiv(dialect::felt::is_div_op), visit_felt_div;
+        "felt.pow" => FeltPow(dialect::felt::is_pow_op), visit_felt_pow;
+        "felt.inv" => FeltInv(dialect::felt::is_inv_op), visit_felt_inv;
+        "felt.neg" => FeltNeg(dialect::felt::is_neg_op), visit_felt_neg;
+        "felt.shl" => FeltShl(dialect::felt::is_shl_op), visit_felt_shl;
+        "felt.shr" => FeltShr(dialect::felt::is_shr_op), visit_felt_shr;
+        "felt.sintdiv" => FeltSIntDiv(dialect::felt::is_sintdiv_op), visit_felt_sintdiv;
+        "felt.smod" => FeltSMod(dialect::felt::is_smod_op), visit_felt_smod;
+        "felt.uintdiv" => FeltUIntDiv(dialect::felt::is_uintdiv_op), visit_felt_uintdiv;
+        "felt.umod" => FeltUMod(dialect::felt::is_umod_op), visit_felt_umod;
+        "felt.bit_and" => FeltBitAnd(dialect::felt::is_bit_and_op), visit_felt_bit_and;
+        "felt.bit_or" => FeltBitOr(dialect::felt::is_bit_or_op), visit_felt_bit_or;
+        "felt.bit_xor" => FeltBitXor(dialect::felt::is_bit_xor_op), visit_felt_bit_xor;
+        "felt.bit_not" => FeltBitNot(dialect::felt::is_bit_not_op), visit_felt_bit_not;
+        "function.return" => Return(dialect::function::is_return_op), visit_return;
+        "global.def" => GlobalDef(dialect::global::is_def_op), visit_global_def;
+        "global.read" => GlobalRead(dialect::global::is_read_op), visit_global_read;
+        "global.write" => GlobalWrite(dialect::global::is_write_op), visit_global_write;
+        "llzk.nondet" => NonDet(dialect::llzk::is_nondet_op), visit_nondet;
+        "pod.new" => PodNew(dialect::pod::is_new_op), visit_pod_new;
+        "pod.read" => PodRead(dialect::pod::is_read_op), visit_pod_read;
+        "pod.write" => PodWrite(dialect::pod::is_write_op), visit_pod_write;
+        "poly.read_const" => ReadConst(dialect::poly::is_read_const_op), visit_read_const;
+        "poly.unifiable_cast" => UnifiableCast(dialect::poly::is_unifiable_cast_op), visit_unifiable_cast;
+        "poly.applymap" => ApplyMap(dialect::poly::is_applymap_op), visit_applymap;
+        "ram.load" => RamLoad(dialect::ram::is_load_op), visit_ram_load;
+        "ram.store" => RamStore(dialect::ram::is_store_op), visit_ram_store;
+        "struct.new" => StructNew(dialect::r#struct::is_new_op), visit_struct_new;
+        "struct.readm" => MemberRead(dialect::r#struct::is_readm_op), visit_member_read;
+        "struct.writem" => MemberWrite(dialect::r#struct::is_writem_op), visit_member_write;
+        "verif.contract_end" => ContractEnd(dialect::verif::is_contract_end_op), visit_contract_end;
+        "verif.increases" => Increases(dialect::verif::is_increases_op), visit_increases;
+        "verif.decreases" => Decreases(dialect::verif::is_decreases_op), visit_decreases;
+        "verif.step" => Step(dialect::verif::is_step_op), visit_step;
+        "verif.step_yield" => StepYield(dialect::verif::is_step_yield_op), visit_step_yield;
+        "verif.old" => Old(dialect::verif::is_old_op), visit_old;
+    }
+}
+
+impl LlzkOpRef<'_, '_> {
+    /// Returns `true` iff the operation is part of an LLZK dialect.
+    pub fn is_llzk(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+}
+
+impl<'c: 'a, 'a> TypedOperationRef<'c, 'a> for LlzkOpRef<'c, 'a> {
+    #[inline]
+    fn isa(_: &OperationRef<'c, 'a>) -> bool {
+        true
+    }
+}
+
+impl<'c: 'a, 'a> From<LlzkOpRef<'c, 'a>> for OperationRef<'c, 'a> {
+    fn from(op: LlzkOpRef<'c, 'a>) -> Self {
+        unsafe { Self::from_raw(op.to_raw()) }
+    }
+}
+
+impl fmt::Display for LlzkOpRef<'_, '_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&OperationRef::from(*self), formatter)
+    }
+}
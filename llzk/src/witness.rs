@@ -0,0 +1,764 @@
+//! Input and witness files of circuits.
+//!
+//! Both are JSON documents whose values follow the types they are read as:
+//!
+//! - felts are strings with a decimal or a `0x`-prefixed hexadecimal number less than the prime of
+//!   their field, and are written in decimal;
+//! - `i1` values are booleans and `index` values are numbers;
+//! - arrays are nested arrays, one level per dimension;
+//! - pods and structs are objects keyed by the names of their records and members;
+//! - uninitialized elements, records and members are `null`.
+//!
+//! An input file is an object keyed by the names of the inputs in the [`CircuitAbi`] of the main
+//! struct. A witness file holds the inputs and the members of the instance of the main struct
+//! that was computed from them, which is what [`Interpreter::check_constraints`] needs:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "main": "@Main",
+//!   "inputs": { "a": "3", "b": ["1", "0x2"] },
+//!   "members": { "c": "9", "sub": { "x": "1" } }
+//! }
+//! ```
+//!
+//! Errors point at the offending value with a path like `$.members.sub.x`.
+//!
+//! [`Interpreter::check_constraints`]: crate::interpreter::Interpreter::check_constraints
+
+use melior::ir::{
+    Type, TypeLike as _, attribute::IntegerAttribute, operation::OperationLike, r#type::IntegerType,
+};
+use num_bigint::BigUint;
+use std::collections::HashMap;
+
+use crate::{
+    abi::{CircuitAbi, Shape},
+    dialect::{
+        array::ArrayType,
+        felt::FeltType,
+        pod::r#type::PodType,
+        r#struct::{MemberDefOpLike as _, StructDefOpLike as _, StructDefOpRef, StructType},
+    },
+    error::Error,
+    interpreter::{ArrayValue, Field, PodValue, StructInstance, Value, module_fields},
+    json::Json,
+};
+
+/// Version of the format of witness files.
+pub const WITNESS_VERSION: u64 = 1;
+
+/// Reads an input file of the circuit. Returns the inputs in the order of the arguments of
+/// `@compute`.
+///
+/// Felts are checked against the builtin field their type names, or `bn128`, the default field of
+/// the interpreter, if it doesn't name one. The ABI does not describe the fields declared by the
+/// module, so the felts of those fields are not checked.
+pub fn read_inputs(abi: &CircuitAbi, json: &str) -> Result<Vec<Value>, Error> {
+    read_input_values(abi, &Json::parse(json)?, "$", &Fields::builtin())
+}
+
+/// Writes the inputs of the circuit, in the order of the arguments of `@compute`, as an input
+/// file. Felts are checked like in [`read_inputs`].
+pub fn write_inputs(abi: &CircuitAbi, inputs: &[Value]) -> Result<String, Error> {
+    Ok(write_input_values(abi, inputs, "$", &Fields::builtin())?.to_string())
+}
+
+/// The inputs of a struct and the instance computed from them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    /// Arguments of `@compute`, in order.
+    pub inputs: Vec<Value>,
+    /// Instance returned by `@compute`.
+    pub instance: StructInstance,
+}
+
+impl Witness {
+    /// Reads a witness file of the struct.
+    ///
+    /// Felts are checked against the field their type names, or `bn128`, the default field of the
+    /// interpreter, if it doesn't name one.
+    pub fn read(def: StructDefOpRef, json: &str) -> Result<Self, Error> {
+        Self::read_with_default_field(def, json, Fields::default_field())
+    }
+
+    /// Like [`read`](Self::read), but checks the felts whose type doesn't name a field against the
+    /// given field. It should be the default field of the interpreter that checks the witness.
+    pub fn read_with_default_field(
+        def: StructDefOpRef,
+        json: &str,
+        default_field: Field,
+    ) -> Result<Self, Error> {
+        let fields = Fields {
+            named: module_fields(&def)?,
+            default: default_field,
+        };
+        let abi = CircuitAbi::from_struct(def)?;
+        let layout = Layout::of_struct(def, def.r#type(), &def, &fields)?;
+        let json = Json::parse(json)?;
+        let Json::Object(entries) = &json else {
+            return Err(invalid("$", expected("an object", &json)));
+        };
+        if let Some((key, _)) = entries
+            .iter()
+            .find(|(key, _)| !["version", "main", "inputs", "members"].contains(&key.as_str()))
+        {
+            return Err(invalid("$", format!("unknown key '{key}'")));
+        }
+        let entry = |key: &str| {
+            entries
+                .iter()
+                .find(|(entry, _)| entry == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| invalid("$", format!("missing key '{key}'")))
+        };
+
+        let version = WITNESS_VERSION.to_string();
+        match entry("version")? {
+            Json::Number(number) if *number == version => {}
+            json => {
+                return Err(invalid(
+                    "$.version",
+                    format!("expected version {version}, found {json}"),
+                ));
+            }
+        }
+        match entry("main")? {
+            Json::String(main) if *main == abi.main => {}
+            json => {
+                return Err(invalid(
+                    "$.main",
+                    format!("expected the witness of {}, found {json}", abi.main),
+                ));
+            }
+        }
+        let inputs = read_input_values(&abi, entry("inputs")?, "$.inputs", &fields)?;
+        let instance = match layout.read(entry("members")?, "$.members")? {
+            Some(Value::Struct(instance)) => instance,
+            _ => return Err(invalid("$.members", "expected the members of the struct")),
+        };
+        Ok(Self { inputs, instance })
+    }
+
+    /// Writes the witness of the struct as a witness file.
+    ///
+    /// Fails if the inputs or the members don't match the types of the struct, or if a felt is not
+    /// less than the prime of its field, which is checked like in [`read`](Self::read).
+    pub fn to_json(&self, def: StructDefOpRef) -> Result<String, Error> {
+        let fields = Fields {
+            named: module_fields(&def)?,
+            default: Fields::default_field(),
+        };
+        let abi = CircuitAbi::from_struct(def)?;
+        let layout = Layout::of_struct(def, def.r#type(), &def, &fields)?;
+        let instance = Value::Struct(self.instance.clone());
+        Ok(Json::object([
+            ("version", Json::from(WITNESS_VERSION)),
+            ("main", Json::from(abi.main.as_str())),
+            (
+                "inputs",
+                write_input_values(&abi, &self.inputs, "$.inputs", &fields)?,
+            ),
+            ("members", layout.write(Some(&instance), "$.members")?),
+        ])
+        .to_string())
+    }
+}
+
+fn invalid(path: &str, msg: impl Into<String>) -> Error {
+    Error::InvalidWitness {
+        path: path.to_owned(),
+        msg: msg.into(),
+    }
+}
+
+fn expected(what: &str, json: &Json) -> String {
+    format!("expected {what} but got {}", json.kind())
+}
+
+fn join(path: &str, name: &str) -> String {
+    format!("{path}.{name}")
+}
+
+/// Parses a decimal or a `0x`-prefixed hexadecimal number.
+fn parse_felt(text: &str) -> Option<BigUint> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(text.as_bytes(), 10),
+    }
+}
+
+/// The fields that felts are checked against.
+struct Fields {
+    /// Fields that felt types can name.
+    named: HashMap<String, Field>,
+    /// Field of the felt types that don't name one.
+    default: Field,
+}
+
+impl Fields {
+    fn default_field() -> Field {
+        Field::builtin("bn128").expect("bn128 is a builtin field")
+    }
+
+    /// Returns the builtin fields, with the default field of the interpreter.
+    fn builtin() -> Self {
+        Self {
+            named: Field::builtin_names()
+                .filter_map(|name| Some((name.to_owned(), Field::builtin(name)?)))
+                .collect(),
+            default: Self::default_field(),
+        }
+    }
+
+    /// Returns the field of a felt type with the given field name, or `None` if the field is not
+    /// known.
+    fn get(&self, name: Option<&str>) -> Option<Field> {
+        match name {
+            None => Some(self.default.clone()),
+            Some(name) => self.named.get(name).cloned(),
+        }
+    }
+}
+
+fn input_layouts(abi: &CircuitAbi, fields: &Fields) -> Vec<(String, Layout)> {
+    abi.inputs
+        .iter()
+        .map(|input| (input.name.clone(), Layout::of_shape(&input.shape, fields)))
+        .collect()
+}
+
+fn read_input_values(
+    abi: &CircuitAbi,
+    json: &Json,
+    path: &str,
+    fields: &Fields,
+) -> Result<Vec<Value>, Error> {
+    let Json::Object(entries) = json else {
+        return Err(invalid(path, expected("an object with the inputs", json)));
+    };
+    read_fields(&input_layouts(abi, fields), entries, path, "input")?
+        .into_iter()
+        .map(|(name, value)| {
+            value.ok_or_else(|| invalid(&join(path, &name), "inputs must be initialized"))
+        })
+        .collect()
+}
+
+fn write_input_values(
+    abi: &CircuitAbi,
+    inputs: &[Value],
+    path: &str,
+    fields: &Fields,
+) -> Result<Json, Error> {
+    let layouts = input_layouts(abi, fields);
+    if inputs.len() != layouts.len() {
+        return Err(invalid(
+            path,
+            format!("expected {} inputs, found {}", layouts.len(), inputs.len()),
+        ));
+    }
+    let entries = layouts
+        .iter()
+        .zip(inputs)
+        .map(|((name, layout), value)| {
+            Ok((name.clone(), layout.write(Some(value), &join(path, name))?))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(Json::Object(entries))
+}
+
+/// Reads the fields of a pod, a struct or the inputs, which must all be present.
+fn read_fields(
+    layouts: &[(String, Layout)],
+    entries: &[(String, Json)],
+    path: &str,
+    what: &str,
+) -> Result<Vec<(String, Option<Value>)>, Error> {
+    if let Some((key, _)) = entries
+        .iter()
+        .find(|(key, _)| !layouts.iter().any(|(name, _)| name == key))
+    {
+        return Err(invalid(path, format!("unknown {what} '{key}'")));
+    }
+    layouts
+        .iter()
+        .map(|(name, layout)| {
+            let (_, json) = entries
+                .iter()
+                .find(|(key, _)| key == name)
+                .ok_or_else(|| invalid(path, format!("missing {what} '{name}'")))?;
+            Ok((name.clone(), layout.read(json, &join(path, name))?))
+        })
+        .collect()
+}
+
+/// Reads the elements of an array in row-major order.
+fn read_elements(
+    dims: &[usize],
+    element: &Layout,
+    json: &Json,
+    path: &str,
+    slots: &mut Vec<Option<Value>>,
+) -> Result<(), Error> {
+    let Some((dim, rest)) = dims.split_first() else {
+        slots.push(element.read(json, path)?);
+        return Ok(());
+    };
+    let Json::Array(items) = json else {
+        return Err(invalid(path, expected("an array", json)));
+    };
+    if items.len() != *dim {
+        return Err(invalid(
+            path,
+            format!("expected {dim} elements, found {}", items.len()),
+        ));
+    }
+    for (index, item) in items.iter().enumerate() {
+        read_elements(rest, element, item, &format!("{path}[{index}]"), slots)?;
+    }
+    Ok(())
+}
+
+/// Nests the elements of an array in row-major order, one level per dimension.
+fn nest(dims: &[usize], items: &[Json]) -> Json {
+    match dims {
+        [_, rest @ ..] if !rest.is_empty() => {
+            let stride = rest.iter().product::<usize>();
+            Json::Array(
+                (0..dims[0])
+                    .map(|index| nest(rest, &items[index * stride..(index + 1) * stride]))
+                    .collect(),
+            )
+        }
+        _ => Json::Array(items.to_vec()),
+    }
+}
+
+/// Returns the path of the element at the flat position of an array.
+fn element_path(path: &str, dims: &[usize], mut position: usize) -> String {
+    let mut indices = vec![0; dims.len()];
+    for (index, dim) in indices.iter_mut().zip(dims).rev() {
+        *index = position % dim;
+        position /= dim;
+    }
+    indices
+        .iter()
+        .fold(path.to_owned(), |path, index| format!("{path}[{index}]"))
+}
+
+/// How the values of a type are represented in the files.
+enum Layout {
+    /// A felt, with the field its values must belong to if it is known.
+    Felt(Option<Field>),
+    Bool,
+    Index,
+    Array {
+        dims: Vec<usize>,
+        element: Box<Layout>,
+    },
+    Pod(Vec<(String, Layout)>),
+    Struct {
+        name: String,
+        members: Vec<(String, Layout)>,
+    },
+}
+
+impl Layout {
+    fn of_shape(shape: &Shape, fields: &Fields) -> Self {
+        match shape {
+            Shape::Felt { field } => Self::Felt(fields.get(field.as_deref())),
+            Shape::Array { dims, element } => Self::Array {
+                dims: dims.clone(),
+                element: Box::new(Self::of_shape(element, fields)),
+            },
+            Shape::Pod { records } => Self::Pod(
+                records
+                    .iter()
+                    .map(|(name, shape)| (name.clone(), Self::of_shape(shape, fields)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the layout of the instances of the struct with the given type, looking up the
+    /// structs of its members from `scope`.
+    fn of_struct<'c: 'a, 'a>(
+        def: StructDefOpRef<'c, '_>,
+        ty: StructType<'c>,
+        scope: &impl OperationLike<'c, 'a>,
+        fields: &Fields,
+    ) -> Result<Self, Error> {
+        let members = def
+            .member_defs()
+            .into_iter()
+            .map(|member| {
+                let name = member.member_name().to_owned();
+                let layout = Self::of_type(
+                    member.member_type(),
+                    scope,
+                    &format!("{}.{name}", ty.name()),
+                    fields,
+                )?;
+                Ok((name, layout))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self::Struct {
+            name: ty.name().to_string(),
+            members,
+        })
+    }
+
+    /// Returns the layout of the type, looking up the structs from `scope`. `name` identifies the
+    /// member of the type in errors.
+    fn of_type<'c: 'a, 'a>(
+        ty: Type<'c>,
+        scope: &impl OperationLike<'c, 'a>,
+        name: &str,
+        fields: &Fields,
+    ) -> Result<Self, Error> {
+        let unsupported = || {
+            invalid(
+                name,
+                format!("values of type {ty} cannot be read or written"),
+            )
+        };
+        if let Ok(felt) = FeltType::try_from(ty) {
+            Ok(Self::Felt(fields.get(felt.field_name().as_deref())))
+        } else if IntegerType::try_from(ty).is_ok_and(|ty| ty.width() == 1) {
+            Ok(Self::Bool)
+        } else if ty.is_index() {
+            Ok(Self::Index)
+        } else if let Ok(array) = ArrayType::try_from(ty) {
+            let dims = array
+                .dims()
+                .into_iter()
+                .map(|dim| {
+                    IntegerAttribute::try_from(dim)
+                        .ok()
+                        .and_then(|dim| usize::try_from(dim.value()).ok())
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(unsupported)?;
+            Ok(Self::Array {
+                dims,
+                element: Box::new(Self::of_type(array.element_type(), scope, name, fields)?),
+            })
+        } else if let Ok(pod) = PodType::try_from(ty) {
+            let records = pod
+                .records()
+                .into_iter()
+                .map(|record| {
+                    let record_name = record.name().as_string_ref().as_str()?.to_owned();
+                    let layout = Self::of_type(record.r#type(), scope, name, fields)?;
+                    Ok((record_name, layout))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            Ok(Self::Pod(records))
+        } else if let Ok(ty) = StructType::try_from(ty) {
+            let lookup = ty.lookup_definition(scope)?;
+            let op = lookup
+                .operation()
+                .ok_or_else(|| Error::SymbolNotFound(ty.name().to_string()))?;
+            Self::of_struct(StructDefOpRef::try_from(op)?, ty, scope, fields)
+        } else {
+            Err(unsupported())
+        }
+    }
+
+    /// Names the kind of the values of the layout, as [`Value::kind`] does.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Felt(_) => "felt",
+            Self::Bool => "bool",
+            Self::Index => "integer",
+            Self::Array { .. } => "array",
+            Self::Pod(_) => "pod",
+            Self::Struct { .. } => "struct",
+        }
+    }
+
+    /// Describes the JSON values of the layout in errors.
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Felt(_) => "a felt as a decimal or hexadecimal string",
+            Self::Bool => "a boolean",
+            Self::Index => "an index",
+            Self::Array { .. } => "an array",
+            Self::Pod(_) => "an object with the records of a pod",
+            Self::Struct { .. } => "an object with the members of a struct",
+        }
+    }
+
+    fn read(&self, json: &Json, path: &str) -> Result<Option<Value>, Error> {
+        let value = match (self, json) {
+            (_, Json::Null) => return Ok(None),
+            (Self::Felt(field), Json::String(text)) => {
+                let value = parse_felt(text).ok_or_else(|| {
+                    invalid(
+                        path,
+                        format!("'{text}' is not a decimal or hexadecimal number"),
+                    )
+                })?;
+                if let Some(field) = field.as_ref().filter(|field| value >= *field.prime()) {
+                    return Err(invalid(
+                        path,
+                        format!(
+                            "'{text}' is not less than the prime {} of the field",
+                            field.prime()
+                        ),
+                    ));
+                }
+                Value::Felt(value)
+            }
+            (Self::Bool, Json::Bool(value)) => Value::Bool(*value),
+            (Self::Index, Json::Number(text)) => Value::Int(
+                text.parse()
+                    .map_err(|_| invalid(path, format!("{text} is not an index")))?,
+            ),
+            (Self::Array { dims, element }, Json::Array(_)) => {
+                let mut slots = Vec::with_capacity(dims.iter().product());
+                read_elements(dims, element, json, path, &mut slots)?;
+                ArrayValue::from_slots(dims.clone(), slots).into()
+            }
+            (Self::Pod(records), Json::Object(entries)) => {
+                PodValue::new(read_fields(records, entries, path, "record")?).into()
+            }
+            (Self::Struct { name, members }, Json::Object(entries)) => {
+                let values = read_fields(members, entries, path, "member")?;
+                let instance =
+                    StructInstance::new(name.clone(), values.iter().map(|(name, _)| name.clone()));
+                for (member, value) in values {
+                    if let Some(value) = value {
+                        instance.set_member(&member, value);
+                    }
+                }
+                instance.into()
+            }
+            (layout, json) => return Err(invalid(path, expected(layout.description(), json))),
+        };
+        Ok(Some(value))
+    }
+
+    fn write(&self, value: Option<&Value>, path: &str) -> Result<Json, Error> {
+        let Some(value) = value else {
+            return Ok(Json::Null);
+        };
+        match (self, value) {
+            (Self::Felt(field), Value::Felt(value)) => {
+                if let Some(field) = field.as_ref().filter(|field| value >= field.prime()) {
+                    return Err(invalid(
+                        path,
+                        format!(
+                            "'{value}' is not less than the prime {} of the field",
+                            field.prime()
+                        ),
+                    ));
+                }
+                Ok(Json::String(value.to_string()))
+            }
+            (Self::Bool, Value::Bool(value)) => Ok(Json::Bool(*value)),
+            (Self::Index, Value::Int(value)) => Ok(Json::from(*value)),
+            (Self::Array { dims, element }, Value::Array(array)) => {
+                if array.dims() != *dims {
+                    return Err(invalid(
+                        path,
+                        format!(
+                            "expected an array of dimensions {dims:?}, found {:?}",
+                            array.dims()
+                        ),
+                    ));
+                }
+                let items = array
+                    .elements()
+                    .iter()
+                    .enumerate()
+                    .map(|(position, slot)| {
+                        element.write(slot.as_ref(), &element_path(path, dims, position))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(nest(dims, &items))
+            }
+            (Self::Pod(records), Value::Pod(pod)) => write_fields(
+                records,
+                pod.record_names(),
+                |name| pod.get(name),
+                path,
+                "record",
+            ),
+            (Self::Struct { members, .. }, Value::Struct(instance)) => {
+                let names = instance
+                    .members()
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect();
+                write_fields(members, names, |name| instance.member(name), path, "member")
+            }
+            (layout, value) => Err(invalid(
+                path,
+                format!("expected {} value but got {}", layout.kind(), value.kind()),
+            )),
+        }
+    }
+}
+
+/// Writes the fields of a pod or a struct, which must have the given names.
+fn write_fields(
+    layouts: &[(String, Layout)],
+    names: Vec<String>,
+    value: impl Fn(&str) -> Option<Value>,
+    path: &str,
+    what: &str,
+) -> Result<Json, Error> {
+    if let Some(name) = names
+        .iter()
+        .find(|name| !layouts.iter().any(|(layout, _)| layout == *name))
+    {
+        return Err(invalid(path, format!("unknown {what} '{name}'")));
+    }
+    let entries = layouts
+        .iter()
+        .map(|(name, layout)| {
+            if !names.contains(name) {
+                return Err(invalid(path, format!("missing {what} '{name}'")));
+            }
+            Ok((
+                name.clone(),
+                layout.write(value(name).as_ref(), &join(path, name))?,
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(Json::Object(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::LlzkContext, dialect::module::ModuleExt as _, interpreter::Interpreter};
+    use melior::ir::Module;
+
+    #[test]
+    fn inputs_roundtrip() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("witness/test_files/sub.mlir")).unwrap();
+        let abi = CircuitAbi::from_module(&module).unwrap();
+        let inputs = read_inputs(
+            &abi,
+            r#"{"arg0": "0x10", "arg1": [["1", "2"], ["3", "4"]]}"#,
+        )
+        .unwrap();
+        assert_eq!(inputs[0], Value::felt(16u8));
+        assert_eq!(
+            inputs[1].as_array().unwrap().get(&[1, 0]),
+            Some(Value::felt(3u8))
+        );
+        similar_asserts::assert_eq!(
+            write_inputs(&abi, &inputs).unwrap(),
+            r#"{
+  "arg0": "16",
+  "arg1": [
+    ["1", "2"],
+    ["3", "4"]
+  ]
+}"#
+        );
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("witness/test_files/sub.mlir")).unwrap();
+        let abi = CircuitAbi::from_module(&module).unwrap();
+        let err = |json: &str| read_inputs(&abi, json).unwrap_err().to_string();
+        assert_eq!(
+            err(r#"{"arg0": "1", "arg1": [["1", "2"], ["3"]]}"#),
+            "invalid value at '$.arg1[1]': expected 2 elements, found 1"
+        );
+        assert_eq!(
+            err(r#"{"arg0": 1, "arg1": [["1", "2"], ["3", "4"]]}"#),
+            "invalid value at '$.arg0': expected a felt as a decimal or hexadecimal string but got \
+             a number"
+        );
+        assert_eq!(
+            err(r#"{"arg0": "one", "arg1": [["1", "2"], ["3", "4"]]}"#),
+            "invalid value at '$.arg0': 'one' is not a decimal or hexadecimal number"
+        );
+        let prime = Field::builtin("bn128").unwrap().prime().to_string();
+        assert_eq!(
+            err(&format!(
+                r#"{{"arg0": "{prime}", "arg1": [["1", "2"], ["3", "4"]]}}"#
+            )),
+            format!(
+                "invalid value at '$.arg0': '{prime}' is not less than the prime {prime} of the \
+                 field"
+            )
+        );
+        assert_eq!(
+            err(r#"{"arg1": [["1", "2"], ["3", "4"]]}"#),
+            "invalid value at '$': missing input 'arg0'"
+        );
+        assert_eq!(
+            err(r#"{"arg0": "1", "arg1": [["1", "2"], ["3", "4"]], "arg2": "1"}"#),
+            "invalid value at '$': unknown input 'arg2'"
+        );
+    }
+
+    #[test]
+    fn witness_roundtrip() {
+        let ctx = LlzkContext::new();
+        let module = Module::parse(&ctx, include_str!("witness/test_files/sub.mlir")).unwrap();
+        let lookup = module.main_struct_def().unwrap().unwrap();
+        let def = StructDefOpRef::try_from(lookup.operation().unwrap()).unwrap();
+        let abi = CircuitAbi::from_struct(def).unwrap();
+        let inputs =
+            read_inputs(&abi, r#"{"arg0": "5", "arg1": [["1", "2"], ["3", "4"]]}"#).unwrap();
+        let instance = Interpreter::new().compute(&def, &inputs).unwrap();
+        let witness = Witness { inputs, instance };
+
+        let json = witness.to_json(def).unwrap();
+        similar_asserts::assert_eq!(json, include_str!("witness/test_files/sub.json"));
+        let read = Witness::read(def, &json).unwrap();
+        assert_eq!(read.to_json(def).unwrap(), json);
+        assert!(
+            Interpreter::new()
+                .check_constraints(&def, &read.instance, &read.inputs)
+                .unwrap()
+                .is_empty()
+        );
+
+        let err = Witness::read(def, &json.replace("\"x\": \"5\"", "\"x\": [\"5\"]")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value at '$.members.sub.x': expected a felt as a decimal or hexadecimal \
+             string but got an array"
+        );
+
+        // Felts must be less than the prime of the default field of the interpreter, or of the
+        // one that is given.
+        let prime = Field::builtin("bn128").unwrap().prime().clone();
+        let json = json.replace("\"x\": \"5\"", &format!("\"x\": \"{prime}\""));
+        let err = Witness::read(def, &json).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invalid value at '$.members.sub.x': '{prime}' is not less than the prime {prime} \
+                 of the field"
+            )
+        );
+        let read = Witness::read_with_default_field(def, &json, Field::new(&prime + 2u8)).unwrap();
+        let sub = read.instance.member("sub").unwrap();
+        assert_eq!(
+            sub.as_struct().unwrap().member("x"),
+            Some(Value::Felt(prime.clone()))
+        );
+        // Writing checks the felts in the same way, so the file could not be read back.
+        let err = read.to_json(def).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invalid value at '$.members.sub.x': '{prime}' is not less than the prime {prime} \
+                 of the field"
+            )
+        );
+    }
+}
@@ -0,0 +1,40 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Sub {
+    struct.member @x : !felt.type
+    struct.member @valid : i1
+    function.def @compute(%x: !felt.type) -> !struct.type<@Sub> {
+      %self = struct.new : <@Sub>
+      struct.writem %self[@x] = %x : <@Sub>, !felt.type
+      %true = arith.constant true
+      struct.writem %self[@valid] = %true : <@Sub>, i1
+      function.return %self : !struct.type<@Sub>
+    }
+    function.def @constrain(%self: !struct.type<@Sub>, %x: !felt.type) {
+      %member = struct.readm %self[@x] : <@Sub>, !felt.type
+      constrain.eq %member, %x : !felt.type, !felt.type
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    struct.member @sub : !struct.type<@Sub>
+    struct.member @rows : !array.type<2,2 x !felt.type>
+    function.def @compute(%a: !felt.type, %b: !array.type<2,2 x !felt.type>) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %c = felt.mul %a, %a : !felt.type, !felt.type
+      struct.writem %self[@c] = %c : <@Main>, !felt.type
+      %sub = function.call @Sub::@compute(%a) : (!felt.type) -> !struct.type<@Sub>
+      struct.writem %self[@sub] = %sub : <@Main>, !struct.type<@Sub>
+      struct.writem %self[@rows] = %b : <@Main>, !array.type<2,2 x !felt.type>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type, %b: !array.type<2,2 x !felt.type>) {
+      %c = struct.readm %self[@c] : <@Main>, !felt.type
+      %square = felt.mul %a, %a : !felt.type, !felt.type
+      constrain.eq %c, %square : !felt.type, !felt.type
+      %sub = struct.readm %self[@sub] : <@Main>, !struct.type<@Sub>
+      function.call @Sub::@constrain(%sub, %a) : (!struct.type<@Sub>, !felt.type) -> ()
+      function.return
+    }
+  }
+}
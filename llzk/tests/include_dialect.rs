@@ -0,0 +1,25 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the include dialect.
+
+use llzk::builder::OpBuilder;
+use llzk::dialect::include::IncludeOpLike as _;
+use llzk::prelude::*;
+
+mod common;
+
+#[test]
+fn create_include() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let op =
+        dialect::include::from(&builder, Location::unknown(&context), "lib", "lib.llzk").unwrap();
+
+    assert!(dialect::include::is_include_op(&op));
+    assert_eq!(op.sym_name(), "lib");
+    assert_eq!(op.path(), "lib.llzk");
+    let ir = format!("{op}");
+    let expected = r#"include.from "lib.llzk" as @lib"#;
+    assert_eq!(ir, expected);
+}
@@ -0,0 +1,768 @@
+#![allow(unused_crate_dependencies)]
+#![cfg(feature = "bigint")]
+//! Integration tests for the reference interpreter.
+
+use llzk::{
+    builder::{OpBuilder, OpBuilderLike},
+    dialect::array::ArrayCtor,
+    interpreter::{Field, Interpreter, Value},
+    prelude::{
+        Value as IrValue,
+        melior_dialects::{arith, scf},
+        *,
+    },
+};
+use num_bigint::BigUint;
+
+mod common;
+
+/// Creates a struct with a single output `c` that computes `c = a / b` and constrains
+/// `c * b = a`.
+fn division_struct<'c, 'm>(
+    context: &'c Context,
+    module: &'m Module<'c>,
+    felt_type: FeltType<'c>,
+) -> StructDefOpRef<'c, 'm> {
+    let location = Location::unknown(context);
+    let builder = OpBuilder::at_block_begin(context, module.body());
+    let struct_type = StructType::from_str(context, "Div");
+    let inputs = vec![(felt_type.into(), location); 2];
+
+    dialect::r#struct::def(
+        &builder,
+        location,
+        "Div",
+        |builder| -> Result<(), LlzkError> {
+            dialect::r#struct::member(builder, location, "c", felt_type, true, false, true)?;
+            {
+                let _guard = builder.insertion_guard();
+                let compute = dialect::r#struct::helpers::compute_fn(
+                    builder,
+                    location,
+                    struct_type,
+                    &inputs,
+                    None,
+                )?;
+                let block = compute.body()?.first_block().unwrap();
+                let (a, b) = (block.argument(0)?, block.argument(1)?);
+                let self_value = block.first_operation().unwrap().result(0)?;
+                builder.set_insertion_point(block.terminator().unwrap());
+                let c = dialect::felt::div(builder, location, a.into(), b.into())?.result(0)?;
+                dialect::r#struct::writem(builder, location, self_value.into(), "c", c.into())?;
+            }
+            {
+                let _guard = builder.insertion_guard();
+                let constrain = dialect::r#struct::helpers::constrain_fn(
+                    builder,
+                    location,
+                    struct_type,
+                    &inputs,
+                    None,
+                )?;
+                let block = constrain.body()?.first_block().unwrap();
+                let (self_value, a, b) =
+                    (block.argument(0)?, block.argument(1)?, block.argument(2)?);
+                builder.set_insertion_point(block.terminator().unwrap());
+                let c = dialect::r#struct::readm(
+                    builder,
+                    location,
+                    felt_type.into(),
+                    self_value.into(),
+                    "c",
+                )?
+                .result(0)?;
+                let t = dialect::felt::mul(builder, location, c.into(), b.into())?.result(0)?;
+                dialect::constrain::eq(builder, location, t.into(), a.into());
+            }
+            Ok(())
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn compute_division() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(&context, &module, FeltType::new(&context));
+    verify_operation_with_diags(&module.as_operation()).unwrap();
+
+    let instance = Interpreter::new()
+        .compute(&div, &[Value::felt(6u8), Value::felt(3u8)])
+        .unwrap();
+    assert_eq!(instance.name(), "@Div");
+    assert_eq!(instance.member("c"), Some(Value::felt(2u8)));
+}
+
+#[test]
+fn compute_division_in_named_field() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(
+        &context,
+        &module,
+        FeltType::with_field(&context, "koalabear"),
+    );
+
+    let instance = Interpreter::new()
+        .compute(&div, &[Value::felt(1u8), Value::felt(2u8)])
+        .unwrap();
+    // (p + 1) / 2 is the inverse of 2 in the KoalaBear field.
+    assert_eq!(instance.member("c"), Some(Value::felt(1065353217u32)));
+}
+
+#[test]
+fn compute_division_in_default_field() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(&context, &module, FeltType::new(&context));
+
+    let instance = Interpreter::new()
+        .with_default_field(Field::new(BigUint::from(7u8)))
+        .compute(&div, &[Value::felt(1u8), Value::felt(2u8)])
+        .unwrap();
+    assert_eq!(instance.member("c"), Some(Value::felt(4u8)));
+}
+
+#[test]
+fn compute_division_by_zero() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(&context, &module, FeltType::new(&context));
+
+    let err = Interpreter::new()
+        .compute(&div, &[Value::felt(1u8), Value::felt(0u8)])
+        .unwrap_err();
+    assert!(
+        matches!(&err, LlzkError::EvaluationFailed { msg, .. } if msg == "division by zero"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn check_constraints_on_computed_witness() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(&context, &module, FeltType::new(&context));
+    let inputs = [Value::felt(6u8), Value::felt(3u8)];
+
+    let interpreter = Interpreter::new();
+    let instance = interpreter.compute(&div, &inputs).unwrap();
+    let violations = check_constraints(&div, &instance, &inputs).unwrap();
+    assert_eq!(violations, vec![]);
+}
+
+#[test]
+fn check_constraints_reports_violation() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let div = division_struct(&context, &module, FeltType::new(&context));
+    let inputs = [Value::felt(6u8), Value::felt(3u8)];
+
+    let instance = Interpreter::new().compute(&div, &inputs).unwrap();
+    instance.set_member("c", Value::felt(5u8));
+    let violations = check_constraints(&div, &instance, &inputs).unwrap();
+    assert_eq!(violations.len(), 1);
+    let violation = &violations[0];
+    assert_eq!(violation.op, "constrain.eq");
+    assert_eq!(violation.location, Location::unknown(&context).to_string());
+    assert_eq!(
+        violation.operands,
+        vec![Value::felt(15u8), Value::felt(6u8)]
+    );
+    assert!(violation.member_path.is_empty());
+}
+
+/// `@Main` checks one element of an array of `@Bit` subcomponents held by its `@pair` member, and
+/// asserts that its first input is less than the second one.
+const NESTED_MODULE: &str = r#"module attributes {llzk.lang} {
+  struct.def @Bit {
+    struct.member @b : !felt.type {llzk.pub}
+    function.def @compute(%x: !felt.type) -> !struct.type<@Bit> {
+      %self = struct.new : <@Bit>
+      struct.writem %self[@b] = %x : <@Bit>, !felt.type
+      function.return %self : !struct.type<@Bit>
+    }
+    function.def @constrain(%self: !struct.type<@Bit>, %x: !felt.type) {
+      %b = struct.readm %self[@b] : <@Bit>, !felt.type
+      %zero = felt.const 0
+      %one = felt.const 1
+      %bits = array.new %zero, %one : <2 x !felt.type>
+      function.return
+    }
+  }
+  struct.def @Pair {
+    struct.member @bits : !array.type<2 x !struct.type<@Bit>> {llzk.pub}
+    function.def @compute(%x: !felt.type, %y: !felt.type) -> !struct.type<@Pair> {
+      %self = struct.new : <@Pair>
+      %low = function.call @Bit::@compute(%x) : (!felt.type) -> !struct.type<@Bit>
+      %high = function.call @Bit::@compute(%y) : (!felt.type) -> !struct.type<@Bit>
+      %bits = array.new %low, %high : <2 x !struct.type<@Bit>>
+      struct.writem %self[@bits] = %bits : <@Pair>, !array.type<2 x !struct.type<@Bit>>
+      function.return %self : !struct.type<@Pair>
+    }
+    function.def @constrain(%self: !struct.type<@Pair>, %x: !felt.type, %y: !felt.type) {
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @pair : !struct.type<@Pair>
+    function.def @compute(%x: !felt.type, %y: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %pair = function.call @Pair::@compute(%x, %y) : (!felt.type, !felt.type) -> !struct.type<@Pair>
+      struct.writem %self[@pair] = %pair : <@Main>, !struct.type<@Pair>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type, %y: !felt.type) {
+      %pair = struct.readm %self[@pair] : <@Main>, !struct.type<@Pair>
+      %bits = struct.readm %pair[@bits] : <@Pair>, !array.type<2 x !struct.type<@Bit>>
+      %c1 = arith.constant 1 : index
+      %high = array.read %bits[%c1] : <2 x !struct.type<@Bit>>, !struct.type<@Bit>
+      function.call @Bit::@constrain(%high, %y) : (!struct.type<@Bit>, !felt.type) -> ()
+      %lt = bool.cmp lt(%x, %y) : !felt.type, !felt.type
+      bool.assert %lt
+      function.return
+    }
+  }
+}"#;
+
+#[test]
+fn check_constraints_reports_nested_member_paths() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, NESTED_MODULE).unwrap();
+    let def = |name: &str| {
+        module
+            .structs()
+            .find(|def| def.fully_qualified_name().to_string() == name)
+            .unwrap()
+    };
+    // Constrain the bit to be 0 or 1.
+    {
+        let block = def("@Bit")
+            .constrain_func()
+            .unwrap()
+            .body()
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let ops = block.ops().collect::<Vec<OperationRef>>();
+        let builder = OpBuilder::at_block_end(&context, block);
+        builder.set_insertion_point(block.terminator().unwrap());
+        dialect::constrain::r#in(
+            &builder,
+            Location::unknown(&context),
+            ops[3].result(0).unwrap().into(),
+            ops[0].result(0).unwrap().into(),
+        );
+    }
+    let main = def("@Main");
+    let interpreter = Interpreter::new();
+    let check = |x: u8, y: u8| {
+        let inputs = [Value::felt(x), Value::felt(y)];
+        let instance = interpreter.compute(&main, &inputs).unwrap();
+        interpreter
+            .check_constraints(&main, &instance, &inputs)
+            .unwrap()
+    };
+
+    assert_eq!(check(0, 1), vec![]);
+
+    let violations = check(0, 5);
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0].op, "constrain.in");
+    assert_eq!(violations[0].member_path, ["pair", "bits[1]"]);
+    assert!(
+        violations[0].to_string().contains(" in @pair.@bits[1] "),
+        "{}",
+        violations[0]
+    );
+
+    let violations = check(2, 1);
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0].op, "bool.assert");
+    assert_eq!(violations[0].operands, [Value::Bool(false)]);
+    assert!(violations[0].member_path.is_empty());
+
+    let violations = check(7, 7);
+    let ops = violations
+        .iter()
+        .map(|violation| violation.op.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ops, ["constrain.in", "bool.assert"]);
+}
+
+/// Creates an empty free function with the given signature at the end of the module.
+fn free_function<'c, 'm>(
+    context: &'c Context,
+    module: &'m Module<'c>,
+    name: &str,
+    inputs: &[Type<'c>],
+    results: &[Type<'c>],
+) -> FuncDefOpRef<'c, 'm> {
+    let builder = OpBuilder::at_block_end(context, module.body());
+    dialect::function::def(
+        &builder,
+        Location::unknown(context),
+        name,
+        FunctionType::new(context, inputs, results),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap()
+}
+
+fn index_constant<'c>(
+    builder: &OpBuilder<'c, '_>,
+    location: Location<'c>,
+    value: i64,
+) -> IrValue<'c, 'c> {
+    builder
+        .insert(location, |ctx, location| {
+            arith::constant(
+                ctx,
+                IntegerAttribute::new(Type::index(ctx), value).into(),
+                location,
+            )
+        })
+        .result(0)
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn call_scf_for() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let f = free_function(&context, &module, "f", &[felt_type], &[felt_type]);
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let lb = index_constant(&builder, loc, 0);
+        let ub = index_constant(&builder, loc, 4);
+        let step = index_constant(&builder, loc, 1);
+        let op = scf::for_(&builder, loc, lb, ub, step, &[x], |builder, iv, args| {
+            let i = dialect::cast::tofelt(builder, loc, iv, None).result(0)?;
+            let sum = dialect::felt::add(builder, loc, args[0], x)?.result(0)?;
+            let sum = dialect::felt::add(builder, loc, sum.into(), i.into())?;
+            Ok::<_, LlzkError>(vec![sum.result(0)?.into()])
+        })
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    // 3 + 4 * 3 + (0 + 1 + 2 + 3)
+    let results = Interpreter::new().call(&f, &[Value::felt(3u8)]).unwrap();
+    assert_eq!(results, vec![Value::felt(21u8)]);
+}
+
+#[test]
+fn call_scf_if() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let bool_type: Type = IntegerType::new(&context, 1).into();
+    let f = free_function(
+        &context,
+        &module,
+        "f",
+        &[bool_type, felt_type, felt_type],
+        &[felt_type],
+    );
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let cond: IrValue = block.argument(0).unwrap().into();
+        let a: IrValue = block.argument(1).unwrap().into();
+        let b: IrValue = block.argument(2).unwrap().into();
+        let op = scf::if_(
+            &builder,
+            loc,
+            cond,
+            &[felt_type],
+            |_| Ok::<_, LlzkError>(vec![a]),
+            |builder| {
+                let neg = dialect::felt::neg(builder, loc, b)?;
+                Ok(vec![neg.result(0)?.into()])
+            },
+        )
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    let interpreter = Interpreter::new().with_default_field(Field::new(BigUint::from(7u8)));
+    let call = |cond| {
+        interpreter
+            .call(&f, &[Value::Bool(cond), Value::felt(2u8), Value::felt(4u8)])
+            .unwrap()
+    };
+    assert_eq!(call(true), vec![Value::felt(2u8)]);
+    assert_eq!(call(false), vec![Value::felt(3u8)]);
+}
+
+#[test]
+fn call_scf_while() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let index_type = Type::index(&context);
+    let f = free_function(&context, &module, "f", &[index_type], &[index_type]);
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let n: IrValue = block.argument(0).unwrap().into();
+        let zero = index_constant(&builder, loc, 0);
+        let op = scf::while_(
+            &builder,
+            loc,
+            &[zero],
+            &[index_type],
+            |builder, args| {
+                let cond = builder.insert(loc, |ctx, loc| {
+                    arith::cmpi(ctx, arith::CmpiPredicate::Slt, args[0], n, loc)
+                });
+                Ok::<_, LlzkError>((cond.result(0)?.into(), vec![args[0]]))
+            },
+            |builder, args| {
+                let two = index_constant(builder, loc, 2);
+                let next = builder.insert(loc, |_, loc| arith::addi(args[0], two, loc));
+                Ok(vec![next.result(0)?.into()])
+            },
+        )
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    let interpreter = Interpreter::new();
+    assert_eq!(
+        interpreter.call(&f, &[Value::Int(5)]).unwrap(),
+        vec![Value::Int(6)]
+    );
+    assert_eq!(
+        interpreter.call(&f, &[Value::Int(-1)]).unwrap(),
+        vec![Value::Int(0)]
+    );
+}
+
+#[test]
+fn call_arith() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let index_type = Type::index(&context);
+    let bool_type: Type = IntegerType::new(&context, 1).into();
+    let f = free_function(
+        &context,
+        &module,
+        "f",
+        &[index_type, index_type],
+        &[index_type, bool_type],
+    );
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let a: IrValue = block.argument(0).unwrap().into();
+        let b: IrValue = block.argument(1).unwrap().into();
+        let lt = builder.insert(loc, |ctx, loc| {
+            arith::cmpi(ctx, arith::CmpiPredicate::Slt, a, b, loc)
+        });
+        let lt: IrValue = lt.result(0).unwrap().into();
+        let quotient = builder.insert(loc, |_, loc| arith::divsi(b, a, loc));
+        let quotient: IrValue = quotient.result(0).unwrap().into();
+        let product = builder.insert(loc, |_, loc| arith::muli(a, b, loc));
+        let product: IrValue = product.result(0).unwrap().into();
+        let select = builder.insert(loc, |_, loc| arith::select(lt, quotient, product, loc));
+        dialect::function::r#return(&builder, loc, &[select.result(0).unwrap().into(), lt]);
+    }
+
+    let interpreter = Interpreter::new();
+    assert_eq!(
+        interpreter
+            .call(&f, &[Value::Int(2), Value::Int(7)])
+            .unwrap(),
+        vec![Value::Int(3), Value::Bool(true)]
+    );
+    assert_eq!(
+        interpreter
+            .call(&f, &[Value::Int(7), Value::Int(-2)])
+            .unwrap(),
+        vec![Value::Int(-14), Value::Bool(false)]
+    );
+    let err = interpreter
+        .call(&f, &[Value::Int(0), Value::Int(7)])
+        .unwrap_err();
+    assert!(
+        matches!(&err, LlzkError::EvaluationFailed { msg, .. } if msg == "division by zero"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn call_bool() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let bool_type: Type = IntegerType::new(&context, 1).into();
+    let f = free_function(
+        &context,
+        &module,
+        "f",
+        &[felt_type, felt_type],
+        &[bool_type, bool_type],
+    );
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let a: IrValue = block.argument(0).unwrap().into();
+        let b: IrValue = block.argument(1).unwrap().into();
+        let lt: IrValue = dialect::bool::lt(&builder, loc, a, b)
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into();
+        let eq: IrValue = dialect::bool::eq(&builder, loc, a, b)
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into();
+        let ne: IrValue = dialect::bool::not(&builder, loc, eq)
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into();
+        let and = dialect::bool::and(&builder, loc, lt, ne).unwrap();
+        let or: IrValue = dialect::bool::or(&builder, loc, lt, eq)
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into();
+        let xor = dialect::bool::xor(&builder, loc, or, ne).unwrap();
+        dialect::function::r#return(
+            &builder,
+            loc,
+            &[and.result(0).unwrap().into(), xor.result(0).unwrap().into()],
+        );
+    }
+
+    let interpreter = Interpreter::new().with_default_field(Field::new(BigUint::from(7u8)));
+    let call = |a: u8, b: u8| {
+        interpreter
+            .call(&f, &[Value::felt(a), Value::felt(b)])
+            .unwrap()
+    };
+    assert_eq!(call(2, 5), vec![Value::Bool(true), Value::Bool(false)]);
+    // Felts are compared after reducing them.
+    assert_eq!(call(8, 5), vec![Value::Bool(true), Value::Bool(false)]);
+    assert_eq!(call(12, 5), vec![Value::Bool(false), Value::Bool(true)]);
+    assert_eq!(call(6, 5), vec![Value::Bool(false), Value::Bool(true)]);
+}
+
+#[test]
+fn call_array() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let index_type = Type::index(&context);
+    let array_type = ArrayType::new_with_dims(felt_type, &[2]);
+    let f = free_function(
+        &context,
+        &module,
+        "f",
+        &[felt_type, index_type],
+        &[felt_type, index_type],
+    );
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let i: IrValue = block.argument(1).unwrap().into();
+        let one =
+            dialect::felt::constant(&builder, loc, FeltConstAttribute::new(&context, 1, None))
+                .unwrap()
+                .result(0)
+                .unwrap()
+                .into();
+        let array: IrValue =
+            dialect::array::new(&builder, loc, array_type, ArrayCtor::Values(&[x, one]))
+                .result(0)
+                .unwrap()
+                .into();
+        let square = dialect::felt::mul(&builder, loc, x, x).unwrap();
+        dialect::array::write(&builder, loc, array, &[i], square.result(0).unwrap().into());
+        let c0 = index_constant(&builder, loc, 0);
+        let c1 = index_constant(&builder, loc, 1);
+        let first = dialect::array::read(&builder, loc, felt_type, array, &[c0]);
+        let second = dialect::array::read(&builder, loc, felt_type, array, &[c1]);
+        let sum = dialect::felt::add(
+            &builder,
+            loc,
+            first.result(0).unwrap().into(),
+            second.result(0).unwrap().into(),
+        )
+        .unwrap();
+        let len = dialect::array::len(&builder, loc, array, c0);
+        dialect::function::r#return(
+            &builder,
+            loc,
+            &[sum.result(0).unwrap().into(), len.result(0).unwrap().into()],
+        );
+    }
+
+    let interpreter = Interpreter::new();
+    let call = |i: i64| interpreter.call(&f, &[Value::felt(3u8), Value::Int(i)]);
+    assert_eq!(call(0).unwrap(), vec![Value::felt(10u8), Value::Int(2)]);
+    assert_eq!(call(1).unwrap(), vec![Value::felt(12u8), Value::Int(2)]);
+    let err = call(2).unwrap_err();
+    assert!(
+        matches!(&err, LlzkError::EvaluationFailed { msg, .. } if msg == "index [2] out of bounds"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn call_pod() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let pod_type = PodType::new(
+        &context,
+        &[
+            PodRecordAttribute::new("a", felt_type),
+            PodRecordAttribute::new("b", felt_type),
+        ],
+    );
+    let f = free_function(&context, &module, "f", &[felt_type], &[felt_type]);
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let pod: IrValue = dialect::pod::new(
+            &builder,
+            loc,
+            &[RecordValue::new(StringRef::new("a"), x)],
+            Some(pod_type),
+        )
+        .result(0)
+        .unwrap()
+        .into();
+        let square = dialect::felt::mul(&builder, loc, x, x).unwrap();
+        dialect::pod::write(&builder, loc, pod, "b", square.result(0).unwrap().into());
+        let a = dialect::pod::read(&builder, loc, pod, "a", felt_type);
+        let b = dialect::pod::read(&builder, loc, pod, "b", felt_type);
+        let diff = dialect::felt::sub(
+            &builder,
+            loc,
+            b.result(0).unwrap().into(),
+            a.result(0).unwrap().into(),
+        )
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[diff.result(0).unwrap().into()]);
+    }
+
+    let results = Interpreter::new().call(&f, &[Value::felt(3u8)]).unwrap();
+    assert_eq!(results, vec![Value::felt(6u8)]);
+}
+
+#[test]
+fn call_function() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let double = free_function(&context, &module, "double", &[felt_type], &[felt_type]);
+    {
+        let block = double.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let sum = dialect::felt::add(&builder, loc, x, x).unwrap();
+        dialect::function::r#return(&builder, loc, &[sum.result(0).unwrap().into()]);
+    }
+    let quadruple = free_function(&context, &module, "quadruple", &[felt_type], &[felt_type]);
+    {
+        let block = quadruple.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let callee = SymbolRefAttribute::new_from_str(&context, "double", &[]);
+        let twice = dialect::function::call(&builder, loc, callee, &[x], &[felt_type]).unwrap();
+        let twice = dialect::function::call(
+            &builder,
+            loc,
+            callee,
+            &[twice.result(0).unwrap().into()],
+            &[felt_type],
+        )
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[twice.result(0).unwrap().into()]);
+    }
+
+    let results = Interpreter::new()
+        .call(&quadruple, &[Value::felt(3u8)])
+        .unwrap();
+    assert_eq!(results, vec![Value::felt(12u8)]);
+}
+
+#[test]
+fn call_shifts() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let f = free_function(
+        &context,
+        &module,
+        "f",
+        &[felt_type, felt_type],
+        &[felt_type, felt_type],
+    );
+    {
+        let block = f.body().unwrap().first_block().unwrap();
+        let builder = OpBuilder::at_block_begin(&context, block);
+        let x: IrValue = block.argument(0).unwrap().into();
+        let n: IrValue = block.argument(1).unwrap().into();
+        let shl = dialect::felt::shl(&builder, loc, x, n).unwrap();
+        let shr = dialect::felt::shr(&builder, loc, x, n).unwrap();
+        dialect::function::r#return(
+            &builder,
+            loc,
+            &[shl.result(0).unwrap().into(), shr.result(0).unwrap().into()],
+        );
+    }
+
+    let interpreter = Interpreter::new().with_default_field(Field::builtin("babybear").unwrap());
+    let call = |x: u8, n: u8| {
+        interpreter
+            .call(&f, &[Value::felt(x), Value::felt(n)])
+            .unwrap()
+    };
+    assert_eq!(call(12, 2), vec![Value::felt(48u8), Value::felt(3u8)]);
+    // 2^31 = 2^27 - 1 modulo 15 * 2^27 + 1, and shifting right by the bit width or more
+    // yields 0.
+    assert_eq!(
+        call(1, 31),
+        vec![Value::felt((1u32 << 27) - 1), Value::felt(0u8)]
+    );
+    assert_eq!(call(12, 40)[1], Value::felt(0u8));
+}
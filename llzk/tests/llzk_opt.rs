@@ -0,0 +1,199 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the `llzk-opt` binary.
+
+use std::{
+    io::Write as _,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+const MODULE: &str = r#"module attributes {llzk.lang} {
+  struct.def @Main {
+    struct.member @c : !felt.type {llzk.pub}
+    function.def @compute(%a: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@c] = %a : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %a: !felt.type) {
+      function.return
+    }
+  }
+}"#;
+
+fn llzk_opt(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llzk-opt"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn tmp_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn prints_text() {
+    let output = llzk_opt(&[], MODULE.as_bytes());
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("struct.def @Main"));
+    assert!(text.contains("struct.writem"));
+}
+
+#[test]
+fn bytecode_roundtrip() {
+    let input = tmp_file("llzk_opt_roundtrip.mlir");
+    let bytecode = tmp_file("llzk_opt_roundtrip.llzk");
+    std::fs::write(&input, MODULE).unwrap();
+
+    let output = llzk_opt(
+        &[
+            input.to_str().unwrap(),
+            "--emit=bytecode",
+            "-o",
+            bytecode.to_str().unwrap(),
+        ],
+        &[],
+    );
+    assert!(output.status.success());
+    assert!(std::fs::read(&bytecode).unwrap().starts_with(b"ML\xefR"));
+
+    let from_text = llzk_opt(&[input.to_str().unwrap()], &[]);
+    let from_bytecode = llzk_opt(&[bytecode.to_str().unwrap()], &[]);
+    assert!(from_bytecode.status.success());
+    similar_asserts::assert_eq!(
+        String::from_utf8(from_bytecode.stdout).unwrap(),
+        String::from_utf8(from_text.stdout).unwrap()
+    );
+}
+
+#[test]
+fn emits_stats() {
+    let output = llzk_opt(&["--emit", "stats"], MODULE.as_bytes());
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("\"name\": \"@Main\""), "{text}");
+    assert!(text.contains("\"public\": 1"), "{text}");
+}
+
+#[test]
+fn emits_struct_graph() {
+    let output = llzk_opt(&["--emit", "dot"], MODULE.as_bytes());
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.starts_with("digraph structs {"), "{text}");
+    assert!(text.contains("\"@Main\" [label=\"@Main\"];"), "{text}");
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn emits_air() {
+    let input = include_str!("../src/targets/air/test_files/fibonacci.mlir");
+    let output = llzk_opt(&["--emit", "air", "--field", "babybear"], input.as_bytes());
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("\"version\": 2,"), "{text}");
+    assert!(text.contains("\"prime\": \"2013265921\","), "{text}");
+    assert!(text.contains("\"domain\": \"first\","), "{text}");
+    assert!(text.contains("\"domain\": \"last\","), "{text}");
+    assert!(text.contains("\"skip_last\": 1"), "{text}");
+}
+
+#[test]
+fn reports_parse_errors() {
+    let output = llzk_opt(&[], b"module { felt.bogus }");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("error"), "unexpected stderr: {stderr}");
+    assert!(stderr.contains("failed to parse the input"));
+}
+
+#[test]
+fn runs_pass_pipeline() {
+    let input = br#"module attributes {llzk.lang} {
+  poly.template @Wrap {
+    poly.param @N
+    struct.def @Wrap {
+      struct.member @x : !felt.type
+      function.def @compute() -> !struct.type<@Wrap::@Wrap<[@N]>> attributes {function.allow_witness} {
+        %self = struct.new : <@Wrap::@Wrap<[@N]>>
+        function.return %self : !struct.type<@Wrap::@Wrap<[@N]>>
+      }
+      function.def @constrain(%self: !struct.type<@Wrap::@Wrap<[@N]>>) attributes {function.allow_constraint} {
+        function.return
+      }
+    }
+  }
+  struct.def @Main {
+    struct.member @one : !struct.type<@Wrap::@Wrap<[4]>>
+    function.def @compute() -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>) {
+      function.return
+    }
+  }
+}"#;
+
+    let output = llzk_opt(&[], input);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("@Wrap::@Wrap<[4]>"), "{text}");
+
+    // Flattening instantiates the template for the parameter of the member.
+    let output = llzk_opt(&["--pass-pipeline", "llzk-flatten"], input);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "unexpected stderr: {stderr}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("struct.def @Main"), "{text}");
+    assert!(text.contains("struct.member @one"), "{text}");
+    assert!(!text.contains("<[4]>"), "{text}");
+}
+
+#[test]
+fn rejects_unknown_passes() {
+    let output = llzk_opt(&["--pass-pipeline", "no-such-pass"], MODULE.as_bytes());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("invalid pass pipeline"),
+        "unexpected stderr: {stderr}"
+    );
+}
+
+#[test]
+fn rejects_unknown_options() {
+    let output = llzk_opt(&["--bogus"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn resolves_include_dirs() {
+    let lib = tmp_file("llzk_opt_lib");
+    std::fs::create_dir_all(&lib).unwrap();
+    std::fs::write(lib.join("main.mlir"), MODULE).unwrap();
+    let input = br#"module attributes {llzk.lang} {
+  include.from "main.mlir" as @lib
+}"#;
+
+    let output = llzk_opt(&[], input);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("could not find the included file 'main.mlir'"),
+        "unexpected stderr: {stderr}"
+    );
+
+    let output = llzk_opt(&["-I", lib.to_str().unwrap()], input);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("module @lib"));
+    assert!(text.contains("struct.def @Main"));
+}
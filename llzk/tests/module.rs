@@ -0,0 +1,106 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the metadata of LLZK modules.
+
+use llzk::prelude::*;
+
+mod common;
+
+const MODULE: &str = r#"module attributes {llzk.lang = "circom", llzk.main = !struct.type<@Main>} {
+  struct.def @Main {
+    function.def @compute() -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>) {
+      function.return
+    }
+  }
+}"#;
+
+#[test]
+fn read_metadata() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, MODULE).unwrap();
+
+    assert!(module.is_llzk_module());
+    assert_eq!(module.language(), Some("circom"));
+    let main = module.main_struct().unwrap();
+    assert_eq!(main.to_string(), "!struct.type<@Main>");
+    let def = module.main_struct_def().unwrap().unwrap();
+    let def = StructDefOpRef::try_from(def.operation().unwrap()).unwrap();
+    assert_eq!(def.sym_name(), "Main");
+    assert!(module.field_specs().is_empty());
+    validate_llzk_module(&module).unwrap();
+}
+
+#[test]
+fn replace_and_remove_metadata() {
+    common::setup();
+    let context = LlzkContext::new();
+    let foo = FieldSpecAttribute::new(&context, "foo", 4, "7");
+    let bar = FieldSpecAttribute::new(&context, "bar", 5, "13");
+    let mut module = LlzkModuleBuilder::new(&context)
+        .with_language("zirgen")
+        .with_main(StructType::from_str(&context, "Main"))
+        .add_field_spec(foo)
+        .build();
+
+    assert_eq!(module.language(), Some("zirgen"));
+    module.set_language(None);
+    assert!(module.is_llzk_module());
+    assert_eq!(module.language(), None);
+
+    assert_eq!(
+        module
+            .field_specs()
+            .iter()
+            .map(|spec| spec.name())
+            .collect::<Vec<_>>(),
+        ["foo"]
+    );
+    module.set_field_specs(&[bar, foo]);
+    assert_eq!(
+        module
+            .field_specs()
+            .iter()
+            .map(|spec| spec.name())
+            .collect::<Vec<_>>(),
+        ["bar", "foo"]
+    );
+    assert!(module.remove_field_specs());
+    assert!(module.field_specs().is_empty());
+    assert!(!module.remove_field_specs());
+
+    assert!(module.main_struct().is_some());
+    assert!(module.remove_main());
+    assert!(module.main_struct().is_none());
+    assert!(module.main_struct_def().unwrap().is_none());
+    assert!(!module.remove_main());
+}
+
+#[test]
+fn validation_errors() {
+    common::setup();
+    let context = LlzkContext::new();
+    let mut module = Module::new(Location::unknown(&context));
+    let foo = FieldSpecAttribute::new(&context, "foo", 4, "7");
+    module.add_main(StructType::from_str(&context, "Missing"));
+    module.set_field_specs(&[foo, foo]);
+
+    let err = validate_llzk_module(&module).unwrap_err().to_string();
+    assert!(err.contains("missing the 'llzk.lang' attribute"), "{err}");
+    assert!(
+        err.contains("could not find the main struct !struct.type<@Missing>"),
+        "{err}"
+    );
+    assert!(
+        err.contains("the field 'foo' is specified more than once"),
+        "{err}"
+    );
+
+    module.set_language(None);
+    module.remove_main();
+    module.set_field_specs(&[foo]);
+    validate_llzk_module(&module).unwrap();
+}
@@ -0,0 +1,225 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the greedy pattern rewrite driver.
+
+use llzk::builder::{OpBuilder, OpBuilderLike as _};
+use llzk::prelude::*;
+
+mod common;
+
+/// Rewrites `felt.neg (felt.neg x)` into `x`.
+struct DoubleNegation;
+
+impl RewritePattern for DoubleNegation {
+    fn matches(&self, op: OperationRef) -> bool {
+        dialect::felt::is_neg_op(&op)
+    }
+
+    fn rewrite<'c>(
+        &self,
+        op: OperationRef<'c, '_>,
+        rewriter: &mut Rewriter<'c>,
+    ) -> Result<bool, LlzkError> {
+        let Ok(inner) = OperationResult::try_from(op.operand(0)?) else {
+            return Ok(false);
+        };
+        let inner = inner.owner();
+        if !dialect::felt::is_neg_op(&inner) {
+            return Ok(false);
+        }
+        rewriter.replace_op(op, &[inner.operand(0)?])?;
+        Ok(true)
+    }
+}
+
+/// Erases `felt.neg` ops whose result is not used.
+struct UnusedNegation;
+
+impl RewritePattern for UnusedNegation {
+    fn matches(&self, op: OperationRef) -> bool {
+        dialect::felt::is_neg_op(&op)
+    }
+
+    fn rewrite<'c>(
+        &self,
+        op: OperationRef<'c, '_>,
+        rewriter: &mut Rewriter<'c>,
+    ) -> Result<bool, LlzkError> {
+        if llzk::value_ext::has_uses(op.result(0)?) {
+            return Ok(false);
+        }
+        rewriter.erase_op(op)?;
+        Ok(true)
+    }
+}
+
+/// Rewrites `felt.sub a, b` into `felt.add a, (felt.neg b)`.
+struct SubToAdd;
+
+impl RewritePattern for SubToAdd {
+    fn matches(&self, op: OperationRef) -> bool {
+        dialect::felt::is_sub_op(&op)
+    }
+
+    fn rewrite<'c>(
+        &self,
+        op: OperationRef<'c, '_>,
+        rewriter: &mut Rewriter<'c>,
+    ) -> Result<bool, LlzkError> {
+        let loc = op.location();
+        let neg = dialect::felt::neg(&*rewriter, loc, op.operand(1)?)?;
+        let add = dialect::felt::add(&*rewriter, loc, op.operand(0)?, neg.result(0)?.into())?;
+        rewriter.replace_op_with(op, add)?;
+        Ok(true)
+    }
+}
+
+fn felt_function<'c>(
+    context: &'c LlzkContext,
+    module: &Module<'c>,
+    name: &str,
+    body: impl FnOnce(&OpBuilder<'c, '_>, Value<'c, 'c>, Value<'c, 'c>) -> Value<'c, 'c>,
+) -> FuncDefOpRef<'c, 'c> {
+    let loc = Location::unknown(context);
+    let felt_type: Type = FeltType::new(context).into();
+    let builder = OpBuilder::at_block_begin(context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        name,
+        FunctionType::new(context, &[felt_type, felt_type], &[felt_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    let block = f
+        .body()
+        .expect("function.def must have body region")
+        .first_block()
+        .expect("function.def must have entry block");
+    builder.set_insertion_point_at_start(block);
+    let out = body(
+        &builder,
+        block.argument(0).unwrap().into(),
+        block.argument(1).unwrap().into(),
+    );
+    dialect::function::r#return(&builder, loc, &[out]);
+    f
+}
+
+#[test]
+fn removes_double_negations() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let f = felt_function(&context, &module, "f_neg", |builder, a, _| {
+        let mut value = a;
+        for _ in 0..4 {
+            value = dialect::felt::neg(builder, loc, value)
+                .unwrap()
+                .result(0)
+                .unwrap()
+                .into();
+        }
+        value
+    });
+
+    let mut patterns = PatternSet::new();
+    patterns.add(DoubleNegation).add(UnusedNegation);
+    assert!(apply_patterns_greedily(&f, &patterns).unwrap());
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    let expected = r"function.def @f_neg(%arg0: !felt.type, %arg1: !felt.type) -> !felt.type {
+  function.return %arg0 : !felt.type
+}";
+    assert_eq!(ir, expected);
+}
+
+#[test]
+fn new_ops_are_rewritten() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let f = felt_function(&context, &module, "f_sub", |builder, a, b| {
+        let neg = dialect::felt::neg(builder, loc, b).unwrap();
+        dialect::felt::sub(builder, loc, a, neg.result(0).unwrap().into())
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into()
+    });
+
+    let mut patterns = PatternSet::new();
+    patterns
+        .add(SubToAdd)
+        .add(DoubleNegation)
+        .add(UnusedNegation);
+    assert!(apply_patterns_greedily(&f, &patterns).unwrap());
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    let expected = r"function.def @f_sub(%arg0: !felt.type, %arg1: !felt.type) -> !felt.type {
+  %0 = felt.add %arg0, %arg1 : !felt.type, !felt.type
+  function.return %0 : !felt.type
+}";
+    assert_eq!(ir, expected);
+}
+
+#[test]
+fn no_match_leaves_ir_unchanged() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let f = felt_function(&context, &module, "f_add", |builder, a, b| {
+        dialect::felt::add(builder, loc, a, b)
+            .unwrap()
+            .result(0)
+            .unwrap()
+            .into()
+    });
+
+    let mut patterns = PatternSet::new();
+    patterns.add(DoubleNegation);
+    assert!(!apply_patterns_greedily(&f, &patterns).unwrap());
+}
+
+#[test]
+fn ops_outside_of_the_root_are_not_rewritten() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(
+        &context,
+        r#"module attributes {llzk.lang} {
+  function.def @f_if(%c: i1, %a: !felt.type) -> !felt.type {
+    %n = felt.neg %a : !felt.type
+    %0 = scf.if %c -> (!felt.type) {
+      %m = felt.neg %n : !felt.type
+      scf.yield %m : !felt.type
+    } else {
+      scf.yield %a : !felt.type
+    }
+    function.return %0 : !felt.type
+  }
+}"#,
+    )
+    .unwrap();
+    let r#if = module
+        .as_operation()
+        .descendants::<OperationRef>()
+        .find(|op| llzk::operation::isa(op, "scf.if"))
+        .unwrap();
+
+    let mut patterns = PatternSet::new();
+    patterns.add(DoubleNegation).add(UnusedNegation);
+    assert!(apply_patterns_greedily(&r#if, &patterns).unwrap());
+
+    // The negation outside of the `scf.if` becomes unused but is not erased.
+    assert!(module.as_operation().verify());
+    let ir = format!("{}", module.as_operation());
+    assert!(ir.contains("felt.neg %arg1"), "{ir}");
+    assert!(ir.contains("scf.yield %arg1"), "{ir}");
+}
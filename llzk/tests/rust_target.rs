@@ -0,0 +1,90 @@
+#![allow(unused_crate_dependencies)]
+#![cfg(feature = "bigint")]
+//! Integration tests for the witness generators emitted by the Rust target.
+//!
+//! `witness/generated.rs` is the output of the target for `witness/witness.mlir`, without the
+//! runtime helpers. The test crate compiles it against a test field, so that the witnesses it
+//! computes can be compared with the ones of the interpreter.
+
+use ff::PrimeField as _;
+use llzk::{
+    interpreter::{Field, Interpreter, Value},
+    prelude::*,
+    targets,
+};
+use num_bigint::BigUint;
+
+mod common;
+
+#[derive(ff::PrimeField)]
+#[PrimeFieldModulus = "2013265921"]
+#[PrimeFieldGenerator = "31"]
+#[PrimeFieldReprEndianness = "little"]
+struct BabyBear([u64; 1]);
+
+#[allow(dead_code, clippy::redundant_clone)]
+mod generated {
+    include!("witness/generated.rs");
+    include!("../src/targets/rust/runtime.rs");
+}
+
+const RUNTIME: &str = include_str!("../src/targets/rust/runtime.rs");
+
+fn felt(value: &BabyBear) -> Value {
+    Value::felt(BigUint::from_bytes_le(value.to_repr().as_ref()))
+}
+
+#[test]
+fn generated_source_is_up_to_date() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, include_str!("witness/witness.mlir")).unwrap();
+    let source = targets::rust::translate_module(&module, "super::BabyBear").unwrap();
+    similar_asserts::assert_eq!(
+        source,
+        format!("{}\n{RUNTIME}", include_str!("witness/generated.rs"))
+    );
+}
+
+#[test]
+fn generated_witness_matches_interpreter() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, include_str!("witness/witness.mlir")).unwrap();
+    let main = module
+        .structs()
+        .find(|def| def.fully_qualified_name().to_string() == "@Main")
+        .unwrap();
+    let interpreter = Interpreter::new().with_default_field(Field::builtin("babybear").unwrap());
+
+    let values = [0, 1, 2, 5, 13, 30, 31, 40, 1 << 30, 2013265920u64].map(BabyBear::from);
+    for x in &values {
+        for y in &values {
+            let expected = interpreter.compute(&main, &[felt(x), felt(y)]);
+            let actual = generated::Main::compute(*x, *y);
+            let (expected, actual) = match (expected, actual) {
+                (Ok(expected), Ok(actual)) => (expected, actual),
+                (Err(_), Err(_)) => continue,
+                (expected, actual) => {
+                    panic!("inputs {x:?}, {y:?}: interpreter {expected:?}, generated {actual:?}")
+                }
+            };
+            let inputs = format!("inputs {x:?}, {y:?}");
+            assert_eq!(expected.member("low"), Some(felt(&actual.low)), "{inputs}");
+            assert_eq!(expected.member("shl"), Some(felt(&actual.shl)), "{inputs}");
+            assert_eq!(expected.member("shr"), Some(felt(&actual.shr)), "{inputs}");
+            assert_eq!(
+                expected.member("quot"),
+                Some(felt(&actual.quot)),
+                "{inputs}"
+            );
+            let bits = expected
+                .member("sub")
+                .and_then(|sub| sub.as_struct().and_then(|sub| sub.member("bits")))
+                .and_then(|bits| bits.as_array().map(|bits| bits.elements()))
+                .unwrap();
+            let actual_bits = actual.sub.bits.iter().map(|bit| Some(felt(bit)));
+            assert_eq!(bits, actual_bits.collect::<Vec<_>>(), "{inputs}");
+        }
+    }
+}
@@ -0,0 +1,224 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the scf builders.
+
+use llzk::builder::{OpBuilder, OpBuilderLike as _};
+use llzk::prelude::melior_dialects::{arith, scf};
+use llzk::prelude::*;
+
+mod common;
+
+fn index_constant<'c>(
+    builder: &OpBuilder<'c, '_>,
+    location: Location<'c>,
+    value: i64,
+) -> Value<'c, 'c> {
+    builder
+        .insert(location, |ctx, location| {
+            arith::constant(
+                ctx,
+                IntegerAttribute::new(Type::index(ctx), value).into(),
+                location,
+            )
+        })
+        .result(0)
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn for_with_iter_args() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_for",
+        FunctionType::new(&context, &[felt_type], &[felt_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let x: Value = block.argument(0).unwrap().into();
+        let lb = index_constant(&builder, loc, 0);
+        let ub = index_constant(&builder, loc, 4);
+        let step = index_constant(&builder, loc, 1);
+        let bounds = LoopBoundsAttribute::new(&context, 0, 4, 1);
+        let op = scf::for_with_bounds(
+            &builder,
+            loc,
+            lb,
+            ub,
+            step,
+            &[x],
+            bounds,
+            |builder, _iv, args| -> Result<_, LlzkError> {
+                let sum = dialect::felt::add(builder, loc, args[0], x)?;
+                Ok(vec![sum.result(0)?.into()])
+            },
+        )
+        .unwrap();
+        assert!(scf::is_for_op(&op));
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    assert!(
+        ir.contains("scf.for %arg1 = %c0 to %c4 step %c1 iter_args(%arg2 = %arg0) -> (!felt.type)")
+    );
+    assert!(ir.contains("felt.add %arg2, %arg0 : !felt.type, !felt.type"));
+    assert!(ir.contains("scf.yield %1 : !felt.type"));
+    assert!(ir.contains("{llzk.loopbounds = #llzk.loopbounds<0 to 4 step 1>}"));
+}
+
+#[test]
+fn if_with_results() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let bool_type: Type = IntegerType::new(&context, 1).into();
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_if",
+        FunctionType::new(&context, &[bool_type, felt_type, felt_type], &[felt_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let cond: Value = block.argument(0).unwrap().into();
+        let a: Value = block.argument(1).unwrap().into();
+        let b: Value = block.argument(2).unwrap().into();
+        let op = scf::if_(
+            &builder,
+            loc,
+            cond,
+            &[felt_type],
+            |_| Ok::<_, LlzkError>(vec![a]),
+            |builder| {
+                let neg = dialect::felt::neg(builder, loc, b)?;
+                Ok(vec![neg.result(0)?.into()])
+            },
+        )
+        .unwrap();
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    let expected = r"function.def @f_if(%arg0: i1, %arg1: !felt.type, %arg2: !felt.type) -> !felt.type {
+  %0 = scf.if %arg0 -> (!felt.type) {
+    scf.yield %arg1 : !felt.type
+  } else {
+    %1 = felt.neg %arg2 : !felt.type
+    scf.yield %1 : !felt.type
+  }
+  function.return %0 : !felt.type
+}";
+    assert_eq!(ir, expected);
+}
+
+#[test]
+fn while_loop() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let index_type = Type::index(&context);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_while",
+        FunctionType::new(&context, &[index_type], &[index_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let n: Value = block.argument(0).unwrap().into();
+        let zero = index_constant(&builder, loc, 0);
+        let op = scf::while_(
+            &builder,
+            loc,
+            &[zero],
+            &[index_type],
+            |builder, args| {
+                let cond = builder.insert(loc, |ctx, loc| {
+                    arith::cmpi(ctx, arith::CmpiPredicate::Slt, args[0], n, loc)
+                });
+                Ok::<_, LlzkError>((cond.result(0)?.into(), vec![args[0]]))
+            },
+            |builder, args| {
+                let one = index_constant(builder, loc, 1);
+                let next = builder.insert(loc, |_, loc| arith::addi(args[0], one, loc));
+                Ok(vec![next.result(0)?.into()])
+            },
+        )
+        .unwrap();
+        assert!(scf::is_while_op(&op));
+        dialect::function::r#return(&builder, loc, &[op.result(0).unwrap().into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    assert!(ir.contains("scf.while (%arg1 = %c0) : (index) -> index"));
+    assert!(ir.contains("scf.condition("));
+    assert!(ir.contains("arith.addi"));
+}
+
+#[test]
+fn failing_body_erases_op() {
+    common::setup();
+    let context = LlzkContext::new();
+    let location = Location::unknown(&context);
+    let module = Module::new(location);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let cond: Value = builder
+        .insert(location, |ctx, location| {
+            arith::constant(
+                ctx,
+                IntegerAttribute::new(IntegerType::new(ctx, 1).into(), 1).into(),
+                location,
+            )
+        })
+        .result(0)
+        .unwrap()
+        .into();
+    let result = scf::if_then(&builder, location, cond, |_| {
+        Err(LlzkError::GeneralError("failed"))
+    });
+    assert!(result.is_err());
+    let first = module.body().first_operation().unwrap();
+    assert!(first.next_in_block().is_none());
+}
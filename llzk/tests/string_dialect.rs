@@ -0,0 +1,30 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the string dialect.
+
+use llzk::prelude::*;
+
+mod common;
+
+#[test]
+fn create_string_type() {
+    common::setup();
+    let context = LlzkContext::new();
+    let ty = StringType::new(&context);
+
+    let ir = format!("{ty}");
+    let expected = "!string.type";
+    assert_eq!(ir, expected);
+}
+
+#[test]
+fn string_type_try_from() {
+    common::setup();
+    let context = LlzkContext::new();
+    let ty: Type = StringType::new(&context).into();
+    assert!(is_string_type(ty));
+    assert!(StringType::try_from(ty).is_ok());
+
+    let index = Type::index(&context);
+    assert!(!is_string_type(index));
+    assert!(StringType::try_from(index).is_err());
+}
@@ -4,9 +4,10 @@
 use llzk::builder::{OpBuilder, OpBuilderLike};
 use llzk::dialect::module::llzk_module;
 use llzk::prelude::{
-    FuncDefOpLike as _, FuncDefOpRef, FunctionType, LlzkContext, Location, Operation, dialect,
+    FlatSymbolRefAttribute, FuncDefOpLike as _, FuncDefOpRef, FunctionType, LlzkContext, Location,
+    Module, Operation, OperationLike as _, SymbolRefAttribute, Type, dialect,
 };
-use llzk::symbol_table;
+use llzk::symbol_table::{self, SymbolTable};
 
 mod common;
 
@@ -47,3 +48,167 @@ fn insert_renames_symbols_on_collision() {
     assert_eq!(format!("{}", first.fully_qualified_name()), "@foo");
     assert_ne!(format!("{}", second.fully_qualified_name()), "@foo");
 }
+
+/// Creates a function named `caller` that calls `callee`.
+fn make_caller<'c, 'a>(
+    builder: &OpBuilder<'c, '_>,
+    context: &'c LlzkContext,
+    location: Location<'c>,
+    callee: &str,
+) -> FuncDefOpRef<'c, 'a> {
+    let caller = make_empty_func(builder, context, location, "caller");
+    let block = caller
+        .body()
+        .expect("function.def must have body region")
+        .first_block()
+        .expect("function.def must have entry block");
+    let _guard = builder.insertion_guard();
+    builder.set_insertion_point_at_start(block);
+    dialect::function::call(
+        builder,
+        location,
+        FlatSymbolRefAttribute::new(context, callee),
+        &[],
+        &[] as &[Type],
+    )
+    .unwrap();
+    dialect::function::r#return(builder, location, &[]);
+    caller
+}
+
+#[test]
+fn lookup_and_uses() {
+    common::setup();
+    let context = LlzkContext::new();
+    let loc = Location::unknown(&context);
+    let module = llzk_module(loc, None);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let foo = make_empty_func(&builder, &context, loc, "foo");
+    builder.set_insertion_point_after(foo);
+    make_caller(&builder, &context, loc, "foo");
+
+    let module_op = module.as_operation();
+    let table = SymbolTable::new(&module_op).unwrap();
+    assert_eq!(table.lookup("foo").unwrap(), *foo);
+    assert!(table.lookup("bar").is_none());
+    let symbol = SymbolRefAttribute::new_from_str(&context, "foo", &[]);
+    assert_eq!(table.lookup_nested(symbol).unwrap(), *foo);
+
+    let uses = table.symbol_uses("foo");
+    assert_eq!(uses.len(), 1);
+    assert_eq!(uses[0].name().as_string_ref().as_str(), Ok("function.call"));
+    assert!(!table.is_symbol_unused("foo"));
+    assert!(table.is_symbol_unused("caller"));
+}
+
+#[test]
+fn rename_updates_uses() {
+    common::setup();
+    let context = LlzkContext::new();
+    let loc = Location::unknown(&context);
+    let module = llzk_module(loc, None);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let foo = make_empty_func(&builder, &context, loc, "foo");
+    builder.set_insertion_point_after(foo);
+    make_caller(&builder, &context, loc, "foo");
+
+    let module_op = module.as_operation();
+    let mut table = SymbolTable::new(&module_op).unwrap();
+    assert!(table.rename("foo", "caller").is_err());
+    table.rename("foo", "bar").unwrap();
+
+    assert!(table.lookup("foo").is_none());
+    assert_eq!(table.lookup("bar").unwrap(), *foo);
+    assert!(table.is_symbol_unused("foo"));
+    assert_eq!(table.symbol_uses("bar").len(), 1);
+    assert!(module_op.verify());
+    let ir = format!("{module_op}");
+    assert!(ir.contains("function.def @bar()"));
+    assert!(ir.contains("function.call @bar()"));
+}
+
+const STRUCTS: &str = r#"module attributes {llzk.lang} {
+  struct.def @Sub {
+    function.def @compute() -> !struct.type<@Sub> {
+      %self = struct.new : <@Sub>
+      function.return %self : !struct.type<@Sub>
+    }
+    function.def @constrain(%self: !struct.type<@Sub>) {
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @sub : !struct.type<@Sub>
+    function.def @compute() -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %sub = function.call @Sub::@compute() : () -> !struct.type<@Sub>
+      struct.writem %self[@sub] = %sub : <@Main>, !struct.type<@Sub>
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>) {
+      %sub = struct.readm %self[@sub] : <@Main>, !struct.type<@Sub>
+      function.call @Sub::@constrain(%sub) : (!struct.type<@Sub>) -> ()
+      function.return
+    }
+  }
+  function.def @wrap(%sub: !struct.type<@Sub>) -> !pod.type<[@sub: !struct.type<@Sub>]> {
+    %pod = pod.new { @sub = %sub } : <[@sub: !struct.type<@Sub>]>
+    function.return %pod : !pod.type<[@sub: !struct.type<@Sub>]>
+  }
+}"#;
+
+#[test]
+fn rename_updates_types() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, STRUCTS).unwrap();
+    let module_op = module.as_operation();
+    let mut table = SymbolTable::new(&module_op).unwrap();
+    table.rename("Sub", "Leaf").unwrap();
+
+    assert!(table.is_symbol_unused("Sub"));
+    assert!(!table.is_symbol_unused("Leaf"));
+    assert!(module_op.verify());
+    let ir = format!("{module_op}");
+    assert!(!ir.contains("@Sub"), "{ir}");
+    // The types of members, functions, block arguments and results are updated, including the
+    // struct types nested in pod types.
+    for expected in [
+        "struct.def @Leaf",
+        "struct.member @sub : !struct.type<@Leaf>",
+        "function.def @compute() -> !struct.type<@Leaf>",
+        "function.call @Leaf::@compute() : () -> !struct.type<@Leaf>",
+        "(!struct.type<@Leaf>) -> ()",
+        "!pod.type<[@sub: !struct.type<@Leaf>]>",
+    ] {
+        assert!(ir.contains(expected), "missing {expected:?} in {ir}");
+    }
+}
+
+#[test]
+fn erase_symbol() {
+    common::setup();
+    let context = LlzkContext::new();
+    let loc = Location::unknown(&context);
+    let module = llzk_module(loc, None);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    make_empty_func(&builder, &context, loc, "foo");
+
+    let module_op = module.as_operation();
+    let mut table = SymbolTable::new(&module_op).unwrap();
+    table.erase("foo").unwrap();
+    assert!(table.lookup("foo").is_none());
+    assert!(table.erase("foo").is_err());
+    assert!(module.body().first_operation().is_none());
+}
+
+#[test]
+fn non_symbol_table_op_is_rejected() {
+    common::setup();
+    let context = LlzkContext::new();
+    let loc = Location::unknown(&context);
+    let module = llzk_module(loc, None);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let foo = make_empty_func(&builder, &context, loc, "foo");
+    assert!(SymbolTable::new(&*foo).is_err());
+}
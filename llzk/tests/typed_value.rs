@@ -0,0 +1,135 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the typed value wrappers.
+
+use llzk::builder::{OpBuilder, OpBuilderLike as _};
+use llzk::prelude::*;
+use llzk::typed_value::{BoolValue, FeltValue, IndexValue};
+
+mod common;
+
+#[test]
+fn felt_expression() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_expr",
+        FunctionType::new(&context, &[felt_type, felt_type, felt_type], &[felt_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let arg =
+            |index| FeltValue::new(&builder, loc, block.argument(index).unwrap().into()).unwrap();
+        let out = arg(0) * arg(1) + arg(2);
+        dialect::function::r#return(&builder, loc, &[out.into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    let expected = r"function.def @f_expr(%arg0: !felt.type, %arg1: !felt.type, %arg2: !felt.type) -> !felt.type {
+  %0 = felt.mul %arg0, %arg1 : !felt.type, !felt.type
+  %1 = felt.add %0, %arg2 : !felt.type, !felt.type
+  function.return %1 : !felt.type
+}";
+    assert_eq!(ir, expected);
+}
+
+#[test]
+fn felt_comparisons() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let felt_type: Type = FeltType::new(&context).into();
+    let bool_type: Type = IntegerType::new(&context, 1).into();
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_cmp",
+        FunctionType::new(&context, &[felt_type, felt_type], &[bool_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    f.set_allow_witness_attr(true);
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let a = FeltValue::new(&builder, loc, block.argument(0).unwrap().into()).unwrap();
+        let b = FeltValue::new(&builder, loc, block.argument(1).unwrap().into()).unwrap();
+        let out = a.lt(b) & !a.eq(b);
+        dialect::function::r#return(&builder, loc, &[out.into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    assert!(ir.contains("bool.cmp lt(%arg0, %arg1) : !felt.type, !felt.type"));
+    assert!(ir.contains("bool.cmp eq(%arg0, %arg1) : !felt.type, !felt.type"));
+    assert!(ir.contains("bool.not"));
+    assert!(ir.contains("bool.and"));
+}
+
+#[test]
+fn index_expression() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = llzk_module(Location::unknown(&context), None);
+    let loc = Location::unknown(&context);
+    let index_type = Type::index(&context);
+    let builder = OpBuilder::at_block_begin(&context, module.body());
+    let f = dialect::function::def(
+        &builder,
+        loc,
+        "f_index",
+        FunctionType::new(&context, &[index_type], &[index_type]),
+        &[],
+        None,
+        llzk::dialect::empty_region,
+    )
+    .unwrap();
+    {
+        let block = f
+            .body()
+            .expect("function.def must have body region")
+            .first_block()
+            .expect("function.def must have entry block");
+        builder.set_insertion_point_at_start(block);
+        let arg = block.argument(0).unwrap().into();
+        // The wrappers only accept values of their type.
+        assert!(FeltValue::new(&builder, loc, arg).is_err());
+        assert!(BoolValue::new(&builder, loc, arg).is_err());
+        let i = IndexValue::new(&builder, loc, arg).unwrap();
+        let out = (i + IndexValue::constant(&builder, loc, 2)) % i;
+        dialect::function::r#return(&builder, loc, &[out.into()]);
+    }
+
+    assert!(f.verify());
+    let ir = format!("{f}");
+    let expected = r"function.def @f_index(%arg0: index) -> index {
+  %c2 = arith.constant 2 : index
+  %0 = arith.addi %arg0, %c2 : index
+  %1 = arith.remsi %0, %arg0 : index
+  function.return %1 : index
+}";
+    assert_eq!(ir, expected);
+}
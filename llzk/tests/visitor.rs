@@ -0,0 +1,202 @@
+#![allow(unused_crate_dependencies)]
+//! Integration tests for the typed traversal of the IR.
+
+use llzk::prelude::*;
+
+mod common;
+
+const MODULE: &str = r#"module attributes {llzk.lang} {
+  module @lib attributes {llzk.lang} {
+    struct.def @Inner {
+      struct.member @x : !felt.type
+      function.def @compute() -> !struct.type<@lib::@Inner> {
+        %self = struct.new : <@lib::@Inner>
+        function.return %self : !struct.type<@lib::@Inner>
+      }
+      function.def @constrain(%self: !struct.type<@lib::@Inner>) {
+        function.return
+      }
+    }
+  }
+  struct.def @Main {
+    struct.member @a : !felt.type {llzk.pub}
+    struct.member @b : !felt.type
+    function.def @compute(%x: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      struct.writem %self[@a] = %x : <@Main>, !felt.type
+      %sq = felt.mul %x, %x : !felt.type, !felt.type
+      struct.writem %self[@b] = %sq : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type) {
+      %a = struct.readm %self[@a] : <@Main>, !felt.type
+      constrain.eq %a, %x : !felt.type, !felt.type
+      %b = struct.readm %self[@b] : <@Main>, !felt.type
+      %sq = felt.mul %x, %x : !felt.type, !felt.type
+      constrain.eq %b, %sq : !felt.type, !felt.type
+      function.return
+    }
+  }
+}"#;
+
+#[test]
+fn classify_ops() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, MODULE).unwrap();
+
+    let ops = module
+        .as_operation()
+        .descendants::<LlzkOpRef>()
+        .collect::<Vec<_>>();
+    assert!(matches!(ops[0], LlzkOpRef::Other(_)));
+    assert!(!ops[0].is_llzk());
+    assert!(
+        matches!(ops[1], LlzkOpRef::StructDef(def) if def.sym_name() == "Inner"),
+        "{}",
+        ops[1]
+    );
+    assert!(matches!(ops[2], LlzkOpRef::MemberDef(member) if member.member_name() == "x"));
+    assert!(matches!(ops[3], LlzkOpRef::FuncDef(_)));
+    assert!(matches!(ops[4], LlzkOpRef::StructNew(_)));
+    assert!(matches!(ops[5], LlzkOpRef::Return(_)));
+    assert_eq!(
+        ops.iter()
+            .filter(|op| matches!(op, LlzkOpRef::ConstrainEq(_)))
+            .count(),
+        2
+    );
+    assert!(ops[1..].iter().all(LlzkOpRef::is_llzk));
+
+    let op = OperationRef::from(ops[4]);
+    assert_eq!(LlzkOpRef::from(op), ops[4]);
+    assert_eq!(ops[4].to_string(), op.to_string());
+}
+
+#[derive(Default)]
+struct Counter {
+    members: Vec<String>,
+    constraints: usize,
+    muls: usize,
+    visited: usize,
+}
+
+impl<'c: 'a, 'a> LlzkVisitor<'c, 'a> for Counter {
+    fn visit(&mut self, op: LlzkOpRef<'c, 'a>) -> WalkResult {
+        self.visited += 1;
+        self.dispatch(op)
+    }
+
+    fn visit_member_def(&mut self, op: MemberDefOpRef<'c, 'a>) -> WalkResult {
+        self.members.push(op.member_name().to_owned());
+        WalkResult::Advance
+    }
+
+    fn visit_func_def(&mut self, op: FuncDefOpRef<'c, 'a>) -> WalkResult {
+        if op.name_is_compute() {
+            WalkResult::Skip
+        } else {
+            WalkResult::Advance
+        }
+    }
+
+    fn visit_constrain_eq(&mut self, _op: OperationRef<'c, 'a>) -> WalkResult {
+        self.constraints += 1;
+        WalkResult::Advance
+    }
+
+    fn visit_felt_mul(&mut self, _op: OperationRef<'c, 'a>) -> WalkResult {
+        self.muls += 1;
+        WalkResult::Advance
+    }
+}
+
+#[test]
+fn visit_ops() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, MODULE).unwrap();
+
+    let mut counter = Counter::default();
+    assert_eq!(counter.walk(&module.as_operation()), WalkResult::Advance);
+    assert_eq!(counter.members, ["x", "a", "b"]);
+    assert_eq!(counter.constraints, 2);
+    // The body of @compute is skipped.
+    assert_eq!(counter.muls, 1);
+    // The root module is visited, but not the 2 and 5 ops of the bodies of @compute.
+    let count = module.as_operation().descendants::<OperationRef>().count();
+    assert_eq!(counter.visited, count + 1 - 7);
+}
+
+struct FirstConstraint<'c, 'a>(Option<OperationRef<'c, 'a>>);
+
+impl<'c: 'a, 'a> LlzkVisitor<'c, 'a> for FirstConstraint<'c, 'a> {
+    fn visit_constrain_eq(&mut self, op: OperationRef<'c, 'a>) -> WalkResult {
+        self.0 = Some(op);
+        WalkResult::Interrupt
+    }
+}
+
+#[test]
+fn interrupt_walk() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, MODULE).unwrap();
+
+    let mut first = FirstConstraint(None);
+    assert_eq!(first.walk(&module.as_operation()), WalkResult::Interrupt);
+    // The second constraint follows the read of @b.
+    let next = first.0.unwrap().next_in_block().unwrap();
+    assert!(matches!(LlzkOpRef::from(next), LlzkOpRef::MemberRead(_)));
+}
+
+#[test]
+fn typed_iterators() {
+    common::setup();
+    let context = LlzkContext::new();
+    let module = Module::parse(&context, MODULE).unwrap();
+
+    let structs = module
+        .structs()
+        .map(|def| def.fully_qualified_name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(structs, ["@lib::@Inner", "@Main"]);
+
+    let top_level = module
+        .body()
+        .ops_of::<StructDefOpRef>()
+        .map(|def| def.sym_name())
+        .collect::<Vec<_>>();
+    assert_eq!(top_level, ["Main"]);
+    assert_eq!(module.body().ops().count(), 2);
+
+    let main = module.structs().last().unwrap();
+    let public = main
+        .descendants::<MemberDefOpRef>()
+        .filter(|member| member.has_public_attr())
+        .map(|member| member.member_name())
+        .collect::<Vec<_>>();
+    assert_eq!(public, ["a"]);
+
+    let funcs = main
+        .children::<FuncDefOpRef>()
+        .map(|func| func.sym_name().unwrap().value())
+        .collect::<Vec<_>>();
+    assert_eq!(funcs, ["compute", "constrain"]);
+    assert_eq!(main.children::<OperationRef>().count(), 4);
+
+    let constrain = main.constrain_func().unwrap();
+    assert_eq!(
+        constrain
+            .descendants::<OperationRef>()
+            .filter(|op| dialect::constrain::is_eq_op(op))
+            .count(),
+        2
+    );
+    assert_eq!(
+        constrain
+            .descendants::<TemplateSymbolBindingOpRef>()
+            .count(),
+        0
+    );
+}
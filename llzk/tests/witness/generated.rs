@@ -0,0 +1,74 @@
+// Witness generators translated from LLZK IR. Do not edit.
+
+/// Field of the felt values.
+pub type Felt = super::BabyBear;
+
+/// Witness of `@Bits`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bits {
+    pub bits: Vec<Felt>,
+}
+
+impl Bits {
+    /// Computes the witness of `@Bits`.
+    #[allow(unused_mut, unused_variables, unreachable_code, clippy::all)]
+    pub fn compute(v0: Felt) -> Result<Self, &'static str> {
+        let mut v1: Bits = Default::default();
+        let mut v2: Vec<Felt> = vec![Default::default(); 4];
+        let mut v3: i64 = 0i64;
+        let mut v4: i64 = 1i64;
+        let mut v5: i64 = 4i64;
+        let mut v6: Felt = Felt::from(1u64);
+        let (mut iv7, end7, step7): (i64, i64, i64) = (v3, v5, v4);
+        if step7 <= 0 { return Err("loop step must be positive"); }
+        while iv7 < end7 {
+            let mut v7: i64 = iv7;
+            let mut v8: Felt = llzk_from_i64::<Felt>(v7);
+            let mut v9: Felt = llzk_shr(&v0, &v8);
+            let mut v10: Felt = llzk_bitwise(&v9, &v6, |a, b| a & b);
+            v2[llzk_index(&[v7], &[4])? * 1] = v10;
+            iv7 = match iv7.checked_add(step7) { Some(next) => next, None => break };
+        }
+        v1.bits = v2.clone();
+        return Ok(v1.clone());
+    }
+}
+
+/// Witness of `@Main`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Main {
+    pub low: Felt,
+    pub shl: Felt,
+    pub shr: Felt,
+    pub quot: Felt,
+    pub sub: Bits,
+}
+
+impl Main {
+    /// Computes the witness of `@Main`.
+    #[allow(unused_mut, unused_variables, unreachable_code, clippy::all)]
+    pub fn compute(v0: Felt, v1: Felt) -> Result<Self, &'static str> {
+        let mut v2: Main = Default::default();
+        let mut v3: Bits = Bits::compute(v0)?;
+        v2.sub = v3.clone();
+        let mut v4: Vec<Felt> = v3.bits.clone();
+        let mut v5: i64 = 0i64;
+        let mut v6: Felt = v4[llzk_index(&[v5], &[4])? * 1].clone();
+        v2.low = v6;
+        let mut v7: Felt = llzk_shl(&v0, &v1);
+        v2.shl = v7;
+        let mut v8: Felt = llzk_shr(&v0, &v1);
+        v2.shr = v8;
+        let mut v9: bool = llzk_cmp(&v0, &v1).is_lt();
+        let mut v10: Felt;
+        if v9 {
+            let mut v11: Felt = llzk_uintdiv(&v1, &v0)?;
+            v10 = v11;
+        } else {
+            let mut v12: Felt = llzk_signed_divmod(&v0, &v1)?.1;
+            v10 = v12;
+        }
+        v2.quot = v10;
+        return Ok(v2.clone());
+    }
+}
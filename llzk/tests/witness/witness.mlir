@@ -0,0 +1,57 @@
+module attributes {llzk.lang, llzk.main = !struct.type<@Main>} {
+  struct.def @Bits {
+    struct.member @bits : !array.type<4 x !felt.type>
+    function.def @compute(%x: !felt.type) -> !struct.type<@Bits> {
+      %self = struct.new : <@Bits>
+      %bits = array.new : <4 x !felt.type>
+      %c0 = arith.constant 0 : index
+      %c1 = arith.constant 1 : index
+      %c4 = arith.constant 4 : index
+      %one = felt.const 1
+      scf.for %i = %c0 to %c4 step %c1 {
+        %amount = cast.tofelt %i : index, !felt.type
+        %shifted = felt.shr %x, %amount : !felt.type, !felt.type
+        %bit = felt.bit_and %shifted, %one : !felt.type, !felt.type
+        array.write %bits[%i] = %bit : <4 x !felt.type>, !felt.type
+      }
+      struct.writem %self[@bits] = %bits : <@Bits>, !array.type<4 x !felt.type>
+      function.return %self : !struct.type<@Bits>
+    }
+    function.def @constrain(%self: !struct.type<@Bits>, %x: !felt.type) {
+      function.return
+    }
+  }
+  struct.def @Main {
+    struct.member @low : !felt.type {llzk.pub}
+    struct.member @shl : !felt.type
+    struct.member @shr : !felt.type
+    struct.member @quot : !felt.type
+    struct.member @sub : !struct.type<@Bits>
+    function.def @compute(%x: !felt.type {llzk.pub}, %y: !felt.type) -> !struct.type<@Main> {
+      %self = struct.new : <@Main>
+      %sub = function.call @Bits::@compute(%x) : (!felt.type) -> !struct.type<@Bits>
+      struct.writem %self[@sub] = %sub : <@Main>, !struct.type<@Bits>
+      %bits = struct.readm %sub[@bits] : <@Bits>, !array.type<4 x !felt.type>
+      %c0 = arith.constant 0 : index
+      %low = array.read %bits[%c0] : <4 x !felt.type>, !felt.type
+      struct.writem %self[@low] = %low : <@Main>, !felt.type
+      %shl = felt.shl %x, %y : !felt.type, !felt.type
+      struct.writem %self[@shl] = %shl : <@Main>, !felt.type
+      %shr = felt.shr %x, %y : !felt.type, !felt.type
+      struct.writem %self[@shr] = %shr : <@Main>, !felt.type
+      %lt = bool.cmp lt(%x, %y) : !felt.type, !felt.type
+      %quot = scf.if %lt -> (!felt.type) {
+        %q = felt.uintdiv %y, %x : !felt.type, !felt.type
+        scf.yield %q : !felt.type
+      } else {
+        %r = felt.smod %x, %y : !felt.type, !felt.type
+        scf.yield %r : !felt.type
+      }
+      struct.writem %self[@quot] = %quot : <@Main>, !felt.type
+      function.return %self : !struct.type<@Main>
+    }
+    function.def @constrain(%self: !struct.type<@Main>, %x: !felt.type {llzk.pub}, %y: !felt.type) {
+      function.return
+    }
+  }
+}